@@ -28,6 +28,20 @@ pub struct BacktestConfig {
     
     /// Slippage percentage as decimal (0.0005 = 0.05%)
     pub slippage_pct: f64,
+
+    /// Account leverage available to `BacktestEngine::calculate_order_size`
+    /// - notional up to `free_margin * leverage` (1.0 = spot, cash-only sizing).
+    pub leverage: f64,
+
+    /// Fraction of a position's notional that account equity (cash +
+    /// positions value) must stay above before `BacktestEngine` force-closes
+    /// it as a liquidation - see `BacktestEngine::check_liquidations`.
+    pub maintenance_margin_pct: f64,
+
+    /// Whether a `-1` signal may open a short from flat instead of only
+    /// closing an existing long - see `BacktestEngine::execute_signal`.
+    /// `false` keeps the original spot, long-only behavior.
+    pub allow_short: bool,
 }
 
 impl Default for BacktestConfig {
@@ -41,6 +55,9 @@ impl Default for BacktestConfig {
             maker_fee: 0.001,
             taker_fee: 0.001,
             slippage_pct: 0.0005,
+            leverage: 1.0,
+            maintenance_margin_pct: 0.05,
+            allow_short: false,
         }
     }
 }
@@ -68,6 +85,106 @@ pub struct BrokerageConfig {
     
     /// Maximum leverage allowed
     pub max_leverage: f64,
+
+    /// Leverage applied to new positions, used to derive the isolated-margin
+    /// liquidation price in `Brokerage::process_bar` (1.0 = no leverage).
+    pub leverage: f64,
+
+    /// Maintenance margin rate - the fraction of position value that must
+    /// remain as equity before forced liquidation kicks in.
+    pub maintenance_margin_rate: f64,
+
+    /// Maximum fraction of a bar's volume a single order may consume in that
+    /// bar (e.g. 0.1 = at most 10%). Orders larger than that fill in slices
+    /// across however many bars it takes - see `Brokerage::try_fill_order`.
+    pub max_participation_pct: f64,
+
+    /// Bid/ask spread in basis points around a bar's close, used to derive
+    /// `Brokerage::best_bid`/`best_ask` and the execution price for market
+    /// and triggered stop orders (10 = 0.1% wide, so 5bps off the mid on
+    /// either side).
+    pub spread_bps: f64,
+
+    /// Maximum number of resting limit orders `Brokerage::submit_order`
+    /// will accept at once.
+    pub max_limit_orders: usize,
+
+    /// Maximum number of resting stop/stop-limit orders `Brokerage::submit_order`
+    /// will accept at once.
+    pub max_stop_orders: usize,
+
+    /// How often an open perpetual position settles its funding payment,
+    /// in hours - see `Brokerage::settle_funding` (8.0 = the usual 3x/day
+    /// perp cadence).
+    pub funding_interval_hours: f64,
+
+    /// Where the rate applied at each funding boundary comes from - see
+    /// `FundingRateSource`.
+    pub funding_rate_source: FundingRateSource,
+
+    /// Which model `Brokerage::calculate_slippage` uses to turn a fill into
+    /// a slippage amount - see `SlippageModel`.
+    pub slippage_model: SlippageModel,
+}
+
+/// How `Brokerage::calculate_slippage` derives the slippage charged on a
+/// fill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SlippageModel {
+    /// `slippage_fixed` + `slippage_pct` of the fill's base price + a small
+    /// volume-participation term, all scaled by a random jitter factor -
+    /// the original behavior.
+    Fixed,
+
+    /// `slippage_pct` of the fill's base price only, with the same random jitter -
+    /// no fixed term, no volume-participation term.
+    Percentage,
+
+    /// Half of the Corwin-Schultz high-low spread estimate (Corwin &
+    /// Schultz, 2012) derived from this and the previous bar's high/low
+    /// range for the symbol being filled - see
+    /// `Brokerage::corwin_schultz_spread`. Deterministic: no random jitter.
+    CorwinSchultz,
+}
+
+impl Default for SlippageModel {
+    fn default() -> Self {
+        SlippageModel::Fixed
+    }
+}
+
+/// Source of the rate `Brokerage::settle_funding` applies at each funding
+/// boundary: either a flat rate for the whole backtest, or a schedule of
+/// rates keyed by RFC 3339 timestamp, the same lookup key
+/// `engine::BacktestEngine::run` uses for signals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FundingRateSource {
+    /// A single rate applied at every funding boundary, for every symbol.
+    Fixed(f64),
+
+    /// A per-timestamp rate; boundaries with no entry settle at a 0.0 rate.
+    Schedule(std::collections::HashMap<String, f64>),
+}
+
+impl FundingRateSource {
+    /// Rate in effect at `timestamp` - 0.0 if a `Schedule` has no entry
+    /// for it.
+    pub fn rate_at(&self, timestamp: chrono::DateTime<chrono::Utc>) -> f64 {
+        match self {
+            FundingRateSource::Fixed(rate) => *rate,
+            FundingRateSource::Schedule(schedule) => {
+                schedule.get(&timestamp.to_rfc3339()).copied().unwrap_or(0.0)
+            }
+        }
+    }
+}
+
+impl Default for FundingRateSource {
+    fn default() -> Self {
+        // 1bp per funding interval - a typical perp baseline absent a
+        // configured schedule.
+        FundingRateSource::Fixed(0.0001)
+    }
 }
 
 impl Default for BrokerageConfig {
@@ -80,6 +197,15 @@ impl Default for BrokerageConfig {
             realistic_fills: true,
             margin_requirement: 1.0,
             max_leverage: 1.0,
+            leverage: 1.0,
+            maintenance_margin_rate: 0.005,
+            max_participation_pct: 1.0,
+            spread_bps: 5.0,
+            max_limit_orders: 128,
+            max_stop_orders: 128,
+            funding_interval_hours: 8.0,
+            funding_rate_source: FundingRateSource::default(),
+            slippage_model: SlippageModel::default(),
         }
     }
 }
@@ -174,5 +300,25 @@ mod tests {
         assert_eq!(config.initial_capital, 100_000.0);
         assert_eq!(config.maker_fee, 0.001);
     }
+
+    #[test]
+    fn test_funding_rate_source_fixed_ignores_timestamp() {
+        let source = FundingRateSource::Fixed(0.0003);
+        assert_eq!(source.rate_at(chrono::Utc::now()), 0.0003);
+    }
+
+    #[test]
+    fn test_funding_rate_source_schedule_defaults_to_zero() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let mut schedule = std::collections::HashMap::new();
+        schedule.insert(timestamp.to_rfc3339(), 0.0005);
+        let source = FundingRateSource::Schedule(schedule);
+
+        assert_eq!(source.rate_at(timestamp), 0.0005);
+        assert_eq!(source.rate_at(timestamp + chrono::Duration::hours(8)), 0.0);
+    }
 }
 
@@ -6,7 +6,13 @@
 //! - In-memory data
 
 use crate::events::{Bar, EventId, MarketDataEvent};
+use arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, TimeUnit};
+use arrow::record_batch::RecordBatch;
 use chrono::{DateTime, NaiveDateTime, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::file::reader::ChunkReader;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -28,6 +34,25 @@ pub enum DataFeedError {
     Parquet(String),
 }
 
+/// One `(symbol, start, end, limit)` selector for `DataFeed::range_batch`.
+#[derive(Debug, Clone)]
+pub struct RangeQuery {
+    pub symbol: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub limit: usize,
+}
+
+/// One page of a `range`/`range_batch` query: the matching bars, plus a
+/// cursor (one nanosecond past the last returned bar's timestamp) to pass
+/// as the next `start` to keep paging forward. `None` once the window has
+/// been fully returned.
+#[derive(Debug)]
+pub struct RangePage<'a> {
+    pub bars: &'a [Bar],
+    pub next_cursor: Option<DateTime<Utc>>,
+}
+
 /// Data feed that streams OHLCV bars
 pub struct DataFeed {
     /// Loaded data by symbol
@@ -53,18 +78,71 @@ impl DataFeed {
         }
     }
 
-    /// Load data from a Parquet file
+    /// Load data from a Parquet file, mapping the `timestamp,open,high,low,
+    /// close,volume` columns into `Bar` values.
     pub fn load_parquet(&mut self, path: &str, symbol: &str) -> Result<(), DataFeedError> {
-        // For now, we'll implement a simple Parquet reader
-        // In production, use arrow-rs Parquet reader
         let file = File::open(path)?;
-        
-        // Placeholder: In a real implementation, use parquet crate
-        // For now, fall back to CSV-like parsing
-        log::warn!("Parquet loading not fully implemented, using CSV fallback");
-        
-        // Try loading as CSV
-        self.load_csv(path, symbol)
+        self.load_parquet_reader(file, symbol)
+    }
+
+    /// Fetch a Parquet or CSV object from an S3-compatible HTTP endpoint
+    /// (the same GET-object semantics a Garage/S3 server exposes) and load
+    /// it the same way as `load_parquet`/`load_csv`. Lets large historical
+    /// OHLCV datasets live in bucket storage instead of local disk.
+    pub fn load_from_object_store(
+        &mut self,
+        endpoint: &str,
+        bucket: &str,
+        key: &str,
+        symbol: &str,
+    ) -> Result<(), DataFeedError> {
+        let url = format!("{}/{}/{}", endpoint.trim_end_matches('/'), bucket, key);
+
+        let response = reqwest::blocking::get(&url)
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| DataFeedError::Parse(format!("object store GET {} failed: {}", url, e)))?;
+
+        let bytes = response
+            .bytes()
+            .map_err(|e| DataFeedError::Parse(format!("failed to read object body: {}", e)))?;
+
+        if key.ends_with(".parquet") {
+            self.load_parquet_reader(bytes, symbol)
+        } else {
+            let mut bars = self.parse_csv_reader(bytes.as_ref())?;
+            bars.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+            self.data.insert(symbol.to_string(), bars);
+            self.indices.insert(symbol.to_string(), 0);
+            Ok(())
+        }
+    }
+
+    /// Read every record batch out of a Parquet source and store the
+    /// resulting bars for `symbol`. Shared by `load_parquet` (local file)
+    /// and `load_from_object_store` (in-memory bytes) since both `File`
+    /// and `bytes::Bytes` implement `ChunkReader`.
+    fn load_parquet_reader<R: ChunkReader + 'static>(
+        &mut self,
+        reader: R,
+        symbol: &str,
+    ) -> Result<(), DataFeedError> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(reader)
+            .map_err(|e| DataFeedError::Parquet(e.to_string()))?;
+        let arrow_reader = builder
+            .build()
+            .map_err(|e| DataFeedError::Parquet(e.to_string()))?;
+
+        let mut bars = Vec::new();
+        for batch in arrow_reader {
+            let batch = batch.map_err(|e| DataFeedError::Parquet(e.to_string()))?;
+            bars.extend(bars_from_record_batch(&batch)?);
+        }
+
+        bars.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        self.data.insert(symbol.to_string(), bars);
+        self.indices.insert(symbol.to_string(), 0);
+
+        Ok(())
     }
 
     /// Load data from a CSV file
@@ -72,10 +150,24 @@ impl DataFeed {
     pub fn load_csv(&mut self, path: &str, symbol: &str) -> Result<(), DataFeedError> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
-        
+
+        let mut bars = self.parse_csv_reader(reader)?;
+
+        // Sort by timestamp
+        bars.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        self.data.insert(symbol.to_string(), bars);
+        self.indices.insert(symbol.to_string(), 0);
+
+        Ok(())
+    }
+
+    /// Parse OHLCV rows out of any buffered reader, skipping a header line
+    /// if present. Shared by `load_csv` and `load_from_object_store`.
+    fn parse_csv_reader<R: BufRead>(&self, reader: R) -> Result<Vec<Bar>, DataFeedError> {
         let mut bars = Vec::new();
         let mut lines = reader.lines();
-        
+
         // Skip header if present
         if let Some(Ok(first_line)) = lines.next() {
             if !first_line.starts_with(|c: char| c.is_ascii_digit()) {
@@ -87,21 +179,15 @@ impl DataFeed {
                 }
             }
         }
-        
+
         for line in lines {
             let line = line?;
             if let Some(bar) = self.parse_csv_line(&line)? {
                 bars.push(bar);
             }
         }
-        
-        // Sort by timestamp
-        bars.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-        
-        self.data.insert(symbol.to_string(), bars);
-        self.indices.insert(symbol.to_string(), 0);
-        
-        Ok(())
+
+        Ok(bars)
     }
 
     fn parse_csv_line(&self, line: &str) -> Result<Option<Bar>, DataFeedError> {
@@ -183,6 +269,50 @@ impl DataFeed {
         })
     }
 
+    /// Binary-search a symbol's sorted bars for the `[start, end]` window and
+    /// return at most `limit` of them, plus a cursor to pass as `start` on
+    /// the next call to page forward. This is the windowed-read counterpart
+    /// to `get_aligned_bars`'s full-history scan - `O(log n)` to find the
+    /// bounds instead of scanning every bar for every query.
+    pub fn range(&self, symbol: &str, start: DateTime<Utc>, end: DateTime<Utc>, limit: usize) -> RangePage<'_> {
+        let bars = match self.data.get(symbol) {
+            Some(bars) => bars,
+            None => return RangePage { bars: &[], next_cursor: None },
+        };
+
+        let lo = bars.partition_point(|b| b.timestamp < start);
+        let hi = bars.partition_point(|b| b.timestamp <= end);
+        let window = &bars[lo..hi];
+
+        if window.len() <= limit {
+            return RangePage { bars: window, next_cursor: None };
+        }
+
+        let page = &window[..limit];
+        RangePage {
+            bars: page,
+            // `start` is inclusive, so step past the last returned bar to
+            // keep it off the next page.
+            next_cursor: page.last().map(|bar| bar.timestamp + chrono::Duration::nanoseconds(1)),
+        }
+    }
+
+    /// Run several `RangeQuery` selectors against this feed in one call,
+    /// keyed by symbol - the in-process equivalent of a batch/range read API
+    /// over `DataFeed`, for callers that want bars for many symbols without
+    /// round-tripping through `range` once per symbol.
+    pub fn range_batch<'a>(&'a self, queries: &[RangeQuery]) -> HashMap<String, RangePage<'a>> {
+        queries
+            .iter()
+            .map(|query| {
+                (
+                    query.symbol.clone(),
+                    self.range(&query.symbol, query.start, query.end, query.limit),
+                )
+            })
+            .collect()
+    }
+
     /// Get all bars aligned by timestamp across all symbols
     pub fn get_aligned_bars(&mut self) -> Vec<MarketDataEvent> {
         let mut events = Vec::new();
@@ -266,6 +396,102 @@ impl Default for DataFeed {
     }
 }
 
+/// Map one Arrow record batch's `timestamp,open,high,low,close,volume`
+/// columns into `Bar` values.
+fn bars_from_record_batch(batch: &RecordBatch) -> Result<Vec<Bar>, DataFeedError> {
+    let schema = batch.schema();
+    let column = |name: &str| -> Result<ArrayRef, DataFeedError> {
+        let index = schema
+            .index_of(name)
+            .map_err(|_| DataFeedError::Parquet(format!("missing column: {}", name)))?;
+        Ok(batch.column(index).clone())
+    };
+
+    let timestamps = timestamps_from_column(&column("timestamp")?)?;
+    let open = as_f64_array(&column("open")?)?;
+    let high = as_f64_array(&column("high")?)?;
+    let low = as_f64_array(&column("low")?)?;
+    let close = as_f64_array(&column("close")?)?;
+    let volume = as_f64_array(&column("volume")?)?;
+
+    let mut bars = Vec::with_capacity(batch.num_rows());
+    for row in 0..batch.num_rows() {
+        bars.push(Bar::new(
+            timestamps[row],
+            open.value(row),
+            high.value(row),
+            low.value(row),
+            close.value(row),
+            volume.value(row),
+        ));
+    }
+
+    Ok(bars)
+}
+
+/// Cast a numeric column to `f64`, accepting any integer/float Arrow type.
+fn as_f64_array(array: &ArrayRef) -> Result<Float64Array, DataFeedError> {
+    let casted = cast(array, &DataType::Float64)
+        .map_err(|e| DataFeedError::Parquet(format!("numeric column cast failed: {}", e)))?;
+    casted
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .cloned()
+        .ok_or_else(|| DataFeedError::Parquet("expected a numeric column".to_string()))
+}
+
+/// Decode a timestamp column that is either a raw `i64` epoch (seconds or
+/// milliseconds, same heuristic as `DataFeed::parse_timestamp`) or an
+/// Arrow `Timestamp` logical type in any time unit.
+fn timestamps_from_column(array: &ArrayRef) -> Result<Vec<DateTime<Utc>>, DataFeedError> {
+    match array.data_type().clone() {
+        DataType::Int64 => {
+            let values = array
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .ok_or_else(|| DataFeedError::Parquet("expected an Int64 timestamp column".to_string()))?;
+
+            values
+                .iter()
+                .map(|v| {
+                    let ts = v.ok_or_else(|| DataFeedError::Parquet("null timestamp".to_string()))?;
+                    let secs = if ts > 10_000_000_000 { ts / 1000 } else { ts };
+                    DateTime::from_timestamp(secs, 0)
+                        .ok_or_else(|| DataFeedError::Parquet("invalid epoch timestamp".to_string()))
+                })
+                .collect()
+        }
+        DataType::Timestamp(unit, _) => {
+            let raw = cast(array, &DataType::Int64)
+                .map_err(|e| DataFeedError::Parquet(format!("timestamp cast failed: {}", e)))?;
+            let raw = raw
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .ok_or_else(|| DataFeedError::Parquet("expected an Int64-castable timestamp column".to_string()))?;
+
+            raw.iter()
+                .map(|v| {
+                    let v = v.ok_or_else(|| DataFeedError::Parquet("null timestamp".to_string()))?;
+                    let dt = match unit {
+                        TimeUnit::Second => DateTime::from_timestamp(v, 0),
+                        TimeUnit::Millisecond => {
+                            DateTime::from_timestamp(v / 1_000, ((v % 1_000) * 1_000_000) as u32)
+                        }
+                        TimeUnit::Microsecond => {
+                            DateTime::from_timestamp(v / 1_000_000, ((v % 1_000_000) * 1_000) as u32)
+                        }
+                        TimeUnit::Nanosecond => {
+                            DateTime::from_timestamp(v / 1_000_000_000, (v % 1_000_000_000) as u32)
+                        }
+                    };
+                    dt.ok_or_else(|| DataFeedError::Parquet("invalid timestamp".to_string()))
+                })
+                .collect()
+        }
+        other => Err(DataFeedError::Parquet(format!("unsupported timestamp column type: {:?}", other))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,5 +526,80 @@ mod tests {
         let event = feed.next_bar("BTC/USD");
         assert!(event.is_none());
     }
+
+    #[test]
+    fn test_range_pages_forward_via_cursor() {
+        let mut feed = DataFeed::new();
+        let base = Utc::now();
+        let bars: Vec<Bar> = (0..5)
+            .map(|i| Bar::new(base + chrono::Duration::minutes(i), 100.0, 105.0, 95.0, 102.0, 1000.0))
+            .collect();
+        feed.load_bars("BTC/USD", bars.clone());
+
+        let first = feed.range("BTC/USD", bars[0].timestamp, bars[4].timestamp, 2);
+        assert_eq!(first.bars.len(), 2);
+        assert_eq!(first.bars[0].timestamp, bars[0].timestamp);
+        let cursor = first.next_cursor.expect("more bars remain");
+
+        let second = feed.range("BTC/USD", cursor, bars[4].timestamp, 2);
+        assert_eq!(second.bars.len(), 2);
+        assert_eq!(second.bars[0].timestamp, bars[2].timestamp);
+
+        let last = feed.range("BTC/USD", second.next_cursor.unwrap(), bars[4].timestamp, 2);
+        assert_eq!(last.bars.len(), 1);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_range_batch_keys_results_by_symbol() {
+        let mut feed = DataFeed::new();
+        let now = Utc::now();
+        feed.load_bars("BTC/USD", vec![Bar::new(now, 100.0, 105.0, 95.0, 102.0, 1000.0)]);
+        feed.load_bars("ETH/USD", vec![Bar::new(now, 10.0, 11.0, 9.0, 10.5, 500.0)]);
+
+        let results = feed.range_batch(&[
+            RangeQuery { symbol: "BTC/USD".to_string(), start: now, end: now, limit: 10 },
+            RangeQuery { symbol: "ETH/USD".to_string(), start: now, end: now, limit: 10 },
+            RangeQuery { symbol: "SOL/USD".to_string(), start: now, end: now, limit: 10 },
+        ]);
+
+        assert_eq!(results["BTC/USD"].bars.len(), 1);
+        assert_eq!(results["ETH/USD"].bars.len(), 1);
+        assert!(results["SOL/USD"].bars.is_empty());
+    }
+
+    #[test]
+    fn test_bars_from_record_batch_decodes_millisecond_epoch_timestamps() {
+        use arrow::array::{Float64Array, Int64Array};
+        use arrow::datatypes::{Field, Schema};
+        use std::sync::Arc;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("timestamp", DataType::Int64, false),
+            Field::new("open", DataType::Float64, false),
+            Field::new("high", DataType::Float64, false),
+            Field::new("low", DataType::Float64, false),
+            Field::new("close", DataType::Float64, false),
+            Field::new("volume", DataType::Float64, false),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1_700_000_000_000i64])),
+                Arc::new(Float64Array::from(vec![100.0])),
+                Arc::new(Float64Array::from(vec![105.0])),
+                Arc::new(Float64Array::from(vec![95.0])),
+                Arc::new(Float64Array::from(vec![102.0])),
+                Arc::new(Float64Array::from(vec![1000.0])),
+            ],
+        )
+        .expect("valid record batch");
+
+        let bars = bars_from_record_batch(&batch).expect("parses successfully");
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].close, 102.0);
+        assert_eq!(bars[0].timestamp.timestamp_millis(), 1_700_000_000_000);
+    }
 }
 
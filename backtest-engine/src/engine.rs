@@ -6,10 +6,10 @@
 use crate::brokerage::Brokerage;
 use crate::config::{BacktestConfig, BrokerageConfig};
 use crate::datafeed::DataFeed;
-use crate::events::{Bar, Event, FillEvent, MarketDataEvent, OrderEvent, Side, EventId};
+use crate::events::{Bar, Event, FillEvent, FundingEvent, MarketDataEvent, OrderEvent, OrderType, Side, TimeInForce, EventId};
 use crate::metrics::{BacktestMetrics, MetricsCalculator};
 use crate::order_manager::OrderManager;
-use crate::portfolio::Portfolio;
+use crate::portfolio::{Portfolio, Position};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use std::collections::{BinaryHeap, HashMap};
 use std::cmp::Reverse;
@@ -104,6 +104,16 @@ impl BacktestEngine {
         Ok(())
     }
 
+    /// Load pre-sliced in-memory bars for `symbol`, bypassing `load_data`'s
+    /// file parsing - used by `optimizer::Optimizer` to feed each
+    /// walk-forward fold's train/test window into a fresh engine.
+    pub fn load_bars(&mut self, symbol: &str, bars: Vec<Bar>) {
+        self.data_feed.load_bars(symbol, bars);
+        self.total_bars = self.config.symbols.iter()
+            .map(|s| self.data_feed.len(s))
+            .sum();
+    }
+
     /// Load data from JSON string
     fn load_json_data(&mut self, data: &str) -> Result<(), EngineError> {
         // Parse as array of OHLCV data
@@ -154,144 +164,370 @@ impl BacktestEngine {
 
     /// Run backtest with pre-computed signals
     /// signals: Vec of (timestamp, signal) where signal is -1, 0, or 1
+    ///
+    /// A thin adapter over `run_strategy` - it wraps `signals` in a
+    /// `SignalStrategy` so the precomputed-signal path and the
+    /// `Strategy`-driven path share one event loop.
     pub fn run(&mut self, signals: Vec<(String, i32)>) -> Result<BacktestMetrics, EngineError> {
+        let mut strategy = SignalStrategy {
+            signals: signals.into_iter().collect(),
+        };
+        self.run_strategy(&mut strategy)
+    }
+
+    /// Run backtest driving a `Strategy` callback instead of a precomputed
+    /// signal map - `strategy.on_bar` sees the current bar, the portfolio,
+    /// and open orders, and can submit orders through `StrategyContext`,
+    /// so it can react to its own fills and equity rather than having
+    /// every decision baked in up front.
+    pub fn run_strategy<S: Strategy>(&mut self, strategy: &mut S) -> Result<BacktestMetrics, EngineError> {
         self.running = true;
         self.bars_processed = 0;
-        
-        // Convert signals to a map
-        let signal_map: HashMap<String, i32> = signals.into_iter().collect();
-        
+
         // Get all bars aligned by timestamp
         let market_events = self.data_feed.get_aligned_bars();
-        
+
         // Process each bar
         for event in market_events {
-            self.process_market_data(&event, &signal_map)?;
+            self.process_market_data(&event, strategy)?;
             self.bars_processed += 1;
         }
-        
+
         // Close any remaining positions
         self.close_all_positions()?;
-        
+
         self.running = false;
-        
+
         // Calculate metrics
         let calculator = MetricsCalculator::new(0.02, &self.config.timeframe);
         let trade_stats = self.portfolio.trade_stats();
         let metrics = calculator.calculate(self.portfolio.equity_curve(), trade_stats);
-        
+
         Ok(metrics)
     }
 
-    /// Process a single market data event
-    fn process_market_data(
+    /// Process a single market data event: the fills/funding/liquidation
+    /// bookkeeping every bar needs regardless of how orders get decided,
+    /// then hand control to `strategy.on_bar` to decide this bar's orders.
+    fn process_market_data<S: Strategy>(
         &mut self,
         event: &MarketDataEvent,
-        signals: &HashMap<String, i32>,
+        strategy: &mut S,
     ) -> Result<(), EngineError> {
-        let symbol = &event.symbol;
-        let bar = &event.bar;
-        
+        let symbol = event.symbol.clone();
+        let bar = event.bar.clone();
+
         // Update current price
         self.current_prices.insert(symbol.clone(), bar.close);
         self.current_time = Some(bar.timestamp);
-        
-        // Process pending orders
-        let fills = self.brokerage.process_bar(bar, symbol);
+
+        // Mark positions to the new bar before the solvency check below so
+        // it sees this bar's unrealized PnL, not the previous one's.
+        self.portfolio.update_market_values(&self.current_prices);
+        self.check_liquidations(&bar, &symbol)?;
+
+        // Process pending orders. Time-in-force cancellations (expired GTD
+        // deadlines, unfilled IOC/FOK remainders) don't touch the portfolio,
+        // so only the fills and funding settlements need forwarding here.
+        let (fills, _cancelled, funding) = self.brokerage.process_bar(&bar, &symbol);
         for fill in fills {
             self.process_fill(fill)?;
         }
-        
-        // Get signal for this timestamp
-        let timestamp_key = bar.timestamp.to_rfc3339();
-        if let Some(&signal) = signals.get(&timestamp_key) {
-            self.execute_signal(symbol, signal, bar)?;
+        for settlement in funding {
+            self.process_funding(settlement)?;
         }
-        
+
+        // Sweep `order_manager`'s own `GTD` orders before they get a chance
+        // to trade this bar - mirrors `Brokerage::sweep_expired_orders` for
+        // the orders that go through `OrderManager` instead.
+        self.order_manager.expire_orders(bar.timestamp);
+
+        // Walk resting limit/stop orders before the strategy runs, so a
+        // passive order placed on an earlier bar gets a chance at this
+        // bar's OHLC range instead of sitting dead in `order_manager`.
+        self.execute_resting_orders(&bar, &symbol)?;
+
+        // Let the strategy react to this bar
+        let mut ctx = StrategyContext {
+            engine: self,
+            bar: &bar,
+            symbol: &symbol,
+        };
+        strategy.on_bar(&mut ctx);
+
         // Update portfolio market values
         self.portfolio.update_market_values(&self.current_prices);
-        
+
         // Record equity point
         self.portfolio.record_equity(bar.timestamp);
-        
+
         Ok(())
     }
 
     /// Execute a trading signal
     fn execute_signal(&mut self, symbol: &str, signal: i32, bar: &Bar) -> Result<(), EngineError> {
         let position = self.portfolio.get_position(symbol);
-        let current_qty = position.map(|p| p.quantity).unwrap_or(0.0);
-        
+        let current_qty = position.map(|p| p.quantity.to_f64()).unwrap_or(0.0);
+
         match signal {
             1 => {
-                // Buy signal
-                if current_qty <= 0.0 {
-                    // Close short or open long
-                    let order_qty = self.calculate_order_size(symbol, bar.close);
-                    if order_qty > 0.0 {
-                        let order = self.order_manager.create_market_order(
-                            bar.timestamp,
-                            symbol,
-                            Side::Buy,
-                            order_qty,
-                        );
-                        
-                        // Execute immediately for market order
-                        if let Some(fill) = self.brokerage.execute_market_order(&order, bar) {
-                            self.process_fill(fill)?;
-                            self.order_manager.mark_filled(order.id);
-                        }
-                    }
+                // Buy signal: cover a short first, or open long from flat.
+                // Covering only reduces exposure, so it skips the margin
+                // check that gates opening a fresh position.
+                if current_qty < 0.0 {
+                    self.submit_reducing_order(symbol, Side::Buy, current_qty.abs(), bar)?;
+                } else if current_qty == 0.0 {
+                    self.open_position(symbol, Side::Buy, bar)?;
                 }
             }
             -1 => {
-                // Sell signal
+                // Sell signal: close a long, or - if `allow_short` is set -
+                // open a short from flat.
                 if current_qty > 0.0 {
-                    // Close long position
-                    let order = self.order_manager.create_market_order(
-                        bar.timestamp,
-                        symbol,
-                        Side::Sell,
-                        current_qty,
-                    );
-                    
-                    if let Some(fill) = self.brokerage.execute_market_order(&order, bar) {
-                        self.process_fill(fill)?;
-                        self.order_manager.mark_filled(order.id);
-                    }
+                    self.submit_reducing_order(symbol, Side::Sell, current_qty, bar)?;
+                } else if current_qty == 0.0 && self.config.allow_short {
+                    self.open_position(symbol, Side::Sell, bar)?;
                 }
             }
             _ => {
                 // Hold - do nothing
             }
         }
-        
+
         Ok(())
     }
 
-    /// Calculate order size based on available capital
-    fn calculate_order_size(&self, symbol: &str, price: f64) -> f64 {
-        let available = self.portfolio.cash();
-        let position_size = available * 0.95;  // Use 95% of available cash
-        
+    /// Open a new position from flat, sized by `calculate_order_size` and
+    /// gated by free margin - rejected via `OrderManager::mark_rejected`
+    /// when the position would need more margin than is free.
+    fn open_position(&mut self, symbol: &str, side: Side, bar: &Bar) -> Result<(), EngineError> {
+        let order_qty = self.calculate_order_size(symbol, bar.close);
+        if order_qty <= 0.0 {
+            return Ok(());
+        }
+
+        let order = self.order_manager.create_market_order(bar.timestamp, symbol, side, order_qty);
+
+        let margin_needed = self.required_margin(order_qty, bar.close);
+        if margin_needed > self.portfolio.free_margin(self.config.leverage) {
+            self.order_manager.mark_rejected(order.id);
+            return Ok(());
+        }
+
+        if let Some((fill, _fully_filled)) = self.brokerage.execute_market_order(&order, bar) {
+            let fill_quantity = fill.quantity;
+            self.process_fill(fill)?;
+            self.finalize_market_order(order.id, fill_quantity);
+        }
+
+        Ok(())
+    }
+
+    /// Submit and immediately execute a market order that only reduces
+    /// existing exposure (covering a short or closing a long). This frees
+    /// margin rather than consuming it, so it never needs the margin check
+    /// `open_position` applies.
+    fn submit_reducing_order(&mut self, symbol: &str, side: Side, quantity: f64, bar: &Bar) -> Result<(), EngineError> {
+        let order = self.order_manager.create_market_order(bar.timestamp, symbol, side, quantity);
+
+        if let Some((fill, _fully_filled)) = self.brokerage.execute_market_order(&order, bar) {
+            let fill_quantity = fill.quantity;
+            self.process_fill(fill)?;
+            self.finalize_market_order(order.id, fill_quantity);
+        }
+
+        Ok(())
+    }
+
+    /// Calculate order size based on available capital. At `leverage` 1.0
+    /// this is the old spot, cash-only sizing; above that it allows
+    /// notional up to `free_margin * leverage`.
+    fn calculate_order_size(&self, _symbol: &str, price: f64) -> f64 {
+        let available = self.portfolio.free_margin(self.config.leverage) * self.config.leverage;
+        let position_size = available * 0.95;  // Use 95% of available margin
+
         // Account for fees
         let fee_adjusted = position_size / (1.0 + self.config.taker_fee + self.config.slippage_pct);
-        
+
         fee_adjusted / price
     }
 
-    /// Process a fill event
+    /// Margin a new order of `quantity` at `price` would tie up.
+    fn required_margin(&self, quantity: f64, price: f64) -> f64 {
+        (quantity.abs() * price) / self.config.leverage.max(1.0)
+    }
+
+    /// Force-close `symbol`'s position at the bar's close once account
+    /// equity (cash + positions value) drops below `maintenance_margin_pct`
+    /// of that position's notional - a liquidation, not a signal-driven
+    /// exit, so it's filled at market regardless of what the signal says.
+    fn check_liquidations(&mut self, bar: &Bar, symbol: &str) -> Result<(), EngineError> {
+        let quantity = match self.portfolio.get_position(symbol) {
+            Some(position) if !position.is_flat() => position.quantity.to_f64(),
+            _ => return Ok(()),
+        };
+
+        let notional = quantity.abs() * bar.close;
+        let maintenance = self.config.maintenance_margin_pct * notional;
+
+        if self.portfolio.total_equity() >= maintenance {
+            return Ok(());
+        }
+
+        let side = if quantity > 0.0 { Side::Sell } else { Side::Buy };
+        let order = self.order_manager.create_market_order(
+            bar.timestamp,
+            symbol,
+            side,
+            quantity.abs(),
+        );
+
+        if let Some((fill, _fully_filled)) = self.brokerage.execute_market_order(&order, bar) {
+            let fill_quantity = fill.quantity;
+            self.process_fill(fill)?;
+            self.finalize_market_order(order.id, fill_quantity);
+        }
+
+        Ok(())
+    }
+
+    /// Process a fill event. Above 1x leverage, the resulting position is
+    /// tagged as a perpetual so the portfolio reports margin-based P&L and a
+    /// liquidation price instead of spot cost-basis accounting - mirrors the
+    /// same `required_margin`/`config.leverage` the pre-trade margin check
+    /// above already uses.
     fn process_fill(&mut self, fill: FillEvent) -> Result<(), EngineError> {
         self.portfolio.process_fill(&fill);
+        if self.config.leverage > 1.0 {
+            let margin = self.required_margin(fill.quantity, fill.fill_price);
+            self.portfolio.set_perp_leverage(
+                &fill.symbol,
+                margin,
+                self.config.leverage,
+                self.config.maintenance_margin_pct,
+            );
+        }
+        Ok(())
+    }
+
+    /// Apply a market order's fill and clean up its resting state. A market
+    /// order is never meant to rest - it trades now or not at all - so any
+    /// shortfall against `Brokerage::execute_market_order`'s volume cap
+    /// cancels the remainder instead of leaving it open for an unrelated
+    /// future bar to pick up.
+    fn finalize_market_order(&mut self, order_id: EventId, fill_quantity: f64) {
+        self.order_manager.record_fill(order_id, fill_quantity);
+        if self.order_manager.remaining_quantity(order_id) > 1e-9 {
+            self.order_manager.mark_cancelled(order_id);
+        }
+    }
+
+    /// Cancel `order_id`'s resting remainder if its time-in-force is `IOC`
+    /// or `FOK` and it didn't fully fill on this sweep - `FOK` on any
+    /// shortfall, `IOC` keeping whatever already filled. A no-op for
+    /// `GTC`/`GTD` orders, which are allowed to keep resting.
+    fn enforce_immediate_tif(&mut self, order_id: EventId, time_in_force: TimeInForce) {
+        if matches!(time_in_force, TimeInForce::IOC | TimeInForce::FOK)
+            && self.order_manager.remaining_quantity(order_id) > 1e-9
+        {
+            self.order_manager.mark_cancelled(order_id);
+        }
+    }
+
+    /// Apply a funding settlement's cash flow to the portfolio
+    fn process_funding(&mut self, funding: FundingEvent) -> Result<(), EngineError> {
+        self.portfolio.process_funding(&funding);
         Ok(())
     }
 
+    /// Walk `symbol`'s resting limit/stop orders and fill any the bar's OHLC
+    /// range touches: a buy-limit fills when `bar.low <= limit_price`, a
+    /// sell-limit when `bar.high >= limit_price`, each at the better of the
+    /// limit and the bar's open; a stop triggers on the same touch test
+    /// against `stop_price` and then executes as a market order.
+    fn execute_resting_orders(&mut self, bar: &Bar, symbol: &str) -> Result<(), EngineError> {
+        let orders: Vec<OrderEvent> = self.order_manager
+            .open_orders_for_symbol(symbol)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        for order in orders {
+            let fill = match order.order_type {
+                OrderType::Limit => {
+                    let limit_price = match order.limit_price {
+                        Some(price) => price,
+                        None => continue,
+                    };
+                    match order.side {
+                        Side::Buy if bar.low <= limit_price => {
+                            Some((self.build_resting_fill(&order, bar, limit_price.min(bar.open)), true))
+                        }
+                        Side::Sell if bar.high >= limit_price => {
+                            Some((self.build_resting_fill(&order, bar, limit_price.max(bar.open)), true))
+                        }
+                        _ => None,
+                    }
+                }
+                OrderType::Stop => {
+                    let stop_price = match order.stop_price {
+                        Some(price) => price,
+                        None => continue,
+                    };
+                    let triggered = match order.side {
+                        Side::Buy => bar.high >= stop_price,
+                        Side::Sell => bar.low <= stop_price,
+                    };
+
+                    if triggered {
+                        // `execute_market_order` only fills `Market` orders.
+                        let mut market_order = order.clone();
+                        market_order.order_type = OrderType::Market;
+                        self.brokerage.execute_market_order(&market_order, bar)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+
+            if let Some((fill, _fully_filled)) = fill {
+                let fill_quantity = fill.quantity;
+                self.process_fill(fill)?;
+                self.order_manager.record_fill(order.id, fill_quantity);
+            }
+
+            self.enforce_immediate_tif(order.id, order.time_in_force);
+        }
+
+        Ok(())
+    }
+
+    /// Build the fill for a resting limit order touched this bar. It pays
+    /// the maker fee, not the taker fee charged to market/stop fills - it
+    /// rested in the book rather than crossing the spread - and no
+    /// slippage, since the limit price is already the worst acceptable one.
+    fn build_resting_fill(&self, order: &OrderEvent, bar: &Bar, fill_price: f64) -> FillEvent {
+        let commission = order.quantity * fill_price * self.config.maker_fee;
+        FillEvent {
+            id: order.id,
+            order_id: order.id,
+            timestamp: bar.timestamp,
+            symbol: order.symbol.clone(),
+            side: order.side,
+            quantity: order.quantity,
+            fill_price,
+            commission,
+            slippage: 0.0,
+        }
+    }
+
     /// Close all open positions
     fn close_all_positions(&mut self) -> Result<(), EngineError> {
         let positions: Vec<(String, f64)> = self.portfolio.positions()
             .iter()
             .filter(|(_, p)| !p.is_flat())
-            .map(|(s, p)| (s.clone(), p.quantity))
+            .map(|(s, p)| (s.clone(), p.quantity.to_f64()))
             .collect();
         
         for (symbol, qty) in positions {
@@ -307,13 +543,15 @@ impl BacktestEngine {
                         qty,
                     );
                     
-                    if let Some(fill) = self.brokerage.execute_market_order(&order, &bar) {
+                    if let Some((fill, _fully_filled)) = self.brokerage.execute_market_order(&order, &bar) {
+                        let fill_quantity = fill.quantity;
                         self.process_fill(fill)?;
+                        self.finalize_market_order(order.id, fill_quantity);
                     }
                 }
             }
         }
-        
+
         Ok(())
     }
 
@@ -339,7 +577,7 @@ impl BacktestEngine {
     pub fn equity_curve(&self) -> Vec<f64> {
         self.portfolio.equity_curve()
             .iter()
-            .map(|e| e.equity)
+            .map(|e| e.equity.to_f64())
             .collect()
     }
 
@@ -362,6 +600,142 @@ impl BacktestEngine {
     }
 }
 
+/// A trading strategy driven bar-by-bar by `BacktestEngine::run_strategy`.
+/// Unlike the precomputed `run(signals)` path, `on_bar` sees the engine's
+/// live state through `ctx` - positions, open orders, fills already
+/// applied this bar - so it can scale in/out, manage its own stops, or
+/// react to a partial fill instead of having every decision baked in
+/// ahead of time.
+pub trait Strategy {
+    fn on_bar(&mut self, ctx: &mut StrategyContext);
+}
+
+/// What a `Strategy` sees and can act on for one bar of one symbol.
+/// Borrows the engine mutably for the duration of `on_bar`, so order
+/// submission takes effect immediately - a market order fills before
+/// `on_bar` returns, the same as `BacktestEngine::execute_signal`.
+pub struct StrategyContext<'a> {
+    engine: &'a mut BacktestEngine,
+    bar: &'a Bar,
+    symbol: &'a str,
+}
+
+impl<'a> StrategyContext<'a> {
+    /// The bar currently being processed
+    pub fn bar(&self) -> &Bar {
+        self.bar
+    }
+
+    /// The symbol the current bar belongs to
+    pub fn symbol(&self) -> &str {
+        self.symbol
+    }
+
+    /// This symbol's open position, if any
+    pub fn position(&self) -> Option<&Position> {
+        self.engine.portfolio.get_position(self.symbol)
+    }
+
+    /// Available cash
+    pub fn cash(&self) -> f64 {
+        self.engine.portfolio.cash()
+    }
+
+    /// Account equity (cash + positions value)
+    pub fn equity(&self) -> f64 {
+        self.engine.portfolio.total_equity()
+    }
+
+    /// This symbol's resting limit/stop orders
+    pub fn open_orders(&self) -> Vec<&OrderEvent> {
+        self.engine.order_manager.open_orders_for_symbol(self.symbol)
+    }
+
+    /// Submit a market order and execute it immediately against the
+    /// current bar, routing the fill through the portfolio.
+    pub fn submit_market_order(&mut self, side: Side, quantity: f64) -> Result<(), EngineError> {
+        let order = self.engine.order_manager.create_market_order(
+            self.bar.timestamp,
+            self.symbol,
+            side,
+            quantity,
+        );
+
+        if let Some((fill, _fully_filled)) = self.engine.brokerage.execute_market_order(&order, self.bar) {
+            let fill_quantity = fill.quantity;
+            self.engine.process_fill(fill)?;
+            self.engine.finalize_market_order(order.id, fill_quantity);
+        }
+
+        Ok(())
+    }
+
+    /// Submit a resting limit order, returning its ID so the strategy can
+    /// track or cancel it later. It's picked up by
+    /// `BacktestEngine::execute_resting_orders` on a later bar.
+    pub fn submit_limit_order(&mut self, side: Side, quantity: f64, limit_price: f64) -> EventId {
+        self.engine
+            .order_manager
+            .create_limit_order(self.bar.timestamp, self.symbol, side, quantity, limit_price)
+            .id
+    }
+
+    /// Submit a resting limit order with an explicit time-in-force - see
+    /// `OrderManager::create_limit_order_with_tif`.
+    pub fn submit_limit_order_with_tif(
+        &mut self,
+        side: Side,
+        quantity: f64,
+        limit_price: f64,
+        time_in_force: TimeInForce,
+    ) -> EventId {
+        self.engine
+            .order_manager
+            .create_limit_order_with_tif(self.bar.timestamp, self.symbol, side, quantity, limit_price, time_in_force)
+            .id
+    }
+
+    /// Submit a resting stop order, returning its ID - triggers and
+    /// executes as a market order once the bar's range touches `stop_price`.
+    pub fn submit_stop_order(&mut self, side: Side, quantity: f64, stop_price: f64) -> EventId {
+        self.engine
+            .order_manager
+            .create_stop_order(self.bar.timestamp, self.symbol, side, quantity, stop_price)
+            .id
+    }
+
+    /// Submit a resting stop order with an explicit time-in-force - see
+    /// `OrderManager::create_stop_order_with_tif`.
+    pub fn submit_stop_order_with_tif(
+        &mut self,
+        side: Side,
+        quantity: f64,
+        stop_price: f64,
+        time_in_force: TimeInForce,
+    ) -> EventId {
+        self.engine
+            .order_manager
+            .create_stop_order_with_tif(self.bar.timestamp, self.symbol, side, quantity, stop_price, time_in_force)
+            .id
+    }
+}
+
+/// Adapts a precomputed `(timestamp, signal)` map to the `Strategy`
+/// interface so `BacktestEngine::run` can be a thin wrapper around
+/// `run_strategy` instead of its own copy of the event loop.
+struct SignalStrategy {
+    signals: HashMap<String, i32>,
+}
+
+impl Strategy for SignalStrategy {
+    fn on_bar(&mut self, ctx: &mut StrategyContext) {
+        let timestamp_key = ctx.bar.timestamp.to_rfc3339();
+        if let Some(&signal) = self.signals.get(&timestamp_key) {
+            let _ = ctx.engine.execute_signal(ctx.symbol, signal, ctx.bar);
+        }
+    }
+}
+
 /// Helper struct for JSON data parsing
 #[derive(serde::Deserialize)]
 struct OhlcvRow {
@@ -18,12 +18,14 @@
 
 pub mod events;
 pub mod datafeed;
+pub mod money;
 pub mod brokerage;
 pub mod portfolio;
 pub mod order_manager;
 pub mod engine;
 pub mod metrics;
 pub mod config;
+pub mod optimizer;
 
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
@@ -170,6 +172,7 @@ impl PyBacktestEngine {
             maker_fee: config.maker_fee,
             taker_fee: config.taker_fee,
             slippage_pct: config.slippage_pct,
+            ..Default::default()
         };
         
         Ok(Self {
@@ -244,6 +247,7 @@ fn run_optimization(
                     maker_fee: base_config.maker_fee,
                     taker_fee: base_config.taker_fee,
                     slippage_pct: base_config.slippage_pct,
+                    ..Default::default()
                 });
                 
                 let _ = engine.load_data(&data_path);
@@ -8,6 +8,7 @@
 //! - Alpha/Beta
 //! - VaR/CVaR
 
+use crate::money::Amount;
 use crate::portfolio::{EquityPoint, TradeStats};
 
 /// Complete backtest result with all metrics
@@ -38,7 +39,15 @@ pub struct BacktestMetrics {
     // Risk measures
     pub var_95: f64,
     pub cvar_95: f64,
-    
+    /// Cornish-Fisher modified VaR at 95% confidence, which adjusts the
+    /// normal-distribution quantile for the return series' own skew and
+    /// kurtosis instead of relying purely on the historical 5th percentile.
+    pub modified_var_95: f64,
+
+    // Distribution-aware measures
+    pub omega_ratio: f64,
+    pub tail_ratio: f64,
+
     // Trade statistics
     pub trade_stats: TradeStats,
     
@@ -66,6 +75,9 @@ impl Default for BacktestMetrics {
             treynor_ratio: 0.0,
             var_95: 0.0,
             cvar_95: 0.0,
+            modified_var_95: 0.0,
+            omega_ratio: 0.0,
+            tail_ratio: 0.0,
             trade_stats: TradeStats::default(),
             equity_curve: Vec::new(),
             timestamps: Vec::new(),
@@ -73,6 +85,29 @@ impl Default for BacktestMetrics {
     }
 }
 
+/// Time-aligned series for charting a strategy's run, mirroring R's
+/// `charts.PerformanceSummary` three-panel layout (equity, drawdown,
+/// rolling risk). Every series is the same length as `timestamps`, so
+/// callers can zip them together directly instead of re-deriving
+/// alignment.
+#[derive(Debug, Clone)]
+pub struct PerformanceSeries {
+    pub timestamps: Vec<String>,
+    /// Cumulative return at each timestamp, as a fraction of the starting
+    /// equity (0.20 = +20% since inception).
+    pub cumulative_returns: Vec<f64>,
+    /// Peak-to-current drawdown at each timestamp, as a fraction (0.10 =
+    /// 10% below the running peak).
+    pub drawdown: Vec<f64>,
+    /// Annualized volatility over the trailing `lookback` periods ending at
+    /// each timestamp - `0.0` until `lookback` periods of history exist.
+    pub rolling_volatility: Vec<f64>,
+    /// Annualized Sharpe ratio over the same trailing `lookback` window -
+    /// `0.0` until `lookback` periods of history exist, or the window's
+    /// volatility is zero.
+    pub rolling_sharpe: Vec<f64>,
+}
+
 /// Calculator for backtest metrics
 pub struct MetricsCalculator {
     /// Risk-free rate (annualized)
@@ -107,7 +142,7 @@ impl MetricsCalculator {
             return BacktestMetrics::default();
         }
         
-        let equities: Vec<f64> = equity_curve.iter().map(|e| e.equity).collect();
+        let equities: Vec<f64> = equity_curve.iter().map(|e| e.equity.to_f64()).collect();
         let returns = self.calculate_returns(&equities);
         
         let initial = equities.first().copied().unwrap_or(1.0);
@@ -147,7 +182,12 @@ impl MetricsCalculator {
         
         // VaR and CVaR
         let (var_95, cvar_95) = self.calculate_var(&returns);
-        
+        let modified_var_95 = self.calculate_modified_var(&returns, -1.645);
+
+        // Omega ratio and tail ratio
+        let omega_ratio = self.calculate_omega_ratio(&returns, self.risk_free_rate / self.periods_per_year);
+        let tail_ratio = self.calculate_tail_ratio(&returns);
+
         // Equity curve for export
         let timestamps: Vec<String> = equity_curve
             .iter()
@@ -171,12 +211,77 @@ impl MetricsCalculator {
             treynor_ratio: 0.0,
             var_95: var_95 * 100.0,
             cvar_95: cvar_95 * 100.0,
+            modified_var_95: modified_var_95 * 100.0,
+            omega_ratio,
+            tail_ratio,
             trade_stats,
             equity_curve: equities,
             timestamps,
         }
     }
 
+    /// Time-aligned series for charting: cumulative returns, the
+    /// peak-to-current drawdown curve, and a rolling-window annualized
+    /// Sharpe/volatility over the trailing `lookback` periods at each
+    /// timestamp - everything `calculate` collapses into the single
+    /// `max_drawdown`/`sharpe_ratio` scalars, but aligned to `timestamps` so
+    /// callers can render the classic equity/drawdown/rolling-risk chart.
+    /// A `lookback` below 2 is treated as 2.
+    pub fn performance_series(&self, equity_curve: &[EquityPoint], lookback: usize) -> PerformanceSeries {
+        if equity_curve.is_empty() {
+            return PerformanceSeries {
+                timestamps: Vec::new(),
+                cumulative_returns: Vec::new(),
+                drawdown: Vec::new(),
+                rolling_volatility: Vec::new(),
+                rolling_sharpe: Vec::new(),
+            };
+        }
+
+        let equities: Vec<f64> = equity_curve.iter().map(|e| e.equity.to_f64()).collect();
+        let returns = self.calculate_returns(&equities);
+        let timestamps: Vec<String> = equity_curve.iter().map(|e| e.timestamp.to_rfc3339()).collect();
+
+        let initial = equities.first().copied().unwrap_or(1.0);
+        let cumulative_returns: Vec<f64> = equities
+            .iter()
+            .map(|&e| if initial != 0.0 { (e / initial) - 1.0 } else { 0.0 })
+            .collect();
+
+        let mut peak = equities[0];
+        let drawdown: Vec<f64> = equities
+            .iter()
+            .map(|&e| {
+                if e > peak {
+                    peak = e;
+                }
+                if peak > 0.0 { (peak - e) / peak } else { 0.0 }
+            })
+            .collect();
+
+        let lookback = lookback.max(2);
+        let mut rolling_volatility = vec![0.0; equities.len()];
+        let mut rolling_sharpe = vec![0.0; equities.len()];
+
+        // `returns[i]` is the period return ending at `equities[i + 1]`, so
+        // a window of `lookback` returns ending at index `i` of the equity
+        // series lives at `returns[i - lookback..i]`.
+        for i in lookback..equities.len() {
+            let window = &returns[i - lookback..i];
+            let volatility = self.calculate_volatility(window);
+            rolling_volatility[i] = volatility;
+            rolling_sharpe[i] = self.calculate_sharpe(window, volatility);
+        }
+
+        PerformanceSeries {
+            timestamps,
+            cumulative_returns,
+            drawdown,
+            rolling_volatility,
+            rolling_sharpe,
+        }
+    }
+
     /// Calculate period returns from equity curve
     fn calculate_returns(&self, equities: &[f64]) -> Vec<f64> {
         if equities.len() < 2 {
@@ -325,6 +430,89 @@ impl MetricsCalculator {
         (var_95.abs(), cvar_95.abs())
     }
 
+    /// Cornish-Fisher modified VaR: adjusts the standard normal quantile `z`
+    /// (e.g. -1.645 at 95% confidence) for the return series' own skewness
+    /// `S` and excess kurtosis `K-3` before applying it to the series' own
+    /// mean/std, so fat tails and asymmetry that the plain historical
+    /// percentile in `calculate_var` ignores still widen the estimate.
+    fn calculate_modified_var(&self, returns: &[f64], z: f64) -> f64 {
+        if returns.len() < 2 {
+            return 0.0;
+        }
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        let std_dev = variance.sqrt();
+        if std_dev == 0.0 {
+            return 0.0;
+        }
+
+        let skewness = returns.iter().map(|r| ((r - mean) / std_dev).powi(3)).sum::<f64>() / n;
+        let excess_kurtosis = returns.iter().map(|r| ((r - mean) / std_dev).powi(4)).sum::<f64>() / n - 3.0;
+
+        let z_cf = z
+            + (z.powi(2) - 1.0) / 6.0 * skewness
+            + (z.powi(3) - 3.0 * z) / 24.0 * excess_kurtosis
+            - (2.0 * z.powi(3) - 5.0 * z) / 36.0 * skewness.powi(2);
+
+        (-(mean + z_cf * std_dev)).abs()
+    }
+
+    /// Calculate the Omega ratio at `threshold`: the ratio of total gains
+    /// above the threshold to total losses below it,
+    /// `sum(max(r-threshold,0)) / sum(max(threshold-r,0))`. Unlike
+    /// Sharpe/Sortino this uses the whole return distribution rather than
+    /// just its mean and variance, so it captures skew. Returns 0 when
+    /// there are no losing periods to divide by.
+    fn calculate_omega_ratio(&self, returns: &[f64], threshold: f64) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+
+        let (gains, losses) = returns.iter().fold((0.0, 0.0), |(gains, losses), &r| {
+            if r > threshold {
+                (gains + (r - threshold), losses)
+            } else {
+                (gains, losses + (threshold - r))
+            }
+        });
+
+        if losses > 0.0 {
+            gains / losses
+        } else {
+            0.0
+        }
+    }
+
+    /// Calculate the tail ratio: `abs(95th percentile) / abs(5th
+    /// percentile)` of returns. Above 1.0 means the right tail (big wins) is
+    /// fatter than the left tail (big losses). Returns 0 when the 5th
+    /// percentile is zero.
+    fn calculate_tail_ratio(&self, returns: &[f64]) -> f64 {
+        if returns.is_empty() {
+            return 0.0;
+        }
+
+        let mut sorted: Vec<f64> = returns.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Same percentile-index convention as `calculate_var`'s 5th-percentile VaR.
+        let percentile = |p: f64| -> f64 {
+            let index = ((sorted.len() as f64 * p).floor() as usize).min(sorted.len() - 1);
+            sorted[index]
+        };
+
+        let p95 = percentile(0.95);
+        let p5 = percentile(0.05);
+
+        if p5.abs() > 0.0 {
+            p95.abs() / p5.abs()
+        } else {
+            0.0
+        }
+    }
+
     /// Calculate metrics with benchmark comparison
     pub fn calculate_with_benchmark(
         &self,
@@ -334,7 +522,7 @@ impl MetricsCalculator {
     ) -> BacktestMetrics {
         let mut metrics = self.calculate(equity_curve, trade_stats);
         
-        let equities: Vec<f64> = equity_curve.iter().map(|e| e.equity).collect();
+        let equities: Vec<f64> = equity_curve.iter().map(|e| e.equity.to_f64()).collect();
         let strategy_returns = self.calculate_returns(&equities);
         
         if strategy_returns.len() != benchmark_returns.len() {
@@ -424,10 +612,10 @@ mod tests {
         values.iter().enumerate().map(|(i, &v)| {
             EquityPoint {
                 timestamp: Utc::now(),
-                equity: v,
-                cash: v,
-                positions_value: 0.0,
-                drawdown: 0.0,
+                equity: Amount::from_f64(v),
+                cash: Amount::from_f64(v),
+                positions_value: Amount::ZERO,
+                drawdown: Amount::ZERO,
                 drawdown_pct: 0.0,
             }
         }).collect()
@@ -458,10 +646,42 @@ mod tests {
     fn test_volatility() {
         let calc = MetricsCalculator::new(0.02, "1d");
         let curve = create_equity_curve(vec![100.0, 101.0, 99.0, 102.0, 98.0]);
-        
+
         let metrics = calc.calculate(&curve, TradeStats::default());
-        
+
         assert!(metrics.volatility > 0.0);
     }
+
+    #[test]
+    fn test_performance_series_is_aligned_to_timestamps() {
+        let calc = MetricsCalculator::new(0.02, "1d");
+        let curve = create_equity_curve(vec![100.0, 110.0, 90.0, 95.0, 105.0]);
+
+        let series = calc.performance_series(&curve, 2);
+
+        assert_eq!(series.timestamps.len(), curve.len());
+        assert_eq!(series.cumulative_returns.len(), curve.len());
+        assert_eq!(series.drawdown.len(), curve.len());
+        assert_eq!(series.rolling_volatility.len(), curve.len());
+        assert_eq!(series.rolling_sharpe.len(), curve.len());
+
+        // Final equity is 5% above the 100.0 starting point.
+        assert!((series.cumulative_returns[4] - 0.05).abs() < 1e-9);
+
+        // Peak of 110.0 at index 1, down to 90.0 at index 2: (110-90)/110.
+        assert!((series.drawdown[2] - (20.0 / 110.0)).abs() < 1e-9);
+
+        // Still below the index-1 peak of 110.0 on the final bar.
+        assert!((series.drawdown[4] - (5.0 / 110.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_performance_series_empty_curve() {
+        let calc = MetricsCalculator::new(0.02, "1d");
+        let series = calc.performance_series(&[], 10);
+
+        assert!(series.timestamps.is_empty());
+        assert!(series.cumulative_returns.is_empty());
+    }
 }
 
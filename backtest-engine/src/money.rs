@@ -0,0 +1,167 @@
+//! Fixed-point money type for portfolio accounting
+//!
+//! `Position`, `TradeRecord`, `EquityPoint`, and `Portfolio` used raw `f64`
+//! for cash, price, and P&L math, which accumulates binary floating-point
+//! error across many fills - the `quantity.abs() < 1e-10` flat-check that
+//! used to live in `Position::is_flat` was a symptom of exactly this.
+//! `Amount` wraps `rust_decimal::Decimal` so that arithmetic is exact, and
+//! only converts to/from `f64` at the boundaries that still need it: an
+//! `f64`-typed `FillEvent`/`FundingEvent` field, JSON serialization, or a
+//! caller elsewhere in the engine that consumes equity/PnL as `f64`.
+
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// An exact decimal amount - used for both money (cash, P&L, cost basis)
+/// and price, mirroring how settlement-grade ledgers keep one scaled
+/// number type rather than distinguishing the two at the type level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Amount(Decimal);
+
+/// Alias for call sites where "price" reads more clearly than "amount" -
+/// same representation, same arithmetic.
+pub type Price = Amount;
+
+impl Amount {
+    pub const ZERO: Amount = Amount(Decimal::ZERO);
+
+    pub fn from_f64(value: f64) -> Self {
+        Amount(Decimal::from_f64(value).unwrap_or(Decimal::ZERO))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or(0.0)
+    }
+
+    pub fn abs(self) -> Self {
+        Amount(self.0.abs())
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn signum(self) -> Self {
+        Amount(self.0.signum())
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if self.0 >= other.0 { self } else { other }
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if self.0 <= other.0 { self } else { other }
+    }
+}
+
+impl From<f64> for Amount {
+    fn from(value: f64) -> Self {
+        Amount::from_f64(value)
+    }
+}
+
+impl From<Amount> for f64 {
+    fn from(amount: Amount) -> Self {
+        amount.to_f64()
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Amount {
+    type Output = Amount;
+    fn add(self, rhs: Self) -> Self::Output {
+        Amount(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Amount {
+    type Output = Amount;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Amount(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Amount {
+    type Output = Amount;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Amount(self.0 * rhs.0)
+    }
+}
+
+impl Div for Amount {
+    type Output = Amount;
+    fn div(self, rhs: Self) -> Self::Output {
+        Amount(self.0 / rhs.0)
+    }
+}
+
+impl Neg for Amount {
+    type Output = Amount;
+    fn neg(self) -> Self::Output {
+        Amount(-self.0)
+    }
+}
+
+impl AddAssign for Amount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Amount {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Amount {
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Self {
+        iter.fold(Amount::ZERO, Add::add)
+    }
+}
+
+/// Serializes/deserializes as a plain JSON number, so `Amount` is a drop-in
+/// replacement for the `f64` fields it replaces at the API/storage boundary.
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        Ok(Amount::from_f64(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_fractional_adds_are_exact() {
+        // 0.1 + 0.2 famously isn't 0.3 in binary floating point; Decimal
+        // underneath `Amount` doesn't have that problem.
+        let mut total = Amount::ZERO;
+        for _ in 0..10 {
+            total += Amount::from_f64(0.1);
+        }
+        assert_eq!(total, Amount::from_f64(1.0));
+    }
+
+    #[test]
+    fn test_flip_through_zero_is_exact_not_epsilon_bounded() {
+        let a = Amount::from_f64(100.0) - Amount::from_f64(100.0);
+        assert!(a.is_zero());
+    }
+}
@@ -0,0 +1,284 @@
+//! Parameter optimization over `OptimizationConfig`.
+//!
+//! `OptimizationStrategy::WalkForward` is the strategy implemented here:
+//! `Optimizer::walk_forward` slides (or anchors) a training/test window
+//! across a symbol's bars, picks the parameter combination from
+//! `config.parameter_ranges` that maximizes a caller-chosen objective on
+//! each fold's training slice, then measures how that combination holds up
+//! on the adjacent out-of-sample slice.
+
+use crate::config::{BacktestConfig, OptimizationConfig, OptimizationStrategy, ParameterRange};
+use crate::engine::{BacktestEngine, Strategy};
+use crate::events::Bar;
+use crate::metrics::BacktestMetrics;
+use rayon::prelude::*;
+
+/// Whether a walk-forward fold's training window grows from the start of
+/// the data (`Anchored`) or slides forward with the fold, always covering
+/// the same span (`Rolling`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    Rolling,
+    Anchored,
+}
+
+/// One walk-forward fold: the parameter combination (in `parameter_ranges`
+/// order) that scored best on the training slice, plus the in-sample
+/// metrics it was chosen on and the out-of-sample metrics it produced on
+/// the adjacent test slice.
+#[derive(Debug, Clone)]
+pub struct WalkForwardFold {
+    pub params: Vec<f64>,
+    pub in_sample: BacktestMetrics,
+    pub out_of_sample: BacktestMetrics,
+}
+
+/// Full walk-forward result: every fold plus the aggregate walk-forward
+/// efficiency - mean out-of-sample total return divided by mean in-sample
+/// total return. Close to 1.0 means the in-sample edge survived into the
+/// test slices; close to zero or negative means the parameters were
+/// overfit to the training window.
+#[derive(Debug, Clone)]
+pub struct WalkForwardResult {
+    pub folds: Vec<WalkForwardFold>,
+    pub efficiency: f64,
+}
+
+/// Runs `OptimizationConfig`'s configured strategy.
+pub struct Optimizer {
+    config: OptimizationConfig,
+}
+
+impl Optimizer {
+    pub fn new(config: OptimizationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Walk forward over `bars` for `symbol`, evaluating every parameter
+    /// combination in `self.config.parameter_ranges` on each fold's
+    /// training slice and keeping whichever maximizes `objective`.
+    /// `build_strategy` turns a parameter combination into a fresh
+    /// `Strategy` for one engine run. Parameter combinations within a fold
+    /// are evaluated across `self.config.n_workers` threads.
+    ///
+    /// Returns `None` if `self.config.strategy` isn't `WalkForward`, the
+    /// parameter grid is empty, or there isn't enough data for a single
+    /// train/test fold.
+    pub fn walk_forward<S, B, O>(
+        &self,
+        base_config: &BacktestConfig,
+        symbol: &str,
+        bars: &[Bar],
+        window: WindowMode,
+        build_strategy: B,
+        objective: O,
+    ) -> Option<WalkForwardResult>
+    where
+        S: Strategy,
+        B: Fn(&[f64]) -> S + Sync,
+        O: Fn(&BacktestMetrics) -> f64 + Sync,
+    {
+        let (train_pct, test_pct) = match self.config.strategy {
+            OptimizationStrategy::WalkForward { train_pct, test_pct } => (train_pct, test_pct),
+            _ => return None,
+        };
+
+        let combinations = parameter_combinations(&self.config.parameter_ranges);
+        if combinations.is_empty() || bars.is_empty() {
+            return None;
+        }
+
+        let total = bars.len();
+        let train_len = ((total as f64 * train_pct).round() as usize).max(1);
+        let test_len = ((total as f64 * test_pct).round() as usize).max(1);
+        if train_len + test_len > total {
+            return None;
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.config.n_workers.max(1))
+            .build()
+            .ok()?;
+
+        let mut folds = Vec::new();
+        let mut train_start = 0usize;
+
+        while train_start + train_len + test_len <= total {
+            let train_range = match window {
+                WindowMode::Rolling => train_start..(train_start + train_len),
+                WindowMode::Anchored => 0..(train_start + train_len),
+            };
+            let train_end = train_start + train_len;
+            let test_range = train_end..(train_end + test_len);
+
+            let train_bars = &bars[train_range];
+            let test_bars = &bars[test_range];
+
+            let evaluated: Vec<(Vec<f64>, f64, BacktestMetrics)> = pool.install(|| {
+                combinations
+                    .par_iter()
+                    .map(|params| {
+                        let metrics = run_fold(base_config, symbol, train_bars, params, &build_strategy);
+                        let score = objective(&metrics);
+                        (params.clone(), score, metrics)
+                    })
+                    .collect()
+            });
+
+            let best = evaluated
+                .into_iter()
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+            let Some((best_params, _, in_sample)) = best else {
+                train_start += test_len;
+                continue;
+            };
+
+            let out_of_sample = run_fold(base_config, symbol, test_bars, &best_params, &build_strategy);
+
+            folds.push(WalkForwardFold {
+                params: best_params,
+                in_sample,
+                out_of_sample,
+            });
+
+            train_start += test_len;
+        }
+
+        let efficiency = walk_forward_efficiency(&folds);
+
+        Some(WalkForwardResult { folds, efficiency })
+    }
+}
+
+/// Mean OOS total return divided by mean IS total return across `folds`,
+/// or `0.0` when there are no folds or the in-sample mean is zero.
+fn walk_forward_efficiency(folds: &[WalkForwardFold]) -> f64 {
+    if folds.is_empty() {
+        return 0.0;
+    }
+
+    let n = folds.len() as f64;
+    let mean_is = folds.iter().map(|f| f.in_sample.total_return).sum::<f64>() / n;
+    let mean_oos = folds.iter().map(|f| f.out_of_sample.total_return).sum::<f64>() / n;
+
+    if mean_is != 0.0 {
+        mean_oos / mean_is
+    } else {
+        0.0
+    }
+}
+
+/// Run a single engine over `bars` with `params` applied through
+/// `build_strategy`, returning its `BacktestMetrics`.
+fn run_fold<S, B>(base_config: &BacktestConfig, symbol: &str, bars: &[Bar], params: &[f64], build_strategy: &B) -> BacktestMetrics
+where
+    S: Strategy,
+    B: Fn(&[f64]) -> S,
+{
+    let mut engine = BacktestEngine::new(base_config.clone());
+    engine.load_bars(symbol, bars.to_vec());
+
+    let mut strategy = build_strategy(params);
+    engine.run_strategy(&mut strategy).unwrap_or_default()
+}
+
+/// Cartesian product of every range's `values()`, in `ranges` order.
+fn parameter_combinations(ranges: &[ParameterRange]) -> Vec<Vec<f64>> {
+    ranges.iter().fold(vec![Vec::new()], |combos, range| {
+        let values = range.values();
+        combos
+            .into_iter()
+            .flat_map(|prefix| {
+                values.iter().map(move |&v| {
+                    let mut next = prefix.clone();
+                    next.push(v);
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    struct NoopStrategy;
+    impl Strategy for NoopStrategy {
+        fn on_bar(&mut self, _ctx: &mut crate::engine::StrategyContext) {}
+    }
+
+    fn synthetic_bars(n: usize) -> Vec<Bar> {
+        let start = Utc::now();
+        (0..n)
+            .map(|i| {
+                let price = 100.0 + i as f64;
+                Bar::new(start + Duration::hours(i as i64), price, price + 1.0, price - 1.0, price, 1000.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parameter_combinations_cartesian_product() {
+        let ranges = vec![
+            ParameterRange { name: "a".to_string(), min: 1.0, max: 2.0, step: 1.0 },
+            ParameterRange { name: "b".to_string(), min: 10.0, max: 20.0, step: 10.0 },
+        ];
+        let combos = parameter_combinations(&ranges);
+        assert_eq!(combos.len(), 4);
+        assert!(combos.contains(&vec![1.0, 10.0]));
+        assert!(combos.contains(&vec![2.0, 20.0]));
+    }
+
+    #[test]
+    fn test_walk_forward_slides_by_test_length_each_fold() {
+        let optimizer = Optimizer::new(OptimizationConfig {
+            parameter_ranges: vec![ParameterRange { name: "x".to_string(), min: 1.0, max: 1.0, step: 1.0 }],
+            strategy: OptimizationStrategy::WalkForward { train_pct: 0.5, test_pct: 0.25 },
+            n_workers: 2,
+            max_iterations: 10,
+        });
+
+        // 20 bars: train_len = round(20*0.5) = 10, test_len = round(20*0.25)
+        // = 5. Fold 0 covers [0..15), fold 1 covers [5..20) - a third fold
+        // would need [10..25), past the end of the data.
+        let bars = synthetic_bars(20);
+        let result = optimizer
+            .walk_forward(
+                &BacktestConfig::default(),
+                "BTC/USD",
+                &bars,
+                WindowMode::Rolling,
+                |_params| NoopStrategy,
+                |metrics| metrics.sharpe_ratio,
+            )
+            .expect("WalkForward strategy should run");
+
+        assert_eq!(result.folds.len(), 2);
+        assert_eq!(result.folds[0].params, vec![1.0]);
+    }
+
+    #[test]
+    fn test_walk_forward_returns_none_for_non_walk_forward_strategy() {
+        let optimizer = Optimizer::new(OptimizationConfig {
+            parameter_ranges: vec![ParameterRange { name: "x".to_string(), min: 1.0, max: 1.0, step: 1.0 }],
+            strategy: OptimizationStrategy::GridSearch,
+            n_workers: 1,
+            max_iterations: 10,
+        });
+
+        let bars = synthetic_bars(20);
+        let result = optimizer.walk_forward(
+            &BacktestConfig::default(),
+            "BTC/USD",
+            &bars,
+            WindowMode::Rolling,
+            |_params| NoopStrategy,
+            |metrics| metrics.sharpe_ratio,
+        );
+
+        assert!(result.is_none());
+    }
+}
@@ -2,7 +2,7 @@
 //! 
 //! Order lifecycle: Submitted → Pending → Filled/Cancelled/Rejected
 
-use crate::events::{EventId, OrderEvent, OrderStatus, OrderType, Side};
+use crate::events::{EventId, OrderEvent, OrderStatus, OrderType, Side, TimeInForce};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
@@ -96,6 +96,57 @@ impl OrderManager {
         order
     }
 
+    /// Create a new limit order with an explicit time-in-force - `GTC` by
+    /// default via `create_limit_order`, but `IOC`/`FOK` orders only get one
+    /// shot at the next `BacktestEngine::execute_resting_orders` sweep, and
+    /// `GTD` orders expire on their own once `expire_orders` sweeps past
+    /// their deadline.
+    pub fn create_limit_order_with_tif(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        symbol: &str,
+        side: Side,
+        quantity: f64,
+        limit_price: f64,
+        time_in_force: TimeInForce,
+    ) -> OrderEvent {
+        let order = OrderEvent::limit(
+            self.next_id,
+            timestamp,
+            symbol.to_string(),
+            side,
+            quantity,
+            limit_price,
+        )
+        .with_time_in_force(time_in_force);
+        self.submit_order(order.clone());
+        order
+    }
+
+    /// Create a new stop order with an explicit time-in-force - see
+    /// `create_limit_order_with_tif`.
+    pub fn create_stop_order_with_tif(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        symbol: &str,
+        side: Side,
+        quantity: f64,
+        stop_price: f64,
+        time_in_force: TimeInForce,
+    ) -> OrderEvent {
+        let order = OrderEvent::stop(
+            self.next_id,
+            timestamp,
+            symbol.to_string(),
+            side,
+            quantity,
+            stop_price,
+        )
+        .with_time_in_force(time_in_force);
+        self.submit_order(order.clone());
+        order
+    }
+
     /// Submit an order to the manager
     fn submit_order(&mut self, order: OrderEvent) {
         let id = order.id;
@@ -122,6 +173,54 @@ impl OrderManager {
         }
     }
 
+    /// Record a fill against `order_id`, summing it onto the order's
+    /// `filled_quantity` - mirrors how `Brokerage::fill_partial` tracks its
+    /// own resting orders. Moves the order to `Filled` once the sum covers
+    /// `quantity`, otherwise to `PartiallyFilled` and leaves it open so a
+    /// later bar can drain the remainder.
+    pub fn record_fill(&mut self, order_id: EventId, fill_quantity: f64) {
+        let fully_filled = match self.orders.get_mut(&order_id) {
+            Some(order) => {
+                order.filled_quantity += fill_quantity;
+                order.filled_quantity >= order.quantity - 1e-9
+            }
+            None => return,
+        };
+
+        if fully_filled {
+            self.mark_filled(order_id);
+        } else if let Some(order) = self.orders.get_mut(&order_id) {
+            order.status = OrderStatus::PartiallyFilled;
+        }
+    }
+
+    /// Remaining unfilled quantity on `order_id` (0.0 if unknown).
+    pub fn remaining_quantity(&self, order_id: EventId) -> f64 {
+        self.orders
+            .get(&order_id)
+            .map(|o| (o.quantity - o.filled_quantity).max(0.0))
+            .unwrap_or(0.0)
+    }
+
+    /// Cancel every open `GTD` order whose deadline has already passed
+    /// `now`, returning their ids - swept once per bar by
+    /// `BacktestEngine::process_market_data` so a stale limit/stop order
+    /// can't fill on a bar well after the strategy meant it to expire.
+    pub fn expire_orders(&mut self, now: DateTime<Utc>) -> Vec<EventId> {
+        let expired: Vec<EventId> = self.open_orders
+            .iter()
+            .filter_map(|id| self.orders.get(id))
+            .filter(|o| matches!(o.time_in_force, TimeInForce::GTD(deadline) if deadline < now))
+            .map(|o| o.id)
+            .collect();
+
+        for &id in &expired {
+            self.mark_cancelled(id);
+        }
+
+        expired
+    }
+
     /// Mark an order as cancelled
     pub fn mark_cancelled(&mut self, order_id: EventId) {
         if let Some(order) = self.orders.get_mut(&order_id) {
@@ -269,6 +368,48 @@ mod tests {
         assert_eq!(manager.filled_count(), 1);
     }
 
+    #[test]
+    fn test_record_fill_partial_then_complete() {
+        let mut manager = OrderManager::new();
+
+        let order = manager.create_market_order(Utc::now(), "BTC/USD", Side::Buy, 10.0);
+
+        manager.record_fill(order.id, 4.0);
+        assert_eq!(manager.get_order(order.id).unwrap().status, OrderStatus::PartiallyFilled);
+        assert_eq!(manager.remaining_quantity(order.id), 6.0);
+        assert_eq!(manager.open_count(), 1);
+
+        manager.record_fill(order.id, 6.0);
+        assert_eq!(manager.get_order(order.id).unwrap().status, OrderStatus::Filled);
+        assert_eq!(manager.remaining_quantity(order.id), 0.0);
+        assert_eq!(manager.open_count(), 0);
+        assert_eq!(manager.filled_count(), 1);
+    }
+
+    #[test]
+    fn test_expire_orders_cancels_past_deadline_gtd() {
+        let mut manager = OrderManager::new();
+        let submitted = Utc::now();
+        let deadline = submitted + chrono::Duration::hours(1);
+
+        let expiring = manager.create_limit_order_with_tif(
+            submitted,
+            "BTC/USD",
+            Side::Buy,
+            1.0,
+            50000.0,
+            TimeInForce::GTD(deadline),
+        );
+        let resting = manager.create_limit_order(submitted, "ETH/USD", Side::Buy, 10.0, 3000.0);
+
+        assert!(manager.expire_orders(submitted).is_empty());
+
+        let expired = manager.expire_orders(deadline + chrono::Duration::seconds(1));
+        assert_eq!(expired, vec![expiring.id]);
+        assert_eq!(manager.get_order(expiring.id).unwrap().status, OrderStatus::Cancelled);
+        assert_eq!(manager.get_order(resting.id).unwrap().status, OrderStatus::Submitted);
+    }
+
     #[test]
     fn test_cancel_orders() {
         let mut manager = OrderManager::new();
@@ -6,78 +6,156 @@
 //! - Equity curve
 //! - Realized and unrealized P&L
 
-use crate::events::{FillEvent, Side, PortfolioUpdateEvent, EventId};
+use crate::events::{FillEvent, FundingEvent, Side, PortfolioUpdateEvent, EventId};
+use crate::money::{Amount, Price};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Margin/leverage metadata for a perpetual-style position. When present on
+/// a `Position`, `update_market_value` derives unrealized P&L from
+/// `(mark - entry) * size` instead of `market_value - cost_basis`, since
+/// cost-basis spot accounting doesn't reflect a leveraged notional backed by
+/// a fraction of its own value in collateral.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PerpMetadata {
+    /// Collateral backing this position, separate from the account's free cash.
+    pub margin: Amount,
+    pub leverage: f64,
+    pub maintenance_margin_ratio: f64,
+    /// Cumulative funding paid (negative) or received (positive) over the
+    /// position's life - `Portfolio::process_funding` folds the same flow
+    /// into cash; this is just a per-position running total for display.
+    pub cumulative_funding: Amount,
+    /// Isolated-margin liquidation price, recomputed by `open_perp` whenever
+    /// the position's entry price, leverage, or maintenance ratio changes.
+    pub liquidation_price: Price,
+}
+
 /// A single position in a symbol
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub symbol: String,
-    pub quantity: f64,
-    pub average_price: f64,
-    pub market_value: f64,
-    pub unrealized_pnl: f64,
-    pub realized_pnl: f64,
-    pub cost_basis: f64,
+    pub quantity: Amount,
+    pub average_price: Price,
+    pub market_value: Amount,
+    pub unrealized_pnl: Amount,
+    pub realized_pnl: Amount,
+    pub cost_basis: Amount,
+    /// `Some` for a leveraged/funded perpetual position, `None` for a
+    /// fully-collateralized spot position - see `PerpMetadata`.
+    pub perp: Option<PerpMetadata>,
 }
 
 impl Position {
     pub fn new(symbol: &str) -> Self {
         Self {
             symbol: symbol.to_string(),
-            quantity: 0.0,
-            average_price: 0.0,
-            market_value: 0.0,
-            unrealized_pnl: 0.0,
-            realized_pnl: 0.0,
-            cost_basis: 0.0,
+            quantity: Amount::ZERO,
+            average_price: Amount::ZERO,
+            market_value: Amount::ZERO,
+            unrealized_pnl: Amount::ZERO,
+            realized_pnl: Amount::ZERO,
+            cost_basis: Amount::ZERO,
+            perp: None,
         }
     }
 
-    /// Update position with a new fill
+    /// Mark this position as a perpetual with the given margin/leverage,
+    /// computing its isolated-margin liquidation price. Safe to call again
+    /// after the position's average price moves (e.g. after averaging into
+    /// it) to refresh the liquidation price.
+    pub fn open_perp(&mut self, margin: Amount, leverage: f64, maintenance_margin_ratio: f64) {
+        let liquidation_price =
+            Self::isolated_liquidation_price(self.average_price, self.quantity, leverage, maintenance_margin_ratio);
+        let cumulative_funding = self.perp.map(|p| p.cumulative_funding).unwrap_or(Amount::ZERO);
+        self.perp = Some(PerpMetadata {
+            margin,
+            leverage,
+            maintenance_margin_ratio,
+            cumulative_funding,
+            liquidation_price,
+        });
+    }
+
+    /// Isolated-margin liquidation price for a position entered at
+    /// `entry_price`: for a long, the price at which losses eat through the
+    /// leveraged margin plus the maintenance buffer; for a short, the
+    /// mirror image on the upside. Mirrors `Brokerage::liquidation_price`'s
+    /// formula so reported and simulated liquidation thresholds agree.
+    fn isolated_liquidation_price(
+        entry_price: Price,
+        quantity: Amount,
+        leverage: f64,
+        maintenance_margin_ratio: f64,
+    ) -> Price {
+        let leverage = leverage.max(1.0);
+        if quantity >= Amount::ZERO {
+            entry_price * Amount::from_f64(1.0 - 1.0 / leverage + maintenance_margin_ratio)
+        } else {
+            entry_price * Amount::from_f64(1.0 + 1.0 / leverage - maintenance_margin_ratio)
+        }
+    }
+
+    /// Update position with a new fill. Quantity is signed (negative is
+    /// short), so the same logic handles a long and a short symmetrically:
+    /// a fill in the position's own direction (or from flat) adds to it at
+    /// a new volume-weighted average price; a fill against it realizes PnL
+    /// on the offsetting portion - `(exit - entry)` for a long, the mirror
+    /// image for a short - and, if the fill is larger than the position,
+    /// flips through zero into a fresh position at the fill price.
     pub fn update_with_fill(&mut self, fill: &FillEvent) {
-        match fill.side {
-            Side::Buy => {
-                // Calculate new average price
-                let total_cost = self.quantity * self.average_price + fill.quantity * fill.fill_price;
-                let new_quantity = self.quantity + fill.quantity;
-                
-                if new_quantity > 0.0 {
-                    self.average_price = total_cost / new_quantity;
-                }
-                self.quantity = new_quantity;
-                self.cost_basis += fill.quantity * fill.fill_price + fill.commission;
-            }
-            Side::Sell => {
-                // Calculate realized P&L
-                let sold_cost = fill.quantity * self.average_price;
-                let sold_value = fill.quantity * fill.fill_price - fill.commission;
-                self.realized_pnl += sold_value - sold_cost;
-                
-                self.quantity -= fill.quantity;
-                self.cost_basis -= fill.quantity * self.average_price;
-                
-                // Reset if position closed
-                if self.quantity.abs() < 1e-10 {
-                    self.quantity = 0.0;
-                    self.average_price = 0.0;
-                    self.cost_basis = 0.0;
-                }
+        let fill_quantity = Amount::from_f64(fill.quantity);
+        let fill_price = Price::from_f64(fill.fill_price);
+        let commission = Amount::from_f64(fill.commission);
+
+        let signed_qty = match fill.side {
+            Side::Buy => fill_quantity,
+            Side::Sell => -fill_quantity,
+        };
+
+        if self.quantity.is_zero() || self.quantity.signum() == signed_qty.signum() {
+            let total_quantity = self.quantity.abs() + signed_qty.abs();
+            let total_cost = self.quantity.abs() * self.average_price + signed_qty.abs() * fill_price;
+            self.average_price = if !total_quantity.is_zero() { total_cost / total_quantity } else { Amount::ZERO };
+            self.quantity += signed_qty;
+            self.cost_basis += signed_qty.abs() * fill_price + commission;
+        } else {
+            let direction = self.quantity.signum();
+            let closing_quantity = signed_qty.abs().min(self.quantity.abs());
+            self.realized_pnl += direction * closing_quantity * (fill_price - self.average_price) - commission;
+            self.cost_basis -= closing_quantity * self.average_price;
+            self.quantity += signed_qty;
+
+            if self.quantity.is_zero() {
+                self.average_price = Amount::ZERO;
+                self.cost_basis = Amount::ZERO;
+            } else if self.quantity.signum() != direction {
+                // The fill outsized the position - it flipped through zero,
+                // so what's left is a fresh position at the fill price.
+                self.average_price = fill_price;
+                self.cost_basis = self.quantity.abs() * fill_price;
             }
         }
     }
 
-    /// Update market value and unrealized P&L with current price
+    /// Update market value and unrealized P&L with current price. A perp
+    /// position's P&L is `(mark - entry) * size` against its margin, not
+    /// `market_value - cost_basis` - cost basis includes commission and
+    /// isn't meaningful once the position is leveraged rather than fully
+    /// collateralized.
     pub fn update_market_value(&mut self, current_price: f64) {
+        let current_price = Price::from_f64(current_price);
         self.market_value = self.quantity * current_price;
-        self.unrealized_pnl = self.market_value - self.cost_basis;
+        self.unrealized_pnl = match self.perp {
+            Some(_) => (current_price - self.average_price) * self.quantity,
+            None => self.market_value - self.cost_basis,
+        };
     }
 
     /// Check if position is flat (no holdings)
     pub fn is_flat(&self) -> bool {
-        self.quantity.abs() < 1e-10
+        self.quantity.is_zero()
     }
 }
 
@@ -85,42 +163,42 @@ impl Position {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EquityPoint {
     pub timestamp: DateTime<Utc>,
-    pub equity: f64,
-    pub cash: f64,
-    pub positions_value: f64,
-    pub drawdown: f64,
+    pub equity: Amount,
+    pub cash: Amount,
+    pub positions_value: Amount,
+    pub drawdown: Amount,
     pub drawdown_pct: f64,
 }
 
 /// Portfolio manager that tracks all positions and cash
 pub struct Portfolio {
     /// Available cash
-    cash: f64,
-    
+    cash: Amount,
+
     /// Initial capital
-    initial_capital: f64,
-    
+    initial_capital: Amount,
+
     /// Open positions by symbol
     positions: HashMap<String, Position>,
-    
+
     /// Equity curve history
     equity_curve: Vec<EquityPoint>,
-    
+
     /// Peak equity for drawdown calculation
-    peak_equity: f64,
-    
+    peak_equity: Amount,
+
     /// Current drawdown
-    current_drawdown: f64,
-    
+    current_drawdown: Amount,
+
     /// Maximum drawdown seen
-    max_drawdown: f64,
-    
+    max_drawdown: Amount,
+
     /// Total realized P&L
-    total_realized_pnl: f64,
-    
+    total_realized_pnl: Amount,
+
     /// Trade history
     trades: Vec<TradeRecord>,
-    
+
     /// Event ID counter
     event_id: EventId,
 }
@@ -131,27 +209,28 @@ pub struct TradeRecord {
     pub id: u64,
     pub symbol: String,
     pub side: String,
-    pub quantity: f64,
-    pub entry_price: f64,
-    pub exit_price: Option<f64>,
+    pub quantity: Amount,
+    pub entry_price: Price,
+    pub exit_price: Option<Price>,
     pub entry_time: DateTime<Utc>,
     pub exit_time: Option<DateTime<Utc>>,
-    pub pnl: f64,
-    pub commission: f64,
+    pub pnl: Amount,
+    pub commission: Amount,
     pub status: String,  // "open" or "closed"
 }
 
 impl Portfolio {
     pub fn new(initial_capital: f64) -> Self {
+        let initial_capital = Amount::from_f64(initial_capital);
         Self {
             cash: initial_capital,
             initial_capital,
             positions: HashMap::new(),
             equity_curve: Vec::new(),
             peak_equity: initial_capital,
-            current_drawdown: 0.0,
-            max_drawdown: 0.0,
-            total_realized_pnl: 0.0,
+            current_drawdown: Amount::ZERO,
+            max_drawdown: Amount::ZERO,
+            total_realized_pnl: Amount::ZERO,
             trades: Vec::new(),
             event_id: 0,
         }
@@ -159,13 +238,18 @@ impl Portfolio {
 
     /// Process a fill event and update positions
     pub fn process_fill(&mut self, fill: &FillEvent) {
+        let fill_quantity = Amount::from_f64(fill.quantity);
+        let fill_price = Price::from_f64(fill.fill_price);
+        let commission = Amount::from_f64(fill.commission);
+        let slippage = Amount::from_f64(fill.slippage);
+
         // Update cash
         match fill.side {
             Side::Buy => {
-                self.cash -= fill.quantity * fill.fill_price + fill.commission + fill.slippage;
+                self.cash -= fill_quantity * fill_price + commission + slippage;
             }
             Side::Sell => {
-                self.cash += fill.quantity * fill.fill_price - fill.commission - fill.slippage;
+                self.cash += fill_quantity * fill_price - commission - slippage;
             }
         }
 
@@ -176,72 +260,110 @@ impl Portfolio {
 
         // Track realized P&L before update
         let prev_realized = position.realized_pnl;
-        
+
         // Update position
         position.update_with_fill(fill);
-        
+
         // Track trade
         self.record_trade(fill);
-        
+
         // Update total realized P&L
         self.total_realized_pnl += position.realized_pnl - prev_realized;
     }
 
-    fn record_trade(&mut self, fill: &FillEvent) {
-        // Find open trade for this symbol or create new
-        let open_trade = self.trades.iter_mut()
-            .find(|t| t.symbol == fill.symbol && t.status == "open");
-        
-        match fill.side {
-            Side::Buy => {
-                if let Some(trade) = open_trade {
-                    // Adding to existing position
-                    let total_qty = trade.quantity + fill.quantity;
-                    trade.entry_price = (trade.entry_price * trade.quantity + fill.fill_price * fill.quantity) / total_qty;
-                    trade.quantity = total_qty;
-                    trade.commission += fill.commission;
-                } else {
-                    // New trade
-                    self.trades.push(TradeRecord {
-                        id: self.trades.len() as u64 + 1,
-                        symbol: fill.symbol.clone(),
-                        side: "long".to_string(),
-                        quantity: fill.quantity,
-                        entry_price: fill.fill_price,
-                        exit_price: None,
-                        entry_time: fill.timestamp,
-                        exit_time: None,
-                        pnl: 0.0,
-                        commission: fill.commission,
-                        status: "open".to_string(),
-                    });
-                }
-            }
-            Side::Sell => {
-                if let Some(trade) = open_trade {
-                    // Closing position
-                    trade.exit_price = Some(fill.fill_price);
-                    trade.exit_time = Some(fill.timestamp);
-                    trade.pnl = (fill.fill_price - trade.entry_price) * fill.quantity - trade.commission - fill.commission;
-                    trade.commission += fill.commission;
-                    trade.status = "closed".to_string();
-                } else {
-                    // Short trade (new position)
-                    self.trades.push(TradeRecord {
-                        id: self.trades.len() as u64 + 1,
-                        symbol: fill.symbol.clone(),
-                        side: "short".to_string(),
-                        quantity: fill.quantity,
-                        entry_price: fill.fill_price,
-                        exit_price: None,
-                        entry_time: fill.timestamp,
-                        exit_time: None,
-                        pnl: 0.0,
-                        commission: fill.commission,
-                        status: "open".to_string(),
-                    });
-                }
+    /// Apply a funding settlement's cash flow to the position it was
+    /// computed against. Unlike `process_fill`, quantity doesn't change -
+    /// this is pure carry cost (or credit) on an already-open position.
+    pub fn process_funding(&mut self, funding: &FundingEvent) {
+        let amount = Amount::from_f64(funding.amount);
+        self.cash += amount;
+
+        if let Some(position) = self.positions.get_mut(&funding.symbol) {
+            position.realized_pnl += amount;
+            if let Some(perp) = position.perp.as_mut() {
+                perp.cumulative_funding += amount;
             }
+            self.total_realized_pnl += amount;
+        }
+    }
+
+    /// Journal `fill` into `trades`, tracking signed quantity/direction the
+    /// same way `Position::update_with_fill` does so the two never diverge:
+    /// a fill in the open trade's own direction (or from flat) adds to it at
+    /// a new volume-weighted entry price; a fill against it realizes P&L on
+    /// the offsetting portion and, if it's larger than the open trade,
+    /// flips through zero into a fresh trade on the other side. A trade only
+    /// becomes `"closed"` once its quantity nets to exactly zero.
+    fn record_trade(&mut self, fill: &FillEvent) {
+        let fill_quantity = Amount::from_f64(fill.quantity);
+        let fill_price = Price::from_f64(fill.fill_price);
+        let commission = Amount::from_f64(fill.commission);
+        let signed_qty = match fill.side {
+            Side::Buy => fill_quantity,
+            Side::Sell => -fill_quantity,
+        };
+
+        let Some(idx) = self.trades.iter().position(|t| t.symbol == fill.symbol && t.status == "open") else {
+            self.trades.push(Self::new_trade_record(self.trades.len() as u64 + 1, fill, signed_qty, fill_price, commission));
+            return;
+        };
+
+        let direction = if self.trades[idx].side == "long" { Amount::from_f64(1.0) } else { Amount::from_f64(-1.0) };
+        let same_direction = (signed_qty >= Amount::ZERO) == (direction >= Amount::ZERO);
+
+        if same_direction {
+            let trade = &mut self.trades[idx];
+            let total_qty = trade.quantity + signed_qty.abs();
+            trade.entry_price = (trade.entry_price * trade.quantity + fill_price * signed_qty.abs()) / total_qty;
+            trade.quantity = total_qty;
+            trade.commission += commission;
+            return;
+        }
+
+        let closing_quantity = signed_qty.abs().min(self.trades[idx].quantity);
+        let leftover = signed_qty.abs() - closing_quantity;
+
+        let trade = &mut self.trades[idx];
+        trade.pnl += direction * closing_quantity * (fill_price - trade.entry_price) - commission;
+        trade.commission += commission;
+        trade.quantity -= closing_quantity;
+
+        if trade.quantity.is_zero() {
+            trade.exit_price = Some(fill_price);
+            trade.exit_time = Some(fill.timestamp);
+            trade.status = "closed".to_string();
+        }
+
+        if !leftover.is_zero() {
+            let signed_leftover = if signed_qty > Amount::ZERO { leftover } else { -leftover };
+            self.trades.push(Self::new_trade_record(self.trades.len() as u64 + 1, fill, signed_leftover, fill_price, Amount::ZERO));
+        }
+    }
+
+    /// Build a fresh open `TradeRecord` for a new or flipped-through-zero
+    /// position, sized and directed by `signed_qty` (positive is long,
+    /// negative is short). `opening_commission` is the commission to
+    /// attribute to it - zero for the leftover leg of a flip, since the
+    /// fill's commission was already charged against the trade it closed.
+    fn new_trade_record(
+        id: u64,
+        fill: &FillEvent,
+        signed_qty: Amount,
+        fill_price: Price,
+        opening_commission: Amount,
+    ) -> TradeRecord {
+        TradeRecord {
+            id,
+            symbol: fill.symbol.clone(),
+            side: if signed_qty > Amount::ZERO { "long" } else { "short" }.to_string(),
+            quantity: signed_qty.abs(),
+            entry_price: fill_price,
+            exit_price: None,
+            entry_time: fill.timestamp,
+            exit_time: None,
+            pnl: Amount::ZERO,
+            commission: opening_commission,
+            status: "open".to_string(),
         }
     }
 
@@ -256,25 +378,25 @@ impl Portfolio {
 
     /// Record current equity state
     pub fn record_equity(&mut self, timestamp: DateTime<Utc>) {
-        let equity = self.total_equity();
-        let positions_value = self.positions_value();
-        
+        let equity = self.total_equity_amount();
+        let positions_value = self.positions_value_amount();
+
         // Update peak and drawdown
         if equity > self.peak_equity {
             self.peak_equity = equity;
         }
-        
+
         self.current_drawdown = self.peak_equity - equity;
-        let drawdown_pct = if self.peak_equity > 0.0 {
-            (self.current_drawdown / self.peak_equity) * 100.0
+        let drawdown_pct = if !self.peak_equity.is_zero() {
+            (self.current_drawdown.to_f64() / self.peak_equity.to_f64()) * 100.0
         } else {
             0.0
         };
-        
+
         if self.current_drawdown > self.max_drawdown {
             self.max_drawdown = self.current_drawdown;
         }
-        
+
         self.equity_curve.push(EquityPoint {
             timestamp,
             equity,
@@ -285,35 +407,61 @@ impl Portfolio {
         });
     }
 
+    /// Total value of all positions, in `Amount` - shared by `positions_value`
+    /// and the internal equity/margin math that needs to stay exact before
+    /// converting out to `f64`.
+    fn positions_value_amount(&self) -> Amount {
+        self.positions.values().map(|p| p.market_value).sum()
+    }
+
+    /// Cash plus positions value, in `Amount` - see `positions_value_amount`.
+    fn total_equity_amount(&self) -> Amount {
+        self.cash + self.positions_value_amount()
+    }
+
     /// Get total equity (cash + positions value)
     pub fn total_equity(&self) -> f64 {
-        self.cash + self.positions_value()
+        self.total_equity_amount().to_f64()
     }
 
     /// Get total value of all positions
     pub fn positions_value(&self) -> f64 {
-        self.positions.values().map(|p| p.market_value).sum()
+        self.positions_value_amount().to_f64()
     }
 
     /// Get unrealized P&L
     pub fn unrealized_pnl(&self) -> f64 {
-        self.positions.values().map(|p| p.unrealized_pnl).sum()
+        self.positions.values().map(|p| p.unrealized_pnl).sum::<Amount>().to_f64()
     }
 
     /// Get realized P&L
     pub fn realized_pnl(&self) -> f64 {
-        self.total_realized_pnl
+        self.total_realized_pnl.to_f64()
     }
 
     /// Get total P&L
     pub fn total_pnl(&self) -> f64 {
-        self.total_equity() - self.initial_capital
+        self.total_equity() - self.initial_capital.to_f64()
+    }
+
+    /// Margin currently tied up by open positions at the given `leverage` -
+    /// each position's notional divided by leverage, summed across symbols.
+    pub fn used_margin(&self, leverage: f64) -> f64 {
+        let leverage = leverage.max(1.0);
+        let notional: Amount = self.positions.values().map(|p| p.market_value.abs()).sum();
+        notional.to_f64() / leverage
+    }
+
+    /// Margin available for new orders: account equity (cash + positions
+    /// value) minus margin already tied up in open positions.
+    pub fn free_margin(&self, leverage: f64) -> f64 {
+        self.total_equity() - self.used_margin(leverage)
     }
 
     /// Get return percentage
     pub fn total_return_pct(&self) -> f64 {
-        if self.initial_capital > 0.0 {
-            ((self.total_equity() / self.initial_capital) - 1.0) * 100.0
+        if !self.initial_capital.is_zero() {
+            ((self.total_equity() / self.initial_capital.to_f64()) - 1.0) * 100.0
         } else {
             0.0
         }
@@ -321,13 +469,24 @@ impl Portfolio {
 
     /// Get max drawdown percentage
     pub fn max_drawdown_pct(&self) -> f64 {
-        if self.peak_equity > 0.0 {
-            (self.max_drawdown / self.peak_equity) * 100.0
+        if !self.peak_equity.is_zero() {
+            (self.max_drawdown.to_f64() / self.peak_equity.to_f64()) * 100.0
         } else {
             0.0
         }
     }
 
+    /// Mark `symbol`'s position as a leveraged perpetual, attaching margin
+    /// and maintenance-margin metadata so `update_market_value` derives its
+    /// P&L from `(mark - entry) * size` instead of cost-basis, and a
+    /// liquidation price is tracked. No-op if the symbol has no position
+    /// (e.g. it was closed in the same fill that would have opened it).
+    pub fn set_perp_leverage(&mut self, symbol: &str, margin: f64, leverage: f64, maintenance_margin_ratio: f64) {
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.open_perp(Amount::from_f64(margin), leverage, maintenance_margin_ratio);
+        }
+    }
+
     /// Get position for a symbol
     pub fn get_position(&self, symbol: &str) -> Option<&Position> {
         self.positions.get(symbol)
@@ -350,25 +509,25 @@ impl Portfolio {
 
     /// Get current cash
     pub fn cash(&self) -> f64 {
-        self.cash
+        self.cash.to_f64()
     }
 
     /// Generate portfolio update event
     pub fn generate_update_event(&mut self, timestamp: DateTime<Utc>) -> PortfolioUpdateEvent {
         self.event_id += 1;
-        
+
         let positions: Vec<(String, f64, f64)> = self.positions
             .values()
-            .map(|p| (p.symbol.clone(), p.quantity, p.average_price))
+            .map(|p| (p.symbol.clone(), p.quantity.to_f64(), p.average_price.to_f64()))
             .collect();
-        
+
         PortfolioUpdateEvent {
             id: self.event_id,
             timestamp,
-            cash: self.cash,
+            cash: self.cash(),
             equity: self.total_equity(),
             positions,
-            realized_pnl: self.total_realized_pnl,
+            realized_pnl: self.realized_pnl(),
             unrealized_pnl: self.unrealized_pnl(),
         }
     }
@@ -380,37 +539,37 @@ impl Portfolio {
             .collect();
         
         let winning_trades: Vec<_> = closed_trades.iter()
-            .filter(|t| t.pnl > 0.0)
+            .filter(|t| t.pnl.to_f64() > 0.0)
             .collect();
-        
+
         let losing_trades: Vec<_> = closed_trades.iter()
-            .filter(|t| t.pnl < 0.0)
+            .filter(|t| t.pnl.to_f64() < 0.0)
             .collect();
-        
+
         let total_trades = closed_trades.len();
         let winning_count = winning_trades.len();
         let losing_count = losing_trades.len();
-        
+
         let win_rate = if total_trades > 0 {
             (winning_count as f64 / total_trades as f64) * 100.0
         } else {
             0.0
         };
-        
+
         let avg_win = if winning_count > 0 {
-            winning_trades.iter().map(|t| t.pnl).sum::<f64>() / winning_count as f64
+            winning_trades.iter().map(|t| t.pnl.to_f64()).sum::<f64>() / winning_count as f64
         } else {
             0.0
         };
-        
+
         let avg_loss = if losing_count > 0 {
-            losing_trades.iter().map(|t| t.pnl.abs()).sum::<f64>() / losing_count as f64
+            losing_trades.iter().map(|t| t.pnl.abs().to_f64()).sum::<f64>() / losing_count as f64
         } else {
             0.0
         };
-        
-        let total_wins: f64 = winning_trades.iter().map(|t| t.pnl).sum();
-        let total_losses: f64 = losing_trades.iter().map(|t| t.pnl.abs()).sum();
+
+        let total_wins: f64 = winning_trades.iter().map(|t| t.pnl.to_f64()).sum();
+        let total_losses: f64 = losing_trades.iter().map(|t| t.pnl.abs().to_f64()).sum();
         
         let profit_factor = if total_losses > 0.0 {
             total_wins / total_losses
@@ -421,13 +580,24 @@ impl Portfolio {
         };
         
         let largest_win = winning_trades.iter()
-            .map(|t| t.pnl)
+            .map(|t| t.pnl.to_f64())
             .fold(0.0, f64::max);
-        
+
         let largest_loss = losing_trades.iter()
-            .map(|t| t.pnl.abs())
+            .map(|t| t.pnl.abs().to_f64())
             .fold(0.0, f64::max);
-        
+
+        let loss_rate = if total_trades > 0 {
+            (losing_count as f64 / total_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+        let expectancy = (win_rate / 100.0) * avg_win - (loss_rate / 100.0) * avg_loss;
+
+        let sqn = Self::calculate_sqn(&closed_trades);
+        let (max_consecutive_wins, max_consecutive_losses) = Self::consecutive_streaks(&closed_trades);
+        let average_holding_duration_secs = Self::average_holding_duration_secs(&closed_trades);
+
         TradeStats {
             total_trades,
             winning_trades: winning_count,
@@ -438,8 +608,73 @@ impl Portfolio {
             profit_factor,
             largest_win,
             largest_loss,
+            expectancy,
+            sqn,
+            max_consecutive_wins,
+            max_consecutive_losses,
+            average_holding_duration_secs,
         }
     }
+
+    /// System Quality Number: `sqrt(N) * mean(pnl) / std(pnl)` across
+    /// `closed_trades`, `0.0` with fewer than two trades or zero variance.
+    fn calculate_sqn(closed_trades: &[&TradeRecord]) -> f64 {
+        let n = closed_trades.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        let pnls: Vec<f64> = closed_trades.iter().map(|t| t.pnl.to_f64()).collect();
+        let mean = pnls.iter().sum::<f64>() / n as f64;
+        let variance = pnls.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / n as f64;
+        let std_dev = variance.sqrt();
+
+        if std_dev == 0.0 {
+            return 0.0;
+        }
+
+        (n as f64).sqrt() * mean / std_dev
+    }
+
+    /// Longest run of consecutive winning trades and losing trades, each
+    /// counted independently, walking `closed_trades` in entry order.
+    fn consecutive_streaks(closed_trades: &[&TradeRecord]) -> (usize, usize) {
+        let mut max_wins = 0usize;
+        let mut max_losses = 0usize;
+        let mut current_wins = 0usize;
+        let mut current_losses = 0usize;
+
+        for trade in closed_trades {
+            if trade.pnl.to_f64() > 0.0 {
+                current_wins += 1;
+                current_losses = 0;
+            } else if trade.pnl.to_f64() < 0.0 {
+                current_losses += 1;
+                current_wins = 0;
+            } else {
+                current_wins = 0;
+                current_losses = 0;
+            }
+            max_wins = max_wins.max(current_wins);
+            max_losses = max_losses.max(current_losses);
+        }
+
+        (max_wins, max_losses)
+    }
+
+    /// Mean of `exit_time - entry_time` across `closed_trades`, in seconds -
+    /// `0.0` if there are none.
+    fn average_holding_duration_secs(closed_trades: &[&TradeRecord]) -> f64 {
+        if closed_trades.is_empty() {
+            return 0.0;
+        }
+
+        let total_secs: i64 = closed_trades.iter()
+            .filter_map(|t| t.exit_time.map(|exit| (exit - t.entry_time).num_seconds()))
+            .sum();
+
+        total_secs as f64 / closed_trades.len() as f64
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -453,6 +688,19 @@ pub struct TradeStats {
     pub profit_factor: f64,
     pub largest_win: f64,
     pub largest_loss: f64,
+    /// Expected P&L per trade: `win_rate * average_win - loss_rate *
+    /// average_loss`.
+    pub expectancy: f64,
+    /// System Quality Number: `sqrt(N) * mean(trade_pnl) / std(trade_pnl)`,
+    /// Van Tharp's measure of how consistent the per-trade edge is - `0.0`
+    /// with fewer than two closed trades or a zero-variance PnL series.
+    pub sqn: f64,
+    /// Longest streak of consecutive winning trades, in entry order.
+    pub max_consecutive_wins: usize,
+    /// Longest streak of consecutive losing trades, in entry order.
+    pub max_consecutive_losses: usize,
+    /// Mean holding duration of closed trades, in seconds.
+    pub average_holding_duration_secs: f64,
 }
 
 impl Default for TradeStats {
@@ -467,6 +715,11 @@ impl Default for TradeStats {
             profit_factor: 0.0,
             largest_win: 0.0,
             largest_loss: 0.0,
+            expectancy: 0.0,
+            sqn: 0.0,
+            max_consecutive_wins: 0,
+            max_consecutive_losses: 0,
+            average_holding_duration_secs: 0.0,
         }
     }
 }
@@ -474,6 +727,7 @@ impl Default for TradeStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     fn create_buy_fill() -> FillEvent {
         FillEvent {
@@ -509,13 +763,13 @@ mod tests {
         let fill = create_buy_fill();
         
         portfolio.process_fill(&fill);
-        
-        assert!(portfolio.cash < 100000.0);
+
+        assert!(portfolio.cash() < 100000.0);
         assert!(portfolio.get_position("BTC/USD").is_some());
-        
+
         let position = portfolio.get_position("BTC/USD").unwrap();
-        assert_eq!(position.quantity, 1.0);
-        assert_eq!(position.average_price, 50000.0);
+        assert_eq!(position.quantity, Amount::from_f64(1.0));
+        assert_eq!(position.average_price, Amount::from_f64(50000.0));
     }
 
     #[test]
@@ -557,5 +811,142 @@ mod tests {
         // Equity should be ~100000 minus fees
         assert!(portfolio.total_equity() < 100000.0);
     }
+
+    #[test]
+    fn test_short_position_profits_when_price_falls() {
+        let mut portfolio = Portfolio::new(100000.0);
+
+        // Sell from flat opens a short
+        let sell = create_sell_fill(); // 1.0 @ 52000.0
+        portfolio.process_fill(&sell);
+
+        let position = portfolio.get_position("BTC/USD").unwrap();
+        assert_eq!(position.quantity, Amount::from_f64(-1.0));
+        assert_eq!(position.average_price, Amount::from_f64(52000.0));
+
+        // Cover at a lower price - a short profits on the way down
+        let cover = create_buy_fill(); // 1.0 @ 50000.0
+        portfolio.process_fill(&cover);
+
+        let position = portfolio.get_position("BTC/USD").unwrap();
+        assert!(position.is_flat());
+        assert!(portfolio.realized_pnl() > 0.0);
+    }
+
+    #[test]
+    fn test_buy_through_short_flips_to_long() {
+        let mut portfolio = Portfolio::new(100000.0);
+
+        let sell = create_sell_fill(); // -1.0 @ 52000.0
+        portfolio.process_fill(&sell);
+
+        let mut cover_and_flip = create_buy_fill();
+        cover_and_flip.quantity = 3.0; // covers 1.0 short, opens 2.0 long
+        portfolio.process_fill(&cover_and_flip);
+
+        let position = portfolio.get_position("BTC/USD").unwrap();
+        assert_eq!(position.quantity, Amount::from_f64(2.0));
+        assert_eq!(position.average_price, Amount::from_f64(50000.0));
+    }
+
+    #[test]
+    fn test_short_round_trip_closes_trade_record_with_correct_pnl() {
+        let mut portfolio = Portfolio::new(100000.0);
+
+        // Opening a short via Sell must journal an "open" trade, not get
+        // mistaken for "closing a long" - there's nothing open yet.
+        let sell = create_sell_fill(); // -1.0 @ 52000.0
+        portfolio.process_fill(&sell);
+
+        let trades = portfolio.trades();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].side, "short");
+        assert_eq!(trades[0].status, "open");
+
+        // Covering it with a Buy must flip the record to "closed" rather
+        // than averaging it in as if adding to a long.
+        let cover = create_buy_fill(); // 1.0 @ 50000.0
+        portfolio.process_fill(&cover);
+
+        let trades = portfolio.trades();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].status, "closed");
+        assert!(trades[0].pnl.to_f64() > 0.0, "covering a short at a lower price should be profitable");
+
+        let stats = portfolio.trade_stats();
+        assert_eq!(stats.total_trades, 1);
+        assert_eq!(stats.winning_trades, 1);
+        assert_eq!(stats.losing_trades, 0);
+    }
+
+    #[test]
+    fn test_buy_through_short_flip_splits_trade_record() {
+        let mut portfolio = Portfolio::new(100000.0);
+
+        let sell = create_sell_fill(); // -1.0 @ 52000.0
+        portfolio.process_fill(&sell);
+
+        let mut cover_and_flip = create_buy_fill();
+        cover_and_flip.quantity = 3.0; // covers 1.0 short, opens 2.0 long
+        portfolio.process_fill(&cover_and_flip);
+
+        // The short closes as its own completed trade, and the 2.0 excess
+        // opens a brand-new long trade - not a single mis-averaged record.
+        let trades = portfolio.trades();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].side, "short");
+        assert_eq!(trades[0].status, "closed");
+        assert_eq!(trades[1].side, "long");
+        assert_eq!(trades[1].status, "open");
+        assert_eq!(trades[1].quantity, Amount::from_f64(2.0));
+        assert_eq!(trades[1].entry_price, Amount::from_f64(50000.0));
+
+        let stats = portfolio.trade_stats();
+        assert_eq!(stats.total_trades, 1, "only the closed short counts toward trade_stats so far");
+    }
+
+    #[test]
+    fn test_trade_stats_streaks_and_edge_diagnostics() {
+        let mut portfolio = Portfolio::new(100000.0);
+        let mut next_id = 0u64;
+
+        // Two winning round trips, then one loser: streak of 2 wins, then 1 loss.
+        for &(buy_price, sell_price) in &[(100.0, 110.0), (100.0, 105.0), (100.0, 90.0)] {
+            next_id += 1;
+            portfolio.process_fill(&FillEvent {
+                id: next_id,
+                order_id: 0,
+                timestamp: Utc::now(),
+                symbol: "BTC/USD".to_string(),
+                side: Side::Buy,
+                quantity: 1.0,
+                fill_price: buy_price,
+                commission: 0.0,
+                slippage: 0.0,
+            });
+            next_id += 1;
+            portfolio.process_fill(&FillEvent {
+                id: next_id,
+                order_id: 0,
+                timestamp: Utc::now() + Duration::hours(1),
+                symbol: "BTC/USD".to_string(),
+                side: Side::Sell,
+                quantity: 1.0,
+                fill_price: sell_price,
+                commission: 0.0,
+                slippage: 0.0,
+            });
+        }
+
+        let stats = portfolio.trade_stats();
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.winning_trades, 2);
+        assert_eq!(stats.losing_trades, 1);
+        assert_eq!(stats.max_consecutive_wins, 2);
+        assert_eq!(stats.max_consecutive_losses, 1);
+        assert!(stats.average_holding_duration_secs > 0.0);
+        assert!(stats.expectancy > 0.0, "wins of 10 and 5 should outweigh one loss of 10");
+        assert!(stats.sqn != 0.0);
+    }
 }
 
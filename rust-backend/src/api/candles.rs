@@ -0,0 +1,47 @@
+/// OHLCV candle query route over `CandleStore`.
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::services::swap_stream::{Candle, Interval};
+use crate::AppState;
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/candles/:pair/:interval", get(get_candles))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CandlesQueryParams {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize { 200 }
+
+#[derive(Debug, Serialize)]
+pub struct CandlesResponse {
+    pub pair: String,
+    pub interval: String,
+    pub candles: Vec<Candle>,
+}
+
+async fn get_candles(
+    State(state): State<AppState>,
+    Path((pair, interval)): Path<(String, String)>,
+    Query(params): Query<CandlesQueryParams>,
+) -> Result<Json<CandlesResponse>, String> {
+    let parsed_interval = Interval::parse(&interval)
+        .ok_or_else(|| format!("Unknown interval '{}' - expected one of 1m, 5m, 1h", interval))?;
+
+    let candles = state.candles.query(&pair, parsed_interval, params.limit);
+
+    Ok(Json(CandlesResponse {
+        pair,
+        interval: parsed_interval.as_str().to_string(),
+        candles,
+    }))
+}
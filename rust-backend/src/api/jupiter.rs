@@ -9,10 +9,11 @@ use axum::{
     extract::State,
 };
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::{info, warn};
 
 use crate::AppState;
-use crate::jupiter::{JupiterClient, tokens};
+use crate::jupiter::{tokens, JupiterClient, QuoteRequest, SwapMode};
 
 /// Create Jupiter API routes
 pub fn create_routes() -> Router<AppState> {
@@ -27,9 +28,19 @@ pub fn create_routes() -> Router<AppState> {
 pub struct QuoteParams {
     pub input_mint: String,
     pub output_mint: String,
+    /// Amount in the input mint's smallest unit for `ExactIn`, or in the
+    /// output mint's smallest unit for `ExactOut`.
     pub amount: String,
     #[serde(default = "default_slippage")]
     pub slippage_bps: u16,
+    /// Quote a fixed input amount (default) or solve for the input required
+    /// to produce a fixed output amount.
+    #[serde(default)]
+    pub swap_mode: SwapMode,
+    /// When true, ignore `slippage_bps` and let Jupiter compute slippage
+    /// dynamically; the chosen value comes back on `slippage_bps` below.
+    #[serde(default)]
+    pub auto_slippage: bool,
 }
 
 fn default_slippage() -> u16 { 50 } // 0.5%
@@ -43,6 +54,9 @@ pub struct QuoteResult {
     pub out_amount: String,
     pub min_out_amount: String,
     pub price_impact_pct: String,
+    /// The slippage actually used for this quote - the caller's
+    /// `slippage_bps`, or Jupiter's computed value under `auto_slippage`.
+    pub slippage_bps: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -56,11 +70,21 @@ pub struct SwapParams {
     pub user_public_key: String,
     pub input_mint: String,
     pub output_mint: String,
+    /// Amount in the input mint's smallest unit for `ExactIn`, or in the
+    /// output mint's smallest unit for `ExactOut`.
     pub amount: String,
     #[serde(default = "default_slippage")]
     pub slippage_bps: u16,
     #[serde(default)]
     pub priority_fee_lamports: Option<u64>,
+    /// Quote a fixed input amount (default) or solve for the input required
+    /// to produce a fixed output amount.
+    #[serde(default)]
+    pub swap_mode: SwapMode,
+    /// When true, ignore `slippage_bps` and let Jupiter compute slippage
+    /// dynamically; the chosen value comes back on `slippage_bps` below.
+    #[serde(default)]
+    pub auto_slippage: bool,
 }
 
 /// Swap response to frontend
@@ -75,11 +99,75 @@ pub struct SwapResult {
     pub in_amount: String,
     pub out_amount: String,
     pub price_impact_pct: String,
+    /// The slippage actually used to build `transaction` - the caller's
+    /// `slippage_bps`, or Jupiter's computed value under `auto_slippage`.
+    pub slippage_bps: u16,
+    /// The priority fee actually baked into `transaction`, whether supplied
+    /// by the caller or auto-computed by `PriorityFeeService`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_fee_lamports: Option<u64>,
+    /// `PriorityFeeService`'s congestion-aware estimate before any
+    /// account-scoped refinement, surfaced so the frontend can show the
+    /// user what they're paying and why.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_fee_lamports: Option<u64>,
+}
+
+/// Rough USD value of the swap, used only to pick a priority-fee tier via
+/// `PriorityFeeService::get_fee_for_amount`. Stablecoin legs are used
+/// directly (1 unit ~= 1 USD); a SOL leg falls back to a fixed approximate
+/// price since this endpoint has no live price oracle.
+const APPROX_SOL_PRICE_USD: f64 = 150.0;
+
+fn estimate_trade_usd(quote: &crate::jupiter::QuoteResponse) -> f64 {
+    stablecoin_amount_usd(&quote.output_mint, quote.out_amount)
+        .or_else(|| stablecoin_amount_usd(&quote.input_mint, quote.in_amount))
+        .or_else(|| sol_amount_usd(&quote.input_mint, quote.in_amount))
+        .or_else(|| sol_amount_usd(&quote.output_mint, quote.out_amount))
+        .unwrap_or(0.0)
+}
+
+fn stablecoin_amount_usd(mint: &str, amount: u64) -> Option<f64> {
+    if mint == tokens::USDC || mint == tokens::USDT {
+        Some(amount as f64 / 1_000_000.0) // 6 decimals
+    } else {
+        None
+    }
+}
+
+fn sol_amount_usd(mint: &str, amount: u64) -> Option<f64> {
+    if mint == tokens::WSOL {
+        Some((amount as f64 / 1_000_000_000.0) * APPROX_SOL_PRICE_USD)
+    } else {
+        None
+    }
+}
+
+/// Decode a base64 Jupiter swap transaction and pull out the accounts it
+/// write-locks, so the caller can price priority fees against the actual
+/// pools/token accounts the route touches. Returns `None` on any decode
+/// failure - the caller just falls back to the fee it already has.
+fn writable_accounts_of(swap_transaction_b64: &str) -> Option<Vec<solana_sdk::pubkey::Pubkey>> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    let tx_bytes = STANDARD.decode(swap_transaction_b64).ok()?;
+    let tx: VersionedTransaction = bincode::deserialize(&tx_bytes).ok()?;
+    let message = &tx.message;
+
+    Some(
+        message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| message.is_maybe_writable(*i))
+            .map(|(_, key)| *key)
+            .collect(),
+    )
 }
 
 /// GET /jupiter/quote
 async fn get_quote(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<QuoteParams>,
 ) -> Json<QuoteResult> {
     info!(
@@ -87,8 +175,8 @@ async fn get_quote(
         params.amount, params.input_mint, params.output_mint
     );
 
-    let client = JupiterClient::new();
-    
+    let client = JupiterClient::with_mock(state.config.mock_jupiter).with_metrics(state.metrics.clone());
+
     // Parse amount
     let amount: u64 = match params.amount.parse() {
         Ok(a) => a,
@@ -99,18 +187,16 @@ async fn get_quote(
                 out_amount: "0".to_string(),
                 min_out_amount: "0".to_string(),
                 price_impact_pct: "0".to_string(),
+                slippage_bps: params.slippage_bps,
                 error: Some("Invalid amount".to_string()),
                 route_info: None,
             });
         }
     };
 
-    match client.get_quote(
-        &params.input_mint,
-        &params.output_mint,
-        amount,
-        params.slippage_bps,
-    ).await {
+    let request = quote_request(&params.input_mint, &params.output_mint, amount, params.slippage_bps, params.swap_mode, params.auto_slippage);
+
+    match client.get_quote_advanced(request).await {
         Ok(quote) => {
             let route_label = quote.route_plan
                 .first()
@@ -119,10 +205,11 @@ async fn get_quote(
 
             Json(QuoteResult {
                 success: true,
-                in_amount: quote.in_amount,
-                out_amount: quote.out_amount.clone(),
-                min_out_amount: quote.other_amount_threshold,
-                price_impact_pct: quote.price_impact_pct,
+                in_amount: quote.in_amount.to_string(),
+                out_amount: quote.out_amount.to_string(),
+                min_out_amount: quote.min_output().to_string(),
+                price_impact_pct: quote.price_impact().to_string(),
+                slippage_bps: quote.slippage_bps,
                 error: None,
                 route_info: Some(route_label),
             })
@@ -134,6 +221,7 @@ async fn get_quote(
                 out_amount: "0".to_string(),
                 min_out_amount: "0".to_string(),
                 price_impact_pct: "0".to_string(),
+                slippage_bps: params.slippage_bps,
                 error: Some(e.to_string()),
                 route_info: None,
             })
@@ -141,9 +229,32 @@ async fn get_quote(
     }
 }
 
+/// Build a `QuoteRequest`, wiring `auto_slippage` to omit the fixed
+/// `slippage_bps` since Jupiter computes it when auto slippage is on.
+fn quote_request(
+    input_mint: &str,
+    output_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+    swap_mode: SwapMode,
+    auto_slippage: bool,
+) -> QuoteRequest {
+    QuoteRequest {
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        amount,
+        slippage_bps: if auto_slippage { None } else { Some(slippage_bps) },
+        only_direct_routes: None,
+        as_legacy_transaction: None,
+        max_accounts: None,
+        swap_mode: Some(swap_mode),
+        auto_slippage: if auto_slippage { Some(true) } else { None },
+    }
+}
+
 /// POST /jupiter/swap
 async fn prepare_swap(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(params): Json<SwapParams>,
 ) -> Json<SwapResult> {
     info!(
@@ -151,7 +262,7 @@ async fn prepare_swap(
         params.amount, params.input_mint, params.output_mint, params.user_public_key
     );
 
-    let client = JupiterClient::new();
+    let client = JupiterClient::with_mock(state.config.mock_jupiter).with_metrics(state.metrics.clone());
 
     // Parse amount
     let amount: u64 = match params.amount.parse() {
@@ -164,17 +275,18 @@ async fn prepare_swap(
                 in_amount: params.amount,
                 out_amount: "0".to_string(),
                 price_impact_pct: "0".to_string(),
+                slippage_bps: params.slippage_bps,
+                priority_fee_lamports: None,
+                estimated_fee_lamports: None,
             });
         }
     };
 
-    // 1. Get quote
-    let quote = match client.get_quote(
-        &params.input_mint,
-        &params.output_mint,
-        amount,
-        params.slippage_bps,
-    ).await {
+    // 1. Get quote. Same price-impact guard below applies whether this is
+    // an ExactIn or ExactOut quote - Jupiter reports `price_impact_pct`
+    // either way.
+    let request = quote_request(&params.input_mint, &params.output_mint, amount, params.slippage_bps, params.swap_mode, params.auto_slippage);
+    let quote = match client.get_quote_advanced(request).await {
         Ok(q) => q,
         Err(e) => {
             return Json(SwapResult {
@@ -184,28 +296,53 @@ async fn prepare_swap(
                 in_amount: params.amount,
                 out_amount: "0".to_string(),
                 price_impact_pct: "0".to_string(),
+                slippage_bps: params.slippage_bps,
+                priority_fee_lamports: None,
+                estimated_fee_lamports: None,
             });
         }
     };
 
     // Check price impact
-    let impact: f64 = quote.price_impact_pct.parse().unwrap_or(0.0);
+    let impact = quote.price_impact();
     if impact > 10.0 {
         return Json(SwapResult {
             success: false,
             transaction: None,
             error: Some(format!("Price impact too high: {}%", impact)),
-            in_amount: quote.in_amount,
-            out_amount: quote.out_amount,
-            price_impact_pct: quote.price_impact_pct,
+            in_amount: quote.in_amount.to_string(),
+            out_amount: quote.out_amount.to_string(),
+            price_impact_pct: quote.price_impact_pct.to_string(),
+            slippage_bps: quote.slippage_bps,
+            priority_fee_lamports: None,
+            estimated_fee_lamports: None,
         });
     }
 
-    // 2. Get swap transaction
-    let swap_response = match client.get_swap_transaction(
+    // When the caller hasn't pinned a fee, self-tune one from
+    // `PriorityFeeService` instead of leaving it for Jupiter to guess.
+    let estimated_fee_lamports = if params.priority_fee_lamports.is_none() {
+        let amount_usd = estimate_trade_usd(&quote);
+        match state.priority_fee.get_fee_for_amount(amount_usd).await {
+            Ok(fee) => Some(fee),
+            Err(e) => {
+                warn!("Failed to estimate priority fee for swap: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut priority_fee_lamports = params.priority_fee_lamports.or(estimated_fee_lamports);
+
+    // 2. Get swap transaction. When the caller hasn't pinned a fee, rebuild
+    // once we know which accounts the route write-locks, so the final fee
+    // reflects contention on exactly those accounts (the pools and token
+    // accounts) rather than just the trade-size tier above.
+    let mut swap_response = match client.get_swap_transaction(
         &params.user_public_key,
         &quote,
-        params.priority_fee_lamports,
+        priority_fee_lamports,
     ).await {
         Ok(s) => s,
         Err(e) => {
@@ -213,19 +350,42 @@ async fn prepare_swap(
                 success: false,
                 transaction: None,
                 error: Some(format!("Swap build failed: {}", e)),
-                in_amount: quote.in_amount,
-                out_amount: quote.out_amount,
-                price_impact_pct: quote.price_impact_pct,
+                in_amount: quote.in_amount.to_string(),
+                out_amount: quote.out_amount.to_string(),
+                price_impact_pct: quote.price_impact_pct.to_string(),
+                slippage_bps: quote.slippage_bps,
+                priority_fee_lamports: None,
+                estimated_fee_lamports,
             });
         }
     };
 
+    if params.priority_fee_lamports.is_none() {
+        if let Some(writable_accounts) = writable_accounts_of(&swap_response.swap_transaction) {
+            match state.priority_fee.get_fee_for_accounts(&writable_accounts).await {
+                Ok(fee) => {
+                    match client.get_swap_transaction(&params.user_public_key, &quote, Some(fee)).await {
+                        Ok(rebuilt) => {
+                            swap_response = rebuilt;
+                            priority_fee_lamports = Some(fee);
+                        }
+                        Err(e) => warn!("Failed to rebuild swap with account-scoped priority fee: {}", e),
+                    }
+                }
+                Err(e) => warn!("Failed to compute account-scoped priority fee: {}", e),
+            }
+        }
+    }
+
     Json(SwapResult {
         success: true,
         transaction: Some(swap_response.swap_transaction),
         error: None,
-        in_amount: quote.in_amount,
-        out_amount: quote.out_amount,
-        price_impact_pct: quote.price_impact_pct,
+        in_amount: quote.in_amount.to_string(),
+        out_amount: quote.out_amount.to_string(),
+        price_impact_pct: quote.price_impact_pct.to_string(),
+        slippage_bps: quote.slippage_bps,
+        priority_fee_lamports,
+        estimated_fee_lamports,
     })
 }
@@ -0,0 +1,18 @@
+/// AMM-replication ladder routes over `ConditionalOrderEngine`.
+use axum::{routing::post, Json, Router};
+use axum::extract::State;
+
+use crate::services::conditional_orders::ConditionalOrder;
+use crate::services::market_maker::{self, LadderRequest};
+use crate::AppState;
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/market-maker/ladder", post(place_ladder))
+}
+
+async fn place_ladder(
+    State(state): State<AppState>,
+    Json(req): Json<LadderRequest>,
+) -> Result<Json<Vec<ConditionalOrder>>, String> {
+    Ok(Json(market_maker::place_ladder(&state.conditional_orders, req)))
+}
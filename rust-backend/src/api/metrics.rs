@@ -0,0 +1,24 @@
+/// Prometheus Metrics Route
+///
+/// Exposes `telemetry::Metrics` in Prometheus text exposition format so an
+/// operator can scrape submission/quote latency and success rates without
+/// going through any of the JSON API routes.
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+use crate::AppState;
+
+/// Create metrics API routes
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/metrics", get(get_metrics))
+}
+
+/// GET /metrics
+/// Prometheus text exposition of submission/quote latency histograms and
+/// success/failure counters across every instrumented provider.
+async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render_prometheus().await,
+    )
+}
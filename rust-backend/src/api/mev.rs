@@ -12,9 +12,16 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
+use crate::jupiter::types::tokens::WSOL;
+use crate::jupiter::{JupiterClient, SwapMode};
+use crate::services::honeypot_analyzer::fee_bps_from_quote;
+use crate::services::jito_bundle::{EndpointLatencyStats, JitoBundleClient};
+use crate::services::sandwich_detector::{PoolState, SandwichSeverity};
 use crate::AppState;
-use crate::services::jito_bundle::JitoBundleClient;
-use crate::services::sandwich_detector::{SandwichDetector, SandwichSeverity};
+
+/// Slippage tolerance used only to price the quote this route derives a
+/// [`PoolState`] from - not an actual swap.
+const POOL_QUOTE_SLIPPAGE_BPS: u16 = 100;
 
 /// Create MEV protection API routes
 pub fn create_routes() -> Router<AppState> {
@@ -22,6 +29,7 @@ pub fn create_routes() -> Router<AppState> {
         .route("/mev/analyze", post(analyze_sandwich_risk))
         .route("/mev/tip-accounts", get(get_tip_accounts))
         .route("/mev/protection-advice", get(get_protection_advice))
+        .route("/jito/latency", get(get_jito_latency))
 }
 
 /// Sandwich risk analysis request
@@ -66,7 +74,7 @@ pub struct ProtectionAdvice {
 /// POST /mev/analyze
 /// Analyze sandwich attack risk for a planned transaction
 async fn analyze_sandwich_risk(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(params): Json<AnalyzeRequest>,
 ) -> Json<AnalyzeResponse> {
     info!(
@@ -76,16 +84,27 @@ async fn analyze_sandwich_risk(
         params.amount_lamports
     );
 
-    // Create a new detector for this analysis
-    // In production, this would use a shared detector with historical data
-    let detector = SandwichDetector::new();
-    
-    // Analyze risk
-    let alert = detector.analyze_sandwich_risk(
-        &params.token_mint,
-        params.amount_lamports,
-        params.is_buy,
-    );
+    let started = std::time::Instant::now();
+
+    // Register interest so `SandwichMempoolFeed` starts delivering this
+    // mint's transactions, then read the shared detector it populates -
+    // real front-running history instead of a fresh, empty detector.
+    state.sandwich_mints.write().await.insert(params.token_mint.clone());
+    let pool = estimate_pool_state(&params).await;
+    let alert = {
+        let detector = state.sandwich_detector.lock().await;
+        match &pool {
+            Some(pool) => detector.analyze_sandwich_risk_with_pool(
+                &params.token_mint,
+                params.amount_lamports,
+                params.is_buy,
+                pool,
+            ),
+            None => detector.analyze_sandwich_risk(&params.token_mint, params.amount_lamports, params.is_buy),
+        }
+    };
+
+    state.rpc.latency_metrics().record("sandwich_analysis", started.elapsed()).await;
 
     match alert {
         Some(alert) => {
@@ -128,12 +147,32 @@ async fn analyze_sandwich_risk(
     }
 }
 
+/// Derive a constant-product [`PoolState`] for `params`'s token from a fresh
+/// Jupiter quote, so `analyze_sandwich_risk` can use
+/// `analyze_sandwich_risk_with_pool`'s AMM-simulated profit estimate instead
+/// of the flat slippage heuristic. Only meaningful on the buy side -
+/// `analyze_sandwich_risk_with_pool` ignores `pool` for sells - so returns
+/// `None` without a quote round trip otherwise, or if the quote fails or its
+/// price impact is too small to invert into reserves.
+async fn estimate_pool_state(params: &AnalyzeRequest) -> Option<PoolState> {
+    if !params.is_buy {
+        return None;
+    }
+
+    let quote = JupiterClient::new()
+        .get_quote(WSOL, &params.token_mint, params.amount_lamports, POOL_QUOTE_SLIPPAGE_BPS, SwapMode::ExactIn)
+        .await
+        .ok()?;
+    let fee_bps = fee_bps_from_quote(&quote);
+    PoolState::from_quote(params.amount_lamports, quote.out_amount, quote.price_impact(), fee_bps)
+}
+
 /// GET /mev/tip-accounts
 /// Get Jito tip accounts for bundle submission
 async fn get_tip_accounts(
     State(_state): State<AppState>,
 ) -> Json<TipAccountsResponse> {
-    let mut client = JitoBundleClient::new();
+    let client = JitoBundleClient::new();
     let recommended = client.get_tip_account().to_string();
 
     Json(TipAccountsResponse {
@@ -145,6 +184,13 @@ async fn get_tip_accounts(
     })
 }
 
+/// GET /jito/latency
+/// Per-region p50/p90/p99 submit latency, so operators can see which Jito
+/// block-engine region the router is currently favoring.
+async fn get_jito_latency(State(state): State<AppState>) -> Json<Vec<EndpointLatencyStats>> {
+    Json(state.jito.latency_stats().await)
+}
+
 /// GET /mev/protection-advice
 /// Get general MEV protection advice
 async fn get_protection_advice(
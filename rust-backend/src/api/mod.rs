@@ -3,11 +3,16 @@ mod turnkey;
 mod sse;
 mod websocket;
 pub mod pump_fun;
-mod tokens;
+pub mod tokens;
 mod jupiter;
 mod mev;
 mod rpc;
 mod presets;
+mod pubsub;
+mod metrics;
+mod candles;
+mod orders;
+mod market_maker;
 
 use axum::Router;
 use crate::AppState;
@@ -24,5 +29,10 @@ pub fn create_router() -> Router<AppState> {
         .merge(mev::create_routes())
         .merge(rpc::create_routes())
         .merge(presets::create_routes())
+        .merge(pubsub::create_routes())
+        .merge(metrics::create_routes())
+        .merge(candles::create_routes())
+        .merge(orders::create_routes())
+        .merge(market_maker::create_routes())
 }
 
@@ -0,0 +1,44 @@
+/// Conditional (limit/stop-loss) order REST routes over
+/// `ConditionalOrderEngine`.
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::conditional_orders::{ConditionalOrder, PlaceOrderRequest};
+use crate::AppState;
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orders/conditional", post(place_order).get(list_orders))
+        .route("/orders/conditional/:id", delete(cancel_order))
+}
+
+async fn place_order(
+    State(state): State<AppState>,
+    Json(req): Json<PlaceOrderRequest>,
+) -> Result<Json<ConditionalOrder>, String> {
+    Ok(Json(state.conditional_orders.place_order(req)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListOrdersQueryParams {
+    pub owner_pubkey: Option<String>,
+}
+
+async fn list_orders(
+    State(state): State<AppState>,
+    Query(params): Query<ListOrdersQueryParams>,
+) -> Result<Json<Vec<ConditionalOrder>>, String> {
+    Ok(Json(state.conditional_orders.list_orders(params.owner_pubkey.as_deref())))
+}
+
+async fn cancel_order(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<()>, String> {
+    state.conditional_orders.cancel_order(id)?;
+    Ok(Json(()))
+}
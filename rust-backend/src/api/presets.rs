@@ -12,8 +12,14 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
+use crate::jupiter::{swap::prepare_swap_for_signing, tokens, JupiterClient, SwapMode};
+use crate::services::priority_fee::FeeLevel;
 use crate::AppState;
 
+/// Lamports per SOL, for converting a preset's `sol_amount` into the units
+/// Jupiter quotes in.
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
 /// Create trading presets routes
 pub fn create_routes() -> Router<AppState> {
     Router::new()
@@ -105,7 +111,7 @@ async fn get_default_presets(
 /// POST /presets/execute
 /// Execute a trading preset and return the unsigned transaction
 async fn execute_preset(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(request): Json<ExecutePresetRequest>,
 ) -> Json<ExecutePresetResponse> {
     info!(
@@ -133,22 +139,67 @@ async fn execute_preset(
         }
     };
 
-    // In production, this would:
-    // 1. Get a quote from Jupiter
-    // 2. Build the swap transaction
-    // 3. Add priority fee
-    // 4. Optionally wrap in Jito bundle for MEV protection
-    // 5. Return the unsigned transaction for frontend signing
+    // Buy quotes a fixed `sol_amount` of SOL in for the token; sell quotes a
+    // fixed `sol_amount` of SOL out, solving for however much of the token
+    // that costs - both read as "trade roughly this much SOL" to the user.
+    let sol_amount_lamports = (preset.sol_amount * LAMPORTS_PER_SOL).round() as u64;
+    let (input_mint, output_mint, swap_mode) = match request.action {
+        TradeAction::Buy => (tokens::WSOL.to_string(), request.token_mint.clone(), SwapMode::ExactIn),
+        TradeAction::Sell => (request.token_mint.clone(), tokens::WSOL.to_string(), SwapMode::ExactOut),
+    };
 
-    // For now, return a placeholder response
-    Json(ExecutePresetResponse {
-        success: true,
-        transaction_base64: Some("PLACEHOLDER_TRANSACTION".to_string()),
-        estimated_output: Some(preset.sol_amount * 0.99), // Simulated 1% fee
-        price_impact_percent: Some(0.5),
-        mev_protected: preset.use_mev_protection,
-        error: None,
-    })
+    // MEV-protected presets (sniper/protected tiers) want to win the block
+    // even under congestion, so they consult `Turbo`; everything else is
+    // fine outbidding the median recent fee. Either way this replaces the
+    // preset's hardcoded `priority_fee_lamports`, which over/underbids as
+    // network conditions drift from whenever the preset table was tuned.
+    let fee_level = if preset.use_mev_protection {
+        FeeLevel::Turbo
+    } else {
+        FeeLevel::Normal
+    };
+    let priority_fee_lamports = state
+        .priority_fee
+        .estimate(&[], fee_level)
+        .await
+        .unwrap_or(preset.priority_fee_lamports);
+
+    let jupiter = JupiterClient::with_mock(state.config.mock_jupiter).with_metrics(state.metrics.clone());
+    let result = prepare_swap_for_signing(
+        &jupiter,
+        &request.user_public_key,
+        &input_mint,
+        &output_mint,
+        sol_amount_lamports,
+        preset.slippage_bps as u16,
+        swap_mode,
+        Some(priority_fee_lamports),
+    )
+    .await;
+
+    match result {
+        Ok((transaction_base64, quote)) => {
+            // Sell's out_amount is SOL lamports; buy's is the token in its
+            // own decimals, which this endpoint doesn't know - report the
+            // raw quoted amount either way rather than guess a conversion.
+            Json(ExecutePresetResponse {
+                success: true,
+                transaction_base64: Some(transaction_base64),
+                estimated_output: Some(quote.out_amount as f64),
+                price_impact_percent: Some(quote.price_impact()),
+                mev_protected: preset.use_mev_protection,
+                error: None,
+            })
+        }
+        Err(e) => Json(ExecutePresetResponse {
+            success: false,
+            transaction_base64: None,
+            estimated_output: None,
+            price_impact_percent: None,
+            mev_protected: preset.use_mev_protection,
+            error: Some(format!("Failed to build swap transaction: {}", e)),
+        }),
+    }
 }
 
 /// Get built-in trading presets
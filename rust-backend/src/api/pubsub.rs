@@ -0,0 +1,135 @@
+/// WebSocket pub/sub endpoint
+///
+/// Accepts a JSON-RPC-style `{"method":"subscribe","params":["graduations"]}`
+/// (or `["bundleStatus", "<bundle_id>"]`) request per subscription, allocates
+/// a subscription id, and forwards every notification from the shared
+/// [`crate::services::pubsub::PubSubHub`] as a JSON frame.
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::services::pubsub::SubscriptionKind;
+use crate::AppState;
+
+pub fn create_routes() -> Router<AppState> {
+    Router::new().route("/ws", get(pubsub_handler))
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscribeRequest {
+    method: String,
+    params: Vec<String>,
+}
+
+async fn pubsub_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+fn parse_subscription(req: &SubscribeRequest) -> Result<SubscriptionKind, String> {
+    if req.method != "subscribe" {
+        return Err(format!("unsupported method: {}", req.method));
+    }
+
+    match req.params.first().map(String::as_str) {
+        Some("graduations") => Ok(SubscriptionKind::GraduationEvents),
+        Some("bundleStatus") => {
+            let bundle_id = req
+                .params
+                .get(1)
+                .cloned()
+                .ok_or_else(|| "bundleStatus subscription requires a bundle_id param".to_string())?;
+            Ok(SubscriptionKind::BundleStatus { bundle_id })
+        }
+        other => Err(format!("unknown subscription topic: {:?}", other)),
+    }
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    // A single `/ws` connection may only host one active subscription at a
+    // time; callers that want both topics open two connections, matching the
+    // one-stream-per-socket shape of `eth_subscribe` over a single transport.
+    let Some(Ok(Message::Text(text))) = socket.next().await else {
+        return;
+    };
+
+    let subscribe_request: SubscribeRequest = match serde_json::from_str(&text) {
+        Ok(req) => req,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(
+                    serde_json::json!({ "error": format!("invalid subscribe request: {}", e) })
+                        .to_string(),
+                ))
+                .await;
+            return;
+        }
+    };
+
+    let kind = match parse_subscription(&subscribe_request) {
+        Ok(kind) => kind,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(serde_json::json!({ "error": e }).to_string()))
+                .await;
+            return;
+        }
+    };
+
+    match kind {
+        SubscriptionKind::GraduationEvents => {
+            let mut stream = state.pubsub.subscribe_graduations();
+            let subscription_id = stream.subscription_id();
+            if send_ack(&mut socket, subscription_id).await.is_err() {
+                return;
+            }
+            while let Some(notification) = stream.next().await {
+                if forward(&mut socket, &notification).await.is_err() {
+                    break;
+                }
+            }
+        }
+        SubscriptionKind::BundleStatus { bundle_id } => {
+            state.pubsub.track_bundle(bundle_id.clone());
+            let mut stream = state.pubsub.subscribe_bundle_status(bundle_id);
+            let subscription_id = stream.subscription_id();
+            if send_ack(&mut socket, subscription_id).await.is_err() {
+                return;
+            }
+            while let Some(notification) = stream.next().await {
+                if forward(&mut socket, &notification).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn send_ack(socket: &mut WebSocket, subscription_id: u64) -> Result<(), axum::Error> {
+    socket
+        .send(Message::Text(
+            serde_json::json!({ "subscription_id": subscription_id }).to_string(),
+        ))
+        .await
+}
+
+async fn forward<T: serde::Serialize>(
+    socket: &mut WebSocket,
+    notification: &T,
+) -> Result<(), axum::Error> {
+    match serde_json::to_string(notification) {
+        Ok(json) => socket.send(Message::Text(json)).await,
+        Err(e) => {
+            warn!("Failed to serialize pubsub notification: {}", e);
+            Ok(())
+        }
+    }
+}
@@ -6,6 +6,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
+use crate::services::upstream_guard::PUMP_FUN_CACHE_TTL;
 use crate::AppState;
 
 pub fn create_routes() -> Router<AppState> {
@@ -61,9 +62,59 @@ pub struct PumpFunResponse {
     pub count: usize,
 }
 
+/// Pump.fun sometimes wraps the coin list in an object instead of returning
+/// the bare array; `parse_pump_fun_coins` falls back to this shape.
+#[derive(Debug, Deserialize)]
+struct WrappedPumpFunCoins {
+    coins: Vec<PumpFunCoin>,
+}
+
+/// Error from fetching or decoding a Pump.fun response, so callers can tell
+/// "upstream didn't respond" apart from "upstream responded with a shape we
+/// don't understand" instead of matching on a formatted string.
+#[derive(Debug)]
+pub enum PumpFunError {
+    /// `UpstreamGuard` exhausted its retry/stale-fallback path.
+    Upstream(anyhow::Error),
+    /// The body was valid JSON but matched neither the bare-array nor the
+    /// `{ "coins": [...] }` shape.
+    UnexpectedShape,
+}
+
+impl std::fmt::Display for PumpFunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PumpFunError::Upstream(e) => write!(f, "Pump.fun upstream fetch failed: {}", e),
+            PumpFunError::UnexpectedShape => {
+                write!(f, "Pump.fun response matched neither the array nor {{coins:[...]}} shape")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PumpFunError {}
+
+/// Accepts either a bare `[...]` array (the documented shape) or a
+/// `{ "coins": [...] }` wrapper, since the upstream has shipped both.
+fn parse_pump_fun_coins(body: &str) -> Result<Vec<PumpFunCoin>, PumpFunError> {
+    if let Ok(coins) = serde_json::from_str::<Vec<PumpFunCoin>>(body) {
+        return Ok(coins);
+    }
+    serde_json::from_str::<WrappedPumpFunCoins>(body)
+        .map(|wrapped| wrapped.coins)
+        .map_err(|_| PumpFunError::UnexpectedShape)
+}
+
 /// Proxy endpoint for Pump.fun API to avoid CORS issues
+///
+/// Requests are routed through the shared `UpstreamGuard`, which rate-limits
+/// and caches calls to the Pump.fun host (keyed by the full query string, so
+/// distinct `(offset, limit, sort, order, include_nsfw)` tuples each get
+/// their own short-lived cache entry) so a burst of clients collapses to one
+/// upstream fetch, and serves the last good snapshot instead of a Cloudflare
+/// error when the upstream is throttling us.
 async fn get_pump_fun_coins(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Query(params): Query<PumpFunQueryParams>,
 ) -> Result<Json<PumpFunResponse>, String> {
     info!(
@@ -71,14 +122,9 @@ async fn get_pump_fun_coins(
         params.offset, params.limit, params.sort
     );
 
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true) // For development
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
     let url = format!(
-        "https://frontend-api.pump.fun/coins?offset={}&limit={}&sort={}&order={}&includeNsfw={}",
+        "{}/coins?offset={}&limit={}&sort={}&order={}&includeNsfw={}",
+        state.config.pump_fun.base_url,
         params.offset,
         params.limit,
         params.sort,
@@ -86,59 +132,36 @@ async fn get_pump_fun_coins(
         params.include_nsfw
     );
 
-    // Retry up to 3 times
-    let mut last_error = String::new();
-    for attempt in 0..3 {
-        match client
-            .get(&url)
-            .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
-            .header("Accept", "application/json")
-            .send()
-            .await
-        {
-            Ok(response) => {
-                if response.status().is_success() {
-                    // Parse the response - Pump.fun returns an array directly
-                    match response.json::<Vec<PumpFunCoin>>().await {
-                        Ok(coins) => {
-                            let count = coins.len();
-                            let graduated = coins.iter().filter(|c| c.complete.unwrap_or(false) || c.raydium_pool.is_some()).count();
-                            info!(
-                                "Successfully fetched {} coins from Pump.fun: {} not graduated, {} graduated",
-                                count,
-                                count - graduated,
-                                graduated
-                            );
-                            return Ok(Json(PumpFunResponse { coins, count }));
-                        }
-                        Err(e) => {
-                            warn!("Failed to parse Pump.fun response as array, trying object: {}", e);
-                            // Try parsing as object with coins field
-                            // This is handled by the error case returning empty
-                        }
-                    }
-                } else if response.status().as_u16() == 530 || response.status().as_u16() == 503 {
-                    // Cloudflare error
-                    warn!("Pump.fun API returned Cloudflare error {}", response.status());
-                    return Ok(Json(PumpFunResponse { coins: vec![], count: 0 }));
-                } else {
-                    last_error = format!("HTTP error: {}", response.status());
-                    warn!("Pump.fun API error on attempt {}: {}", attempt + 1, last_error);
-                }
-            }
-            Err(e) => {
-                last_error = format!("Request failed: {}", e);
-                warn!("Pump.fun request failed on attempt {}: {}", attempt + 1, e);
-            }
+    let body = match state.pump_fun_guard.cached_get_raw(&url, PUMP_FUN_CACHE_TTL).await {
+        Ok(body) => body,
+        Err(e) => {
+            // Graceful degradation - the guard already tried a stale
+            // snapshot before giving up.
+            warn!("{}", PumpFunError::Upstream(e));
+            return Ok(Json(PumpFunResponse { coins: vec![], count: 0 }));
         }
+    };
 
-        // Wait before retry
-        if attempt < 2 {
-            tokio::time::sleep(std::time::Duration::from_millis(500 * (1 << attempt))).await;
+    let coins = match parse_pump_fun_coins(&body) {
+        Ok(coins) => coins,
+        Err(e) => {
+            warn!("{}", e);
+            return Ok(Json(PumpFunResponse { coins: vec![], count: 0 }));
         }
-    }
+    };
 
-    // Return empty on failure (graceful degradation)
-    warn!("All attempts to fetch Pump.fun coins failed: {}", last_error);
-    Ok(Json(PumpFunResponse { coins: vec![], count: 0 }))
+    let count = coins.len();
+    let graduated = coins
+        .iter()
+        .filter(|c| c.complete.unwrap_or(false) || c.raydium_pool.is_some())
+        .count();
+    info!(
+        "Successfully fetched {} coins from Pump.fun: {} not graduated, {} graduated",
+        count,
+        count - graduated,
+        graduated
+    );
+    state.metrics.set_gauge("pump_fun_coins_returned", "total", count as u64).await;
+    state.metrics.set_gauge("pump_fun_coins_graduated", "total", graduated as u64).await;
+    Ok(Json(PumpFunResponse { coins, count }))
 }
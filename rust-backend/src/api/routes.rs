@@ -4,15 +4,22 @@ use axum::{
     Json, Router,
 };
 use base64::{engine::general_purpose, Engine as _};
+use solana_program::program_pack::Pack;
 use solana_sdk::{
+    account::Account,
     pubkey::Pubkey,
     transaction::Transaction,
 };
+use spl_token::state::{Account as LegacyTokenAccount, Mint as LegacyMint};
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::{Account as Token2022Account, Mint as Token2022Mint};
 use std::str::FromStr;
 
 use crate::{
     AppState,
+    jupiter::JupiterClient,
     models::transaction::{SimulateRequest, AccountBalance, TokenBalance},
+    rpc::RpcManager,
     services::{tx_simulator::TransactionSimulator, honeypot_analyzer::HoneypotAnalyzer},
 };
 
@@ -54,9 +61,11 @@ async fn check_token_safety(
     let pubkey = Pubkey::from_str(&mint)
         .map_err(|e| format!("Invalid pubkey: {}", e))?;
 
-    let analyzer = HoneypotAnalyzer::new(state.rpc.clone());
-    let score = analyzer
-        .analyze_token(&pubkey)
+    let analyzer = HoneypotAnalyzer::new(state.rpc.clone(), JupiterClient::with_mock(state.config.mock_jupiter).with_metrics(state.metrics.clone()));
+    let score = state
+        .rpc
+        .latency_metrics()
+        .timed("token_safety", analyzer.analyze_token(&pubkey))
         .await
         .map_err(|e| format!("Analysis failed: {}", e))?;
 
@@ -77,25 +86,7 @@ async fn get_account_balance(
         .await
         .map_err(|e| format!("Failed to get balance: {}", e))?;
 
-    // Get token accounts
-    let token_accounts_raw = state
-        .rpc
-        .get_token_accounts(&pubkey)
-        .await
-        .map_err(|e| format!("Failed to get token accounts: {}", e))?;
-
-    // Parse token accounts (simplified - would need proper deserialization)
-    let tokens: Vec<TokenBalance> = token_accounts_raw
-        .iter()
-        .filter_map(|account| {
-            // Extract pubkey from the account's pubkey field
-            Some(TokenBalance {
-                mint: account.pubkey.clone(),
-                amount: 0,
-                decimals: 9,
-            })
-        })
-        .collect();
+    let tokens = fetch_token_balances(&state.rpc, &pubkey).await?;
 
     Ok(Json(AccountBalance {
         sol: sol_balance,
@@ -110,22 +101,74 @@ async fn get_token_accounts(
     let pubkey = Pubkey::from_str(&pubkey)
         .map_err(|e| format!("Invalid pubkey: {}", e))?;
 
-    let token_accounts_raw = state
-        .rpc
-        .get_token_accounts(&pubkey)
+    let tokens = fetch_token_balances(&state.rpc, &pubkey).await?;
+
+    Ok(Json(tokens))
+}
+
+/// Resolve `owner`'s token accounts into `(mint, amount, decimals)`
+/// balances. `RpcManager::get_token_accounts` only tells us which accounts
+/// exist - the actual amount and mint come from unpacking each account's
+/// raw data, and a caller needs each mint's decimals to render `amount` as
+/// a UI value, so this fetches both per account.
+async fn fetch_token_balances(rpc: &RpcManager, owner: &Pubkey) -> Result<Vec<TokenBalance>, String> {
+    let token_accounts_raw = rpc
+        .get_token_accounts(owner)
         .await
         .map_err(|e| format!("Failed to get token accounts: {}", e))?;
 
-    let tokens: Vec<TokenBalance> = token_accounts_raw
-        .iter()
-        .filter_map(|account| {
-            Some(TokenBalance {
-                mint: account.pubkey.clone(),
-                amount: 0,
-                decimals: 9,
-            })
+    let fetches = token_accounts_raw.iter().map(|keyed| async move {
+        let token_account_pubkey = Pubkey::from_str(&keyed.pubkey).ok()?;
+        let account = rpc.get_account_data(&token_account_pubkey).await.ok()?;
+        let (mint, amount) = decode_token_account(&account).ok()?;
+        let decimals = fetch_mint_decimals(rpc, &mint).await.ok()?;
+
+        Some(TokenBalance {
+            mint: mint.to_string(),
+            amount,
+            decimals,
         })
-        .collect();
+    });
 
-    Ok(Json(tokens))
+    Ok(futures::future::join_all(fetches)
+        .await
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Decode a token account's mint and raw (not UI-normalized) amount,
+/// dispatching on the owning program the same way
+/// `honeypot_analyzer::inspect_mint` does for mints - a Token-2022 account
+/// carries a TLV extension area past the base 165-byte struct that a fixed
+/// byte offset would misread.
+fn decode_token_account(account: &Account) -> Result<(Pubkey, u64), String> {
+    if account.owner == spl_token_2022::id() {
+        let state = StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .map_err(|e| format!("Failed to unpack Token-2022 account: {}", e))?;
+        Ok((state.base.mint, state.base.amount))
+    } else {
+        let state = LegacyTokenAccount::unpack(&account.data)
+            .map_err(|e| format!("Failed to unpack SPL Token account: {}", e))?;
+        Ok((state.mint, state.amount))
+    }
+}
+
+/// Fetch a mint's decimal count, dispatching on the owning program the same
+/// way `decode_token_account` does for accounts.
+async fn fetch_mint_decimals(rpc: &RpcManager, mint: &Pubkey) -> Result<u8, String> {
+    let mint_account = rpc
+        .get_account_data(mint)
+        .await
+        .map_err(|e| format!("Failed to fetch mint {}: {}", mint, e))?;
+
+    if mint_account.owner == spl_token_2022::id() {
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data)
+            .map_err(|e| format!("Failed to unpack Token-2022 mint: {}", e))?;
+        Ok(state.base.decimals)
+    } else {
+        let mint = LegacyMint::unpack(&mint_account.data)
+            .map_err(|e| format!("Failed to unpack SPL Token mint: {}", e))?;
+        Ok(mint.decimals)
+    }
 }
@@ -3,6 +3,8 @@
 /// Endpoints for monitoring RPC status, latency, and health.
 
 use axum::{
+    http::header,
+    response::IntoResponse,
     routing::get,
     Router,
     Json,
@@ -12,16 +14,19 @@ use serde::Serialize;
 use tracing::info;
 
 use crate::AppState;
-use crate::rpc::{RpcProvider, build_staked_endpoints};
+use crate::rpc::router::EndpointStats;
 
 /// Create RPC API routes
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/rpc/status", get(get_rpc_status))
         .route("/rpc/endpoints", get(get_endpoints))
+        .route("/rpc/metrics", get(get_rpc_metrics))
 }
 
-/// RPC endpoint status
+/// RPC endpoint status, built from `RpcRouter`'s live `EndpointStats` rather
+/// than the static endpoint list - every field here is a real measurement
+/// from the background health-check loop.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcEndpointStatus {
@@ -31,6 +36,29 @@ pub struct RpcEndpointStatus {
     pub latency_tier: u8,
     pub is_available: bool,
     pub weight: u32,
+    pub effective_weight: u32,
+    pub p50_latency_ms: f64,
+    pub p99_latency_ms: f64,
+    pub success_rate: u8,
+    pub last_seen_slot: Option<u64>,
+}
+
+impl From<&EndpointStats> for RpcEndpointStatus {
+    fn from(stats: &EndpointStats) -> Self {
+        Self {
+            provider: stats.provider.name().to_string(),
+            url_preview: mask_url(&stats.url),
+            staked: stats.staked,
+            latency_tier: stats.provider.latency_tier(),
+            is_available: stats.is_healthy(),
+            weight: stats.weight,
+            effective_weight: stats.effective_weight,
+            p50_latency_ms: stats.p50(),
+            p99_latency_ms: stats.p99(),
+            success_rate: stats.success_rate,
+            last_seen_slot: stats.last_slot,
+        }
+    }
 }
 
 /// RPC status response
@@ -45,60 +73,50 @@ pub struct RpcStatusResponse {
 }
 
 /// GET /rpc/status
-/// Get overall RPC infrastructure status
-async fn get_rpc_status(
-    State(_state): State<AppState>,
-) -> Json<RpcStatusResponse> {
-    let endpoints = build_staked_endpoints();
-    
-    let staked_count = endpoints.iter().filter(|e| e.staked).count();
-    
-    // Build status for each endpoint
-    let endpoint_statuses: Vec<RpcEndpointStatus> = endpoints
-        .iter()
-        .map(|e| RpcEndpointStatus {
-            provider: e.provider.name().to_string(),
-            url_preview: mask_url(&e.url),
-            staked: e.staked,
-            latency_tier: e.provider.latency_tier(),
-            is_available: true, // Would come from router stats in production
-            weight: e.weight,
-        })
-        .collect();
+/// Get overall RPC infrastructure status, from measured health rather than
+/// the static endpoint list.
+async fn get_rpc_status(State(state): State<AppState>) -> Json<RpcStatusResponse> {
+    let stats = state.rpc_router.get_stats().await;
 
-    let recommended = if staked_count > 0 {
+    let staked_count = stats.iter().filter(|s| s.staked).count();
+    let available_count = stats.iter().filter(|s| s.is_healthy()).count();
+
+    let endpoint_statuses: Vec<RpcEndpointStatus> = stats.iter().map(RpcEndpointStatus::from).collect();
+
+    let recommended = if stats.iter().any(|s| s.staked && s.is_healthy()) {
         "Staked endpoints available - optimal for trading"
+    } else if staked_count > 0 {
+        "Staked endpoints configured but currently unhealthy - falling back to public"
     } else {
         "Using public endpoints - consider adding staked nodes"
     };
 
     Json(RpcStatusResponse {
-        total_endpoints: endpoints.len(),
+        total_endpoints: stats.len(),
         staked_endpoints: staked_count,
-        available_endpoints: endpoints.len(), // Would filter by availability
+        available_endpoints: available_count,
         endpoints: endpoint_statuses,
         recommended_for_trading: recommended.to_string(),
     })
 }
 
 /// GET /rpc/endpoints
-/// Get list of configured endpoints
-async fn get_endpoints(
-    State(_state): State<AppState>,
-) -> Json<Vec<RpcEndpointStatus>> {
-    let endpoints = build_staked_endpoints();
-    
-    Json(endpoints
-        .iter()
-        .map(|e| RpcEndpointStatus {
-            provider: e.provider.name().to_string(),
-            url_preview: mask_url(&e.url),
-            staked: e.staked,
-            latency_tier: e.provider.latency_tier(),
-            is_available: true,
-            weight: e.weight,
-        })
-        .collect())
+/// Get live status for each configured endpoint.
+async fn get_endpoints(State(state): State<AppState>) -> Json<Vec<RpcEndpointStatus>> {
+    let stats = state.rpc_router.get_stats().await;
+    Json(stats.iter().map(RpcEndpointStatus::from).collect())
+}
+
+/// GET /rpc/metrics
+/// Prometheus text exposition of `RpcManager`'s per-method backend-call
+/// latency histograms (simulate/balance/account/token-account lookups,
+/// plus handler-level `token_safety` and `sandwich_analysis` timings) -
+/// the only prior signal here was `health_check` returning a flat "OK".
+async fn get_rpc_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.rpc.latency_metrics().render_prometheus().await,
+    )
 }
 
 /// Mask sensitive parts of URL for display
@@ -1,32 +1,117 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     response::sse::{Event, Sse},
     routing::get,
     Router,
 };
-use futures::stream::{self, Stream};
+use futures::stream::{Stream, StreamExt};
 use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::BroadcastStream;
 
+use crate::services::price_coalescer::{LatestPrice, PriceCoalescer};
+use crate::telemetry::Metrics;
 use crate::AppState;
 
+/// Gauge name under which `/metrics` reports currently-open SSE connections.
+const ACTIVE_SSE_CONNECTIONS_GAUGE: &str = "sse_active_connections";
+
+const DEFAULT_MIN_INTERVAL_MS: u64 = 1000;
+
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/sse/price/:token_address", get(stream_price_updates))
 }
 
+#[derive(serde::Deserialize)]
+struct PriceStreamQuery {
+    /// Minimum milliseconds between flushed updates for this token.
+    min_interval_ms: Option<u64>,
+}
+
+/// Wraps the coalesced-update stream together with the `JoinHandle` of the
+/// synthetic feed task that publishes into it, so dropping the SSE
+/// connection (client disconnect) stops that task instead of leaking it.
+struct PriceEventStream {
+    inner: Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>,
+    feed_task: JoinHandle<()>,
+    metrics: Arc<Metrics>,
+}
+
+impl Stream for PriceEventStream {
+    type Item = Result<Event, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl Drop for PriceEventStream {
+    fn drop(&mut self) {
+        self.feed_task.abort();
+        // `Metrics::add_gauge` is async and `Drop` isn't, so the decrement
+        // is dispatched onto its own short-lived task rather than blocking
+        // (or skipping) the drop.
+        let metrics = self.metrics.clone();
+        tokio::spawn(async move {
+            metrics.add_gauge(ACTIVE_SSE_CONNECTIONS_GAUGE, "price", -1).await;
+        });
+    }
+}
+
+/// Streams coalesced price updates for one token: at most one `Event` per
+/// `min_interval`, even if the upstream publishes in a burst. The upstream
+/// feed itself is simulated here (this service has no live price oracle
+/// wired in yet) but is fed through `PriceCoalescer::publish` exactly like a
+/// real one would be, so the debounce behavior is exercised end to end.
 async fn stream_price_updates(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     axum::extract::Path(token_address): axum::extract::Path<String>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    // In a real implementation, this would subscribe to Redis pub/sub
-    // For now, return a placeholder stream
-    let stream = stream::unfold(0, move |counter| async move {
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        Some((
-            Ok(Event::default().data(format!("price_update:{}", counter))),
-            counter + 1,
-        ))
-    });
-
-    Sse::new(stream)
+    Query(query): Query<PriceStreamQuery>,
+) -> Sse<PriceEventStream> {
+    let min_interval = Duration::from_millis(query.min_interval_ms.unwrap_or(DEFAULT_MIN_INTERVAL_MS));
+
+    let feed_task = spawn_synthetic_price_feed(state.price_coalescer.clone(), token_address.clone(), min_interval);
+    state.metrics.add_gauge(ACTIVE_SSE_CONNECTIONS_GAUGE, "price", 1).await;
+
+    let rx = state.price_coalescer.subscribe();
+    let filtered_token = token_address.clone();
+    let inner = BroadcastStream::new(rx)
+        .filter_map(|item| async { item.ok() })
+        .filter(move |update| {
+            let matches = update.token_address == filtered_token;
+            async move { matches }
+        })
+        .map(|update| Ok(Event::default().json_data(update).unwrap_or_else(|_| Event::default())));
+
+    Sse::new(PriceEventStream {
+        inner: Box::pin(inner),
+        feed_task,
+        metrics: state.metrics.clone(),
+    })
+}
+
+/// Stand-in for a real price oracle: publishes a new price several times
+/// per `min_interval` so the coalescer's debounce is actually exercised.
+/// Aborted via `PriceEventStream`'s `Drop` once the client disconnects.
+fn spawn_synthetic_price_feed(coalescer: std::sync::Arc<PriceCoalescer>, token_address: String, min_interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let burst_interval = (min_interval / 4).max(Duration::from_millis(50));
+        let mut tick: u64 = 0;
+        loop {
+            tokio::time::sleep(burst_interval).await;
+            tick += 1;
+            coalescer.publish(
+                token_address.clone(),
+                LatestPrice {
+                    price_usd: 1.0 + (tick as f64 * 0.0001),
+                },
+                min_interval,
+            );
+        }
+    })
 }
@@ -1,3 +1,15 @@
+/// `/ws/trading` live trading feed.
+///
+/// A single socket can hold any number of channel subscriptions at once:
+/// clients send `{"type":"subscribe","channel":"priorityFees"}` /
+/// `{"type":"unsubscribe","channel":"priorityFees"}` frames and the handler
+/// starts/stops forwarding that channel's broadcast notifications. Right now
+/// `priorityFees` is fed by a single background poller (see
+/// `AppState::priority_fee_tx`) so connected sockets never hit RPC
+/// directly. `accounts` relays the Yellowstone geyser feed (see
+/// `AppState::geyser_tx`): a client subscribes with
+/// `{"type":"subscribe","channel":"accounts","accounts":["<pubkey>",...]}`
+/// and only receives updates for the pubkeys it named.
 use axum::{
     extract::{
         ws::{Message, WebSocket},
@@ -7,10 +19,17 @@ use axum::{
     routing::get,
     Router,
 };
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::HashSet;
 
 use crate::AppState;
 
+/// Channel name for live `PriorityFeeEstimate` snapshots.
+const CHANNEL_PRIORITY_FEES: &str = "priorityFees";
+/// Channel name for the Yellowstone geyser account-update relay.
+const CHANNEL_ACCOUNTS: &str = "accounts";
+
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/ws/trading", get(websocket_handler))
@@ -18,13 +37,25 @@ pub fn create_routes() -> Router<AppState> {
 
 async fn websocket_handler(
     ws: WebSocketUpgrade,
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> Response {
-    ws.on_upgrade(|socket| handle_socket(socket))
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ClientMessage {
+    Subscribe {
+        channel: String,
+        #[serde(default)]
+        accounts: Vec<String>,
+    },
+    Unsubscribe {
+        channel: String,
+    },
 }
 
-async fn handle_socket(mut socket: WebSocket) {
-    // Send welcome message
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
     if socket
         .send(Message::Text(
             serde_json::json!({
@@ -39,19 +70,118 @@ async fn handle_socket(mut socket: WebSocket) {
         return;
     }
 
-    // Handle incoming messages
-    while let Some(msg) = socket.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                // Echo back for now
-                let _ = socket
-                    .send(Message::Text(format!("Echo: {}", text)))
-                    .await;
+    let mut priority_fees_rx = state.priority_fee_tx.subscribe();
+    let mut geyser_rx = state.geyser_tx.subscribe();
+    let mut subscribed: HashSet<String> = HashSet::new();
+    let mut watched_accounts: HashSet<String> = HashSet::new();
+
+    loop {
+        tokio::select! {
+            msg = socket.next() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        handle_client_message(&mut socket, &state, &mut subscribed, &mut watched_accounts, &text).await;
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(_)) => break,
+                    _ => {}
+                }
+            }
+            fee = priority_fees_rx.recv() => {
+                if !subscribed.contains(CHANNEL_PRIORITY_FEES) {
+                    continue;
+                }
+                match fee {
+                    Ok(estimate) => {
+                        let frame = serde_json::json!({
+                            "type": "blockPrioritizationFees",
+                            "channel": CHANNEL_PRIORITY_FEES,
+                            "data": estimate,
+                        });
+                        if socket.send(Message::Text(frame.to_string())).await.is_err() {
+                            break;
+                        }
+                    }
+                    // A slow subscriber fell behind the broadcast buffer;
+                    // keep the connection open and pick up from the next tick.
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            update = geyser_rx.recv() => {
+                if !subscribed.contains(CHANNEL_ACCOUNTS) {
+                    continue;
+                }
+                match update {
+                    Ok(update) => {
+                        if !watched_accounts.contains(&update.pubkey) {
+                            continue;
+                        }
+                        let frame = serde_json::json!({
+                            "type": "accountUpdate",
+                            "channel": CHANNEL_ACCOUNTS,
+                            "data": update,
+                        });
+                        if socket.send(Message::Text(frame.to_string())).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+}
+
+async fn handle_client_message(
+    socket: &mut WebSocket,
+    state: &AppState,
+    subscribed: &mut HashSet<String>,
+    watched_accounts: &mut HashSet<String>,
+    text: &str,
+) {
+    match serde_json::from_str::<ClientMessage>(text) {
+        Ok(ClientMessage::Subscribe { channel, accounts }) => {
+            subscribed.insert(channel.clone());
+
+            if channel == CHANNEL_ACCOUNTS && !accounts.is_empty() {
+                watched_accounts.extend(accounts.iter().cloned());
+
+                // Register interest with the geyser subscriber so it
+                // (re)issues its upstream subscription to include these
+                // accounts. Accounts accumulate server-wide rather than
+                // being reference-counted per client - the feed just
+                // watches a superset, which is harmless for subscribers
+                // that don't ask for it.
+                let mut geyser_accounts = state.geyser_accounts.write().await;
+                geyser_accounts.extend(accounts);
             }
-            Ok(Message::Close(_)) => {
-                break;
+
+            let _ = socket
+                .send(Message::Text(
+                    serde_json::json!({ "type": "subscribed", "channel": channel }).to_string(),
+                ))
+                .await;
+        }
+        Ok(ClientMessage::Unsubscribe { channel }) => {
+            subscribed.remove(&channel);
+            if channel == CHANNEL_ACCOUNTS {
+                watched_accounts.clear();
             }
-            _ => {}
+            let _ = socket
+                .send(Message::Text(
+                    serde_json::json!({ "type": "unsubscribed", "channel": channel }).to_string(),
+                ))
+                .await;
+        }
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(
+                    serde_json::json!({ "type": "error", "message": format!("invalid message: {}", e) })
+                        .to_string(),
+                ))
+                .await;
         }
     }
 }
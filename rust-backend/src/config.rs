@@ -7,6 +7,39 @@ pub struct Config {
     pub redis: RedisConfig,
     pub turnkey: TurnkeyConfig,
     pub server: ServerConfig,
+    pub geyser: GeyserConfig,
+    /// When true, `JupiterClient` returns canned quotes/transactions instead
+    /// of calling out to Jupiter, so `execute_swap` and the MEV routes can
+    /// run end-to-end in CI and dry runs without a network dependency.
+    pub mock_jupiter: bool,
+    pub mev: MevConfig,
+    pub pump_fun: PumpFunConfig,
+}
+
+/// Upstream host/identity `get_pump_fun_coins` talks through `UpstreamGuard`
+/// with - broken out so a staging Pump.fun mirror can be targeted without a
+/// code change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PumpFunConfig {
+    /// Base URL, no trailing slash, e.g. `https://frontend-api.pump.fun`.
+    pub base_url: String,
+    pub user_agent: String,
+}
+
+/// Tunables for `MevSubmitter`'s dynamic tip sizing.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MevConfig {
+    /// Base tip as basis points of the trade's lamport value, before the
+    /// congestion multiplier is applied.
+    pub tip_bps: u64,
+    /// Never tip less than this, even for a tiny trade.
+    pub tip_floor_lamports: u64,
+    /// Never tip more than this, no matter how congested the network or how
+    /// large the trade.
+    pub tip_ceiling_lamports: u64,
+    /// The tip is also capped at this fraction of the trade's expected
+    /// slippage savings, so protection never costs more than it saves.
+    pub max_tip_fraction_of_savings: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +48,16 @@ pub struct RpcConfig {
     pub private: Option<String>,
     pub fallbacks: Vec<String>,
     pub pool_size: usize,
+    /// Websocket URL backing the TPU client's slot/leader-schedule
+    /// subscriptions. Defaults to `primary` with its scheme swapped to
+    /// `ws(s)://` when not set explicitly.
+    pub websocket_url: String,
+    /// Upper bound on in-flight requests `RpcManager` issues against the
+    /// primary/fallback endpoints at once, enforced with a shared semaphore.
+    /// Keeps bursts (the honeypot buy/sell round-trip, migration backfill
+    /// replaying several slots) from tripping the upstream provider's rate
+    /// limiter.
+    pub parallel_rpc_requests: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +65,20 @@ pub struct RedisConfig {
     pub url: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeyserConfig {
+    /// Yellowstone gRPC endpoint, e.g. `https://geyser.example.com:443`.
+    /// The account-update stream is disabled when unset.
+    pub endpoint: Option<String>,
+    /// `x-token` metadata header most Yellowstone providers use for auth.
+    pub x_token: Option<String>,
+    /// How often the transaction-update subscriber checks for (and backfills)
+    /// a gap between the last slot it processed and the current tip, both on
+    /// reconnect and periodically while connected in case the stream itself
+    /// silently drops slots.
+    pub resync_interval_secs: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnkeyConfig {
     pub api_key: Option<String>,
@@ -39,10 +96,15 @@ impl Config {
     pub async fn load() -> anyhow::Result<Self> {
         dotenv::dotenv().ok();
 
+        let primary = env::var("QUICKNODE_RPC_URL")
+            .or_else(|_| env::var("ALCHEMY_RPC_URL"))
+            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+
+        let websocket_url = env::var("RPC_WEBSOCKET_URL")
+            .unwrap_or_else(|_| derive_websocket_url(&primary));
+
         let rpc = RpcConfig {
-            primary: env::var("QUICKNODE_RPC_URL")
-                .or_else(|_| env::var("ALCHEMY_RPC_URL"))
-                .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
+            primary,
             private: env::var("PRIVATE_RPC_URL").ok(),
             fallbacks: vec![
                 "https://api.mainnet-beta.solana.com".to_string(),
@@ -52,6 +114,11 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10),
+            websocket_url,
+            parallel_rpc_requests: env::var("PARALLEL_RPC_REQUESTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(16),
         };
 
         let redis = RedisConfig {
@@ -72,11 +139,68 @@ impl Config {
                 .unwrap_or(8002),
         };
 
+        let geyser = GeyserConfig {
+            endpoint: env::var("GEYSER_GRPC_URL").ok(),
+            x_token: env::var("GEYSER_X_TOKEN").ok(),
+            resync_interval_secs: env::var("GEYSER_RESYNC_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+        };
+
+        let mock_jupiter = env::var("MOCK_JUPITER")
+            .ok()
+            .map(|s| s == "1" || s.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let mev = MevConfig {
+            tip_bps: env::var("MEV_TIP_BPS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            tip_floor_lamports: env::var("MEV_TIP_FLOOR_LAMPORTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1_000),
+            tip_ceiling_lamports: env::var("MEV_TIP_CEILING_LAMPORTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2_000_000),
+            max_tip_fraction_of_savings: env::var("MEV_MAX_TIP_FRACTION_OF_SAVINGS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.5),
+        };
+
+        let pump_fun = PumpFunConfig {
+            base_url: env::var("PUMP_FUN_BASE_URL")
+                .unwrap_or_else(|_| "https://frontend-api.pump.fun".to_string()),
+            user_agent: env::var("PUMP_FUN_USER_AGENT").unwrap_or_else(|_| {
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36".to_string()
+            }),
+        };
+
         Ok(Config {
             rpc,
             redis,
             turnkey,
             server,
+            geyser,
+            mock_jupiter,
+            mev,
+            pump_fun,
         })
     }
 }
+
+/// Swap an `http(s)://` RPC URL to its `ws(s)://` equivalent, which matches
+/// how most providers (QuickNode, Alchemy) host the two side by side.
+fn derive_websocket_url(rpc_url: &str) -> String {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    }
+}
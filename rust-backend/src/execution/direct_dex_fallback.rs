@@ -27,7 +27,7 @@ impl DirectDexFallback {
         // Compare and execute fastest/best route
         // For now, just execute Jupiter
         if jupiter_sim.will_succeed {
-            let sig = self.rpc.send_transaction(&jupiter_tx, false).await?;
+            let sig = self.rpc.send_transaction(&jupiter_tx, false, false).await?;
             Ok(sig.to_string())
         } else {
             anyhow::bail!("Jupiter transaction simulation failed")
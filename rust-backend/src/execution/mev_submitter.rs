@@ -0,0 +1,332 @@
+/// Unified MEV Submission
+///
+/// `BloxrouteClient::submit_bundle`, `JitoBundleClient::submit_bundle`, and
+/// `RpcManager::send_transaction` each work in isolation today - a caller has
+/// to pick one and eat that provider's latency and failure modes. This races
+/// every configured route concurrently and returns as soon as the first one
+/// succeeds, dropping the rest (`select_ok` over pinned futures, same
+/// first-wins shape as `MultiBundleExecutor`, generalized to a third,
+/// non-bundle route). Only the Jito arm waits for its bundle to land; the
+/// bloXroute arm succeeds once the relay accepts the bundle, and the
+/// private-RPC arm once the transaction is sent.
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::config::MevConfig;
+use crate::rpc::RpcManager;
+use crate::services::bloxroute::BloxrouteClient;
+use crate::services::jito_bundle::{JitoBundleClient, JITO_TIP_ACCOUNTS};
+
+/// How long `confirm_bundle` waits for the Jito arm to land before the race
+/// counts it as a loss - the other providers may still win in that window.
+const JITO_CONFIRM_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A configured submission route `MevSubmitter` can race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MevProvider {
+    Jito,
+    Bloxroute,
+    PrivateRpc,
+}
+
+/// Outcome of the winning arm of a race.
+#[derive(Debug, Clone, Serialize)]
+pub struct MevSubmissionResult {
+    pub provider: MevProvider,
+    /// A transaction signature for the `PrivateRpc` arm; a relay-assigned
+    /// bundle id for `Jito`/`Bloxroute`, since neither returns a signature
+    /// until the bundle actually lands.
+    pub signature: String,
+    pub latency_ms: u64,
+    pub tip_lamports: u64,
+}
+
+/// Rolling success/latency counters for one provider, so a route that keeps
+/// losing (or erroring outright) stops being raced once it has enough
+/// samples to trust, instead of still paying its full latency on every call.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MevProviderStats {
+    pub attempts: u64,
+    pub successes: u64,
+    pub avg_latency_ms: f64,
+}
+
+impl MevProviderStats {
+    fn record(&mut self, success: bool, latency_ms: f64) {
+        self.attempts += 1;
+        if success {
+            self.successes += 1;
+        }
+
+        // EMA rather than a lifetime average, so a provider that recovers
+        // from a slow patch is trusted again within a handful of samples.
+        const ALPHA: f64 = 0.3;
+        self.avg_latency_ms = if self.attempts == 1 {
+            latency_ms
+        } else {
+            ALPHA * latency_ms + (1.0 - ALPHA) * self.avg_latency_ms
+        };
+    }
+
+    fn success_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            1.0
+        } else {
+            self.successes as f64 / self.attempts as f64
+        }
+    }
+}
+
+/// Minimum attempts recorded before a provider's success rate is trusted
+/// enough to skip racing it.
+const MIN_ATTEMPTS_BEFORE_SKIP: u64 = 5;
+/// Below this success rate (once trusted), stop racing the provider.
+const MIN_SUCCESS_RATE_TO_RACE: f64 = 0.15;
+
+pub struct MevSubmitter {
+    jito: JitoBundleClient,
+    bloxroute: BloxrouteClient,
+    rpc: Arc<RpcManager>,
+    config: MevConfig,
+    jito_stats: RwLock<MevProviderStats>,
+    bloxroute_stats: RwLock<MevProviderStats>,
+    private_rpc_stats: RwLock<MevProviderStats>,
+}
+
+impl MevSubmitter {
+    pub fn new(
+        jito: JitoBundleClient,
+        bloxroute: BloxrouteClient,
+        rpc: Arc<RpcManager>,
+        config: MevConfig,
+    ) -> Self {
+        Self {
+            jito,
+            bloxroute,
+            rpc,
+            config,
+            jito_stats: RwLock::new(MevProviderStats::default()),
+            bloxroute_stats: RwLock::new(MevProviderStats::default()),
+            private_rpc_stats: RwLock::new(MevProviderStats::default()),
+        }
+    }
+
+    /// Dynamic tip size: `tip_bps` of the trade's lamport value, scaled up
+    /// under congestion (up to 2x at full congestion), floored/ceilinged per
+    /// `MevConfig`, and additionally capped at `max_tip_fraction_of_savings`
+    /// of what the trade is expected to save in slippage - protection should
+    /// never cost more than it's worth.
+    pub fn compute_tip_lamports(
+        &self,
+        trade_value_lamports: u64,
+        expected_slippage_savings_lamports: u64,
+        congestion_ratio: f64,
+    ) -> u64 {
+        let base = trade_value_lamports as f64 * (self.config.tip_bps as f64 / 10_000.0);
+        let congestion_multiplier = 1.0 + congestion_ratio.clamp(0.0, 1.0);
+        let scaled = base * congestion_multiplier;
+
+        let savings_cap = (expected_slippage_savings_lamports as f64
+            * self.config.max_tip_fraction_of_savings)
+            .max(self.config.tip_floor_lamports as f64);
+
+        scaled
+            .min(savings_cap)
+            .clamp(
+                self.config.tip_floor_lamports as f64,
+                self.config.tip_ceiling_lamports as f64,
+            ) as u64
+    }
+
+    /// Snapshot of every provider's rolling success rate and latency, for the
+    /// operator-facing MEV dashboard.
+    pub async fn provider_stats(&self) -> Vec<(MevProvider, MevProviderStats)> {
+        vec![
+            (MevProvider::Jito, self.jito_stats.read().await.clone()),
+            (MevProvider::Bloxroute, self.bloxroute_stats.read().await.clone()),
+            (MevProvider::PrivateRpc, self.private_rpc_stats.read().await.clone()),
+        ]
+    }
+
+    fn stats_for(&self, provider: MevProvider) -> &RwLock<MevProviderStats> {
+        match provider {
+            MevProvider::Jito => &self.jito_stats,
+            MevProvider::Bloxroute => &self.bloxroute_stats,
+            MevProvider::PrivateRpc => &self.private_rpc_stats,
+        }
+    }
+
+    async fn should_race(&self, provider: MevProvider) -> bool {
+        let stats = self.stats_for(provider).read().await;
+        !(stats.attempts >= MIN_ATTEMPTS_BEFORE_SKIP && stats.success_rate() < MIN_SUCCESS_RATE_TO_RACE)
+    }
+
+    async fn record(&self, provider: MevProvider, success: bool, latency_ms: u64) {
+        self.stats_for(provider).write().await.record(success, latency_ms as f64);
+    }
+
+    /// Race `transactions` across every healthy configured provider and
+    /// return the first arm to succeed (see the module doc for what success
+    /// means per provider). `payer_keypair` signs the tip
+    /// transfer; `trade_value_lamports`/`expected_slippage_savings_lamports`/
+    /// `congestion_ratio` drive [`Self::compute_tip_lamports`].
+    pub async fn submit(
+        &self,
+        transactions: Vec<Transaction>,
+        payer_keypair: &[u8],
+        trade_value_lamports: u64,
+        expected_slippage_savings_lamports: u64,
+        congestion_ratio: f64,
+    ) -> Result<MevSubmissionResult> {
+        if transactions.is_empty() {
+            return Err(anyhow!("Cannot submit an empty transaction set"));
+        }
+
+        let tip_lamports = self.compute_tip_lamports(
+            trade_value_lamports,
+            expected_slippage_savings_lamports,
+            congestion_ratio,
+        );
+
+        let mut racers: Vec<Pin<Box<dyn Future<Output = Result<MevSubmissionResult>> + Send + '_>>> =
+            Vec::new();
+
+        if self.should_race(MevProvider::Jito).await {
+            racers.push(Box::pin(self.race_jito(
+                transactions.clone(),
+                payer_keypair,
+                tip_lamports,
+            )));
+        }
+
+        if self.bloxroute.is_configured() && self.should_race(MevProvider::Bloxroute).await {
+            let payer = Keypair::from_bytes(payer_keypair)
+                .map_err(|e| anyhow!("Invalid payer keypair: {}", e))?;
+            racers.push(Box::pin(self.race_bloxroute(transactions.clone(), payer, tip_lamports)));
+        }
+
+        if self.should_race(MevProvider::PrivateRpc).await {
+            let tx = transactions.last().expect("checked non-empty").clone();
+            racers.push(Box::pin(self.race_private_rpc(tx)));
+        }
+
+        if racers.is_empty() {
+            return Err(anyhow!(
+                "No MEV provider available to race (all disabled or recently failing)"
+            ));
+        }
+
+        match futures::future::select_ok(racers).await {
+            Ok((result, _losers)) => {
+                info!(
+                    "MEV submission won by {:?} in {}ms (tip {} lamports)",
+                    result.provider, result.latency_ms, result.tip_lamports
+                );
+                Ok(result)
+            }
+            Err(e) => Err(anyhow!("Every raced MEV provider failed: {}", e)),
+        }
+    }
+
+    async fn race_jito(
+        &self,
+        transactions: Vec<Transaction>,
+        payer_keypair: &[u8],
+        tip_lamports: u64,
+    ) -> Result<MevSubmissionResult> {
+        let started = Instant::now();
+        let outcome: Result<String> = async {
+            let submission = self
+                .jito
+                .submit_bundle_with_tip(transactions, tip_lamports, payer_keypair)
+                .await?;
+            self.jito
+                .confirm_bundle(&submission.bundle_id, JITO_CONFIRM_TIMEOUT)
+                .await
+                .map_err(|e| anyhow!("{}", e))?;
+            Ok(submission.bundle_id)
+        }
+        .await;
+
+        let latency_ms = started.elapsed().as_millis() as u64;
+        self.record(MevProvider::Jito, outcome.is_ok(), latency_ms).await;
+
+        Ok(MevSubmissionResult {
+            provider: MevProvider::Jito,
+            signature: outcome?,
+            latency_ms,
+            tip_lamports,
+        })
+    }
+
+    /// bloXroute has no tip-account concept wired into this codebase (unlike
+    /// Jito's rotated tip accounts), so the race arm reuses Jito's tip
+    /// accounts to build its own tip transfer and appends it as the bundle's
+    /// last transaction, same placement Jito requires.
+    async fn race_bloxroute(
+        &self,
+        mut transactions: Vec<Transaction>,
+        payer: Keypair,
+        tip_lamports: u64,
+    ) -> Result<MevSubmissionResult> {
+        let started = Instant::now();
+        let outcome: Result<String> = async {
+            let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[0])
+                .map_err(|e| anyhow!("Invalid tip account: {}", e))?;
+            let blockhash = self.rpc.get_latest_blockhash().await?;
+            let transfer_ix = system_instruction::transfer(&payer.pubkey(), &tip_account, tip_lamports);
+            let tip_tx = Transaction::new_signed_with_payer(
+                &[transfer_ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                blockhash,
+            );
+            transactions.push(tip_tx);
+
+            self.bloxroute.submit_bundle(transactions).await
+        }
+        .await;
+
+        let latency_ms = started.elapsed().as_millis() as u64;
+        self.record(MevProvider::Bloxroute, outcome.is_ok(), latency_ms).await;
+
+        Ok(MevSubmissionResult {
+            provider: MevProvider::Bloxroute,
+            signature: outcome?,
+            latency_ms,
+            tip_lamports,
+        })
+    }
+
+    /// Plain private RPC has no bundle/tip concept - just the priority fee
+    /// already baked into `tx`. Sent via the TPU-preferred path, same as
+    /// `SniperMode::execute`.
+    async fn race_private_rpc(&self, tx: Transaction) -> Result<MevSubmissionResult> {
+        let started = Instant::now();
+        let outcome = self.rpc.send_transaction(&tx, true, true).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        self.record(MevProvider::PrivateRpc, outcome.is_ok(), latency_ms).await;
+
+        Ok(MevSubmissionResult {
+            provider: MevProvider::PrivateRpc,
+            signature: outcome?.to_string(),
+            latency_ms,
+            tip_lamports: 0,
+        })
+    }
+}
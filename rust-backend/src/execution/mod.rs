@@ -0,0 +1,4 @@
+pub mod direct_dex_fallback;
+pub mod multi_bundle;
+pub mod mev_submitter;
+pub mod sniper_mode;
@@ -24,7 +24,9 @@ impl SniperMode {
         }
 
         // 2. Send via private RPC if requested (anti-MEV)
-        let signature = self.rpc.send_transaction(&tx, use_private_rpc).await?;
+        // Sniper mode is latency-sensitive: prefer the direct TPU path when
+        // it's available and fall back to RPC automatically if it isn't.
+        let signature = self.rpc.send_transaction(&tx, use_private_rpc, true).await?;
 
         Ok(signature.to_string())
     }
@@ -2,20 +2,81 @@
 /// 
 /// HTTP client for interacting with Jupiter's quote and swap APIs.
 
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
 use anyhow::{anyhow, Result};
 use reqwest::Client;
+use solana_sdk::pubkey::Pubkey;
 use tracing::{info, warn};
 
-use super::types::{QuoteRequest, QuoteResponse, SwapRequest, SwapResponse};
+use super::types::{QuoteRequest, QuoteResponse, RoutePlan, SwapInfo, SwapMode, SwapRequest, SwapResponse};
+use crate::rpc::RpcManager;
+use crate::services::priority_fee::percentile;
+use crate::telemetry::Metrics;
 
 /// Jupiter API base URL
 const JUPITER_API_BASE: &str = "https://quote-api.jup.ag/v6";
 
+/// Floor/ceiling `estimate_priority_fee` clamps its recommendation to,
+/// matching `PriorityFeeService::fallback_lamports`'s role of never handing
+/// back an unreasonable fee when the sampled data is thin or spiked.
+const DEFAULT_PRIORITY_FEE_FLOOR_MICRO_LAMPORTS: u64 = 1_000;
+const DEFAULT_PRIORITY_FEE_CEILING_MICRO_LAMPORTS: u64 = 2_000_000;
+
+/// Base64 placeholder Jupiter would never actually return, used as the
+/// `swapTransaction` payload in mock mode so callers that decode it (e.g.
+/// `build_swap_transaction`) see well-formed base64 rather than a sentinel
+/// string.
+const MOCK_SWAP_TRANSACTION_BASE64: &str = "AQ==";
+
+/// Where `JupiterClient` sources quotes and swap transactions - mirrors the
+/// `Rpc`/`LocalBank` split on `TransactionSimulator::backend`.
+#[derive(Debug, Clone, Copy)]
+pub enum QuoteProvider {
+    /// Round-trip to the real Jupiter V6 API.
+    Live,
+    /// Deterministic canned quotes/transactions from `pricing` - no network
+    /// calls. Selected via `MOCK_JUPITER`/`Config::mock_jupiter`; lets
+    /// `execute_preset` and the MEV routes run end-to-end in tests and
+    /// backtests without a network dependency.
+    Mock(MockPricing),
+}
+
+/// Pricing model behind `QuoteProvider::Mock`: a flat fee in bps applied to
+/// `amount`, plus a fixed price-impact percentage, both configurable so
+/// tests/benches can exercise different market conditions.
+#[derive(Debug, Clone, Copy)]
+pub struct MockPricing {
+    pub fee_bps: u64,
+    pub price_impact_pct: f64,
+}
+
+impl Default for MockPricing {
+    fn default() -> Self {
+        Self {
+            fee_bps: 50,
+            price_impact_pct: 0.01,
+        }
+    }
+}
+
 /// Jupiter API Client
 #[derive(Clone)]
 pub struct JupiterClient {
     http: Client,
     base_url: String,
+    provider: QuoteProvider,
+    /// Cross-cutting quote latency metrics (see `telemetry::Metrics`). `None`
+    /// when constructed without `with_metrics`, e.g. in tests.
+    metrics: Option<Arc<Metrics>>,
+    /// Solana RPC access for `estimate_priority_fee`/`get_swap_transaction_auto`.
+    /// `None` when constructed without `with_rpc`, e.g. in tests - those
+    /// callers should stick to the manual `priority_fee_lamports` overload.
+    rpc: Option<Arc<RpcManager>>,
+    priority_fee_floor_micro_lamports: u64,
+    priority_fee_ceiling_micro_lamports: u64,
 }
 
 impl JupiterClient {
@@ -27,9 +88,33 @@ impl JupiterClient {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: JUPITER_API_BASE.to_string(),
+            provider: QuoteProvider::Live,
+            metrics: None,
+            rpc: None,
+            priority_fee_floor_micro_lamports: DEFAULT_PRIORITY_FEE_FLOOR_MICRO_LAMPORTS,
+            priority_fee_ceiling_micro_lamports: DEFAULT_PRIORITY_FEE_CEILING_MICRO_LAMPORTS,
         }
     }
 
+    /// Create a client that returns canned quotes/transactions instead of
+    /// calling out to Jupiter, per `Config::mock_jupiter`. Uses the default
+    /// `MockPricing` - see `with_provider` to pick a specific price/impact.
+    pub fn with_mock(mock: bool) -> Self {
+        let provider = if mock {
+            QuoteProvider::Mock(MockPricing::default())
+        } else {
+            QuoteProvider::Live
+        };
+        Self::with_provider(provider)
+    }
+
+    /// Create a client against an explicit `QuoteProvider`, e.g.
+    /// `QuoteProvider::Mock` with custom pricing for a backtest or preset
+    /// test that needs a specific fee/impact scenario.
+    pub fn with_provider(provider: QuoteProvider) -> Self {
+        Self { provider, ..Self::new() }
+    }
+
     /// Create with custom base URL (for testing)
     pub fn with_base_url(base_url: &str) -> Self {
         Self {
@@ -38,30 +123,77 @@ impl JupiterClient {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: base_url.to_string(),
+            provider: QuoteProvider::Live,
+            metrics: None,
+            rpc: None,
+            priority_fee_floor_micro_lamports: DEFAULT_PRIORITY_FEE_FLOOR_MICRO_LAMPORTS,
+            priority_fee_ceiling_micro_lamports: DEFAULT_PRIORITY_FEE_CEILING_MICRO_LAMPORTS,
+        }
+    }
+
+    /// Record `get_quote`/`get_swap_transaction` latency/outcome into the
+    /// shared `/metrics` endpoint. `get_quote_advanced` is not recorded.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Enable `estimate_priority_fee`/`get_swap_transaction_auto` by giving
+    /// the client RPC access for `getRecentPrioritizationFees`.
+    pub fn with_rpc(mut self, rpc: Arc<RpcManager>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Override the floor/ceiling `estimate_priority_fee` clamps its
+    /// recommendation to. Defaults to
+    /// `DEFAULT_PRIORITY_FEE_FLOOR_MICRO_LAMPORTS`/`_CEILING_MICRO_LAMPORTS`.
+    pub fn with_priority_fee_bounds(mut self, floor_micro_lamports: u64, ceiling_micro_lamports: u64) -> Self {
+        self.priority_fee_floor_micro_lamports = floor_micro_lamports;
+        self.priority_fee_ceiling_micro_lamports = ceiling_micro_lamports;
+        self
+    }
+
+    async fn observe(&self, operation: &str, latency_ms: f64, success: bool) {
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(operation, "jupiter", latency_ms, success).await;
         }
     }
 
     /// Get quote for token swap
-    /// 
+    ///
     /// # Arguments
     /// * `input_mint` - Input token mint address
     /// * `output_mint` - Output token mint address
-    /// * `amount` - Amount in smallest units (e.g., lamports for SOL)
+    /// * `amount` - Amount in the input mint's smallest unit for `ExactIn`,
+    ///   or the output mint's for `ExactOut`
     /// * `slippage_bps` - Slippage tolerance in basis points (100 = 1%)
+    /// * `swap_mode` - Quote a fixed input (default) or solve for the input
+    ///   required to produce a fixed output
     pub async fn get_quote(
         &self,
         input_mint: &str,
         output_mint: &str,
         amount: u64,
         slippage_bps: u16,
+        swap_mode: SwapMode,
     ) -> Result<QuoteResponse> {
+        if let QuoteProvider::Mock(pricing) = self.provider {
+            return Ok(mock_quote(input_mint, output_mint, amount, slippage_bps, swap_mode, pricing));
+        }
+
         let url = format!("{}/quote", self.base_url);
-        
+        let mode_str = match swap_mode {
+            SwapMode::ExactIn => "ExactIn",
+            SwapMode::ExactOut => "ExactOut",
+        };
+
         info!(
-            "Getting Jupiter quote: {} -> {} (amount: {}, slippage: {}bps)",
-            input_mint, output_mint, amount, slippage_bps
+            "Getting Jupiter quote: {} -> {} (amount: {}, slippage: {}bps, mode: {})",
+            input_mint, output_mint, amount, slippage_bps, mode_str
         );
 
+        let started_at = Instant::now();
         let response = self.http
             .get(&url)
             .query(&[
@@ -69,19 +201,23 @@ impl JupiterClient {
                 ("outputMint", output_mint),
                 ("amount", &amount.to_string()),
                 ("slippageBps", &slippage_bps.to_string()),
+                ("swapMode", mode_str),
             ])
             .send()
             .await?;
+        let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             warn!("Jupiter quote failed: {} - {}", status, text);
+            self.observe("get_quote", latency_ms, false).await;
             return Err(anyhow!("Jupiter quote failed: {} - {}", status, text));
         }
 
         let quote: QuoteResponse = response.json().await?;
-        
+        self.observe("get_quote", latency_ms, true).await;
+
         info!(
             "Quote received: {} {} -> {} {} (impact: {}%)",
             quote.in_amount, input_mint,
@@ -104,6 +240,83 @@ impl JupiterClient {
         quote: &QuoteResponse,
         priority_fee_lamports: Option<u64>,
     ) -> Result<SwapResponse> {
+        self.get_swap_transaction_with_fees(user_pubkey, quote, None, priority_fee_lamports)
+            .await
+    }
+
+    /// Same as [`Self::get_swap_transaction`], but fills the priority fee in
+    /// automatically: [`Self::estimate_priority_fee`] picks the `percentile`
+    /// micro-lamports-per-CU rate for `writable_accounts`, which is sent as
+    /// `compute_unit_price_micro_lamports`; that rate times
+    /// `compute_unit_limit`, converted to lamports, is sent as
+    /// `prioritization_fee_lamports`. Gives landed-transaction reliability during
+    /// congestion without the caller hand-tuning fees.
+    pub async fn get_swap_transaction_auto(
+        &self,
+        user_pubkey: &str,
+        quote: &QuoteResponse,
+        writable_accounts: &[String],
+        percentile: u8,
+        compute_unit_limit: u64,
+    ) -> Result<SwapResponse> {
+        let compute_unit_price_micro_lamports = self.estimate_priority_fee(writable_accounts, percentile).await?;
+        let prioritization_fee_lamports = compute_unit_price_micro_lamports
+            .saturating_mul(compute_unit_limit)
+            / 1_000_000;
+
+        self.get_swap_transaction_with_fees(
+            user_pubkey,
+            quote,
+            Some(compute_unit_price_micro_lamports),
+            Some(prioritization_fee_lamports),
+        )
+        .await
+    }
+
+    /// Sample `getRecentPrioritizationFees` for `writable_accounts` and
+    /// return the requested `percentile` (0-100) of the non-zero fee
+    /// samples as a recommended micro-lamports-per-CU rate, clamped to
+    /// `priority_fee_floor_micro_lamports`/`_ceiling_micro_lamports`.
+    /// Requires [`Self::with_rpc`] to have been called.
+    pub async fn estimate_priority_fee(&self, writable_accounts: &[String], percentile_pct: u8) -> Result<u64> {
+        let rpc = self
+            .rpc
+            .as_ref()
+            .ok_or_else(|| anyhow!("JupiterClient has no RPC configured - call with_rpc() first"))?;
+
+        let pubkeys: Vec<Pubkey> = writable_accounts
+            .iter()
+            .map(|a| Pubkey::from_str(a).map_err(|e| anyhow!("invalid account {}: {}", a, e)))
+            .collect::<Result<_>>()?;
+
+        let recent_fees = rpc.get_recent_prioritization_fees(&pubkeys).await?;
+
+        Ok(recommend_fee_from_samples(
+            &recent_fees,
+            percentile_pct,
+            self.priority_fee_floor_micro_lamports,
+            self.priority_fee_ceiling_micro_lamports,
+        ))
+    }
+
+    /// Shared swap-request builder behind `get_swap_transaction` and
+    /// `get_swap_transaction_auto`.
+    async fn get_swap_transaction_with_fees(
+        &self,
+        user_pubkey: &str,
+        quote: &QuoteResponse,
+        compute_unit_price_micro_lamports: Option<u64>,
+        prioritization_fee_lamports: Option<u64>,
+    ) -> Result<SwapResponse> {
+        if let QuoteProvider::Mock(_) = self.provider {
+            info!("MOCK_JUPITER: returning canned swap transaction for {}", user_pubkey);
+            return Ok(SwapResponse {
+                swap_transaction: MOCK_SWAP_TRANSACTION_BASE64.to_string(),
+                last_valid_block_height: Some(0),
+                prioritization_fee_lamports,
+            });
+        }
+
         let url = format!("{}/swap", self.base_url);
 
         let request = SwapRequest {
@@ -112,8 +325,8 @@ impl JupiterClient {
             wrap_and_unwrap_sol: Some(true),
             use_shared_accounts: Some(true),
             fee_account: None,
-            compute_unit_price_micro_lamports: None,
-            prioritization_fee_lamports: priority_fee_lamports,
+            compute_unit_price_micro_lamports,
+            prioritization_fee_lamports,
             as_legacy_transaction: Some(false), // Use versioned transactions
             use_token_ledger: None,
             destination_token_account: None,
@@ -123,21 +336,25 @@ impl JupiterClient {
 
         info!("Building swap transaction for user: {}", user_pubkey);
 
+        let started_at = Instant::now();
         let response = self.http
             .post(&url)
             .json(&request)
             .send()
             .await?;
+        let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             warn!("Jupiter swap failed: {} - {}", status, text);
+            self.observe("get_swap_transaction", latency_ms, false).await;
             return Err(anyhow!("Jupiter swap failed: {} - {}", status, text));
         }
 
         let swap: SwapResponse = response.json().await?;
-        
+        self.observe("get_swap_transaction", latency_ms, true).await;
+
         info!(
             "Swap transaction built (block height: {:?}, priority fee: {:?})",
             swap.last_valid_block_height,
@@ -149,6 +366,17 @@ impl JupiterClient {
 
     /// Get quote with advanced options
     pub async fn get_quote_advanced(&self, request: QuoteRequest) -> Result<QuoteResponse> {
+        if let QuoteProvider::Mock(pricing) = self.provider {
+            return Ok(mock_quote(
+                &request.input_mint,
+                &request.output_mint,
+                request.amount,
+                request.slippage_bps.unwrap_or(50),
+                request.swap_mode.unwrap_or(SwapMode::ExactIn),
+                pricing,
+            ));
+        }
+
         let url = format!("{}/quote", self.base_url);
 
         let mut query_params: Vec<(&str, String)> = vec![
@@ -173,6 +401,18 @@ impl JupiterClient {
             query_params.push(("maxAccounts", max_acc.to_string()));
         }
 
+        if let Some(swap_mode) = request.swap_mode {
+            let mode_str = match swap_mode {
+                SwapMode::ExactIn => "ExactIn",
+                SwapMode::ExactOut => "ExactOut",
+            };
+            query_params.push(("swapMode", mode_str.to_string()));
+        }
+
+        if let Some(true) = request.auto_slippage {
+            query_params.push(("autoSlippage", "true".to_string()));
+        }
+
         let response = self.http
             .get(&url)
             .query(&query_params)
@@ -195,6 +435,69 @@ impl Default for JupiterClient {
     }
 }
 
+/// Drop zero-fee slots (an idle block, not a real bid) out of a raw
+/// `getRecentPrioritizationFees` sample, take `percentile_pct` of what's
+/// left, and clamp to `[floor, ceiling]`. Pulled out of
+/// `JupiterClient::estimate_priority_fee` so the percentile/clamp math is
+/// testable without a live RPC connection.
+fn recommend_fee_from_samples(raw_fees: &[u64], percentile_pct: u8, floor: u64, ceiling: u64) -> u64 {
+    let non_zero_fees: Vec<u64> = raw_fees.iter().copied().filter(|&f| f > 0).collect();
+
+    let recommended = percentile(&non_zero_fees, (percentile_pct.min(100) as f64) / 100.0)
+        .map(|p| p.round() as u64)
+        .unwrap_or(floor);
+
+    recommended.clamp(floor, ceiling)
+}
+
+/// Build a deterministic canned quote for `QuoteProvider::Mock`: `pricing`'s
+/// fee applied to `amount` in whichever direction `swap_mode` quotes, with a
+/// single direct route so callers that inspect `route_plan` still see a
+/// well-formed entry.
+fn mock_quote(
+    input_mint: &str,
+    output_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+    swap_mode: SwapMode,
+    pricing: MockPricing,
+) -> QuoteResponse {
+    let (in_amount, out_amount) = match swap_mode {
+        SwapMode::ExactIn => (amount, amount * (10_000 - pricing.fee_bps) / 10_000),
+        SwapMode::ExactOut => (amount * (10_000 + pricing.fee_bps) / 10_000, amount),
+    };
+    let other_amount_threshold = out_amount * (10_000 - slippage_bps as u64) / 10_000;
+
+    QuoteResponse {
+        input_mint: input_mint.to_string(),
+        in_amount,
+        output_mint: output_mint.to_string(),
+        out_amount,
+        other_amount_threshold,
+        swap_mode: match swap_mode {
+            SwapMode::ExactIn => "ExactIn".to_string(),
+            SwapMode::ExactOut => "ExactOut".to_string(),
+        },
+        slippage_bps,
+        price_impact_pct: pricing.price_impact_pct,
+        route_plan: vec![RoutePlan {
+            swap_info: SwapInfo {
+                amm_key: "MockAMM11111111111111111111111111111111111".to_string(),
+                label: Some("Mock".to_string()),
+                input_mint: input_mint.to_string(),
+                output_mint: output_mint.to_string(),
+                in_amount: in_amount.to_string(),
+                out_amount: out_amount.to_string(),
+                fee_amount: "0".to_string(),
+                fee_mint: input_mint.to_string(),
+            },
+            percent: 100,
+        }],
+        context_slot: None,
+        time_taken: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,16 +513,66 @@ mod tests {
             "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", // USDC
             100_000_000, // 0.1 SOL in lamports
             50, // 0.5% slippage
+            SwapMode::ExactIn,
         ).await;
 
         match result {
             Ok(quote) => {
                 println!("Quote: {:?}", quote);
-                assert!(!quote.out_amount.is_empty());
+                assert!(quote.out_amount > 0);
             }
             Err(e) => {
                 println!("Quote error (may be network): {}", e);
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_mock_provider_is_deterministic_and_network_free() {
+        let pricing = MockPricing { fee_bps: 100, price_impact_pct: 2.5 };
+        let client = JupiterClient::with_provider(QuoteProvider::Mock(pricing));
+
+        let quote = client
+            .get_quote(
+                "So11111111111111111111111111111111111111112", // SOL
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", // USDC
+                1_000_000,
+                50,
+                SwapMode::ExactIn,
+            )
+            .await
+            .expect("mock quotes never fail");
+
+        assert_eq!(quote.in_amount, 1_000_000);
+        assert_eq!(quote.out_amount, 990_000); // 1% mock fee
+        assert_eq!(quote.price_impact(), 2.5);
+
+        let swap = client
+            .get_swap_transaction("user-pubkey", &quote, None)
+            .await
+            .expect("mock swaps never fail");
+        assert_eq!(swap.swap_transaction, MOCK_SWAP_TRANSACTION_BASE64);
+    }
+
+    #[test]
+    fn test_recommend_fee_from_samples_drops_zero_fee_slots_and_clamps() {
+        let fees = vec![0, 0, 1_000, 2_000, 3_000, 4_000];
+        // p75 of the non-zero samples [1000, 2000, 3000, 4000] is 3250.
+        let recommended = recommend_fee_from_samples(&fees, 75, 100, 10_000);
+        assert_eq!(recommended, 3_250);
+    }
+
+    #[test]
+    fn test_recommend_fee_from_samples_clamps_to_ceiling() {
+        let fees = vec![5_000_000, 6_000_000];
+        let recommended = recommend_fee_from_samples(&fees, 90, 100, 2_000_000);
+        assert_eq!(recommended, 2_000_000);
+    }
+
+    #[test]
+    fn test_recommend_fee_from_samples_falls_back_to_floor_when_all_zero() {
+        let fees = vec![0, 0, 0];
+        let recommended = recommend_fee_from_samples(&fees, 75, 1_000, 2_000_000);
+        assert_eq!(recommended, 1_000);
+    }
 }
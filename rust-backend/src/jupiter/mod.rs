@@ -5,10 +5,12 @@
 
 pub mod client;
 pub mod quote;
+pub mod smart_swap;
 pub mod swap;
 pub mod types;
 
-pub use client::JupiterClient;
+pub use client::{JupiterClient, MockPricing, QuoteProvider};
 pub use quote::get_quote;
-pub use swap::build_swap_transaction;
+pub use smart_swap::send_smart_transaction;
+pub use swap::{build_swap_transaction, prepare_best_swap_for_signing};
 pub use types::*;
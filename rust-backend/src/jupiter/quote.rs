@@ -4,35 +4,43 @@
 
 use anyhow::Result;
 use super::client::JupiterClient;
-use super::types::{QuoteResponse, tokens};
+use super::types::{QuoteResponse, SwapMode, tokens};
 
-/// Get a quick quote for buying a token with SOL
+/// Get a quote for buying a token with SOL. `swap_mode` picks whether
+/// `amount` is the SOL paid in (`ExactIn`) or the token amount wanted out
+/// (`ExactOut`).
 pub async fn get_sol_buy_quote(
     client: &JupiterClient,
     token_mint: &str,
-    sol_amount_lamports: u64,
+    amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
 ) -> Result<QuoteResponse> {
     client.get_quote(
         tokens::WSOL,
         token_mint,
-        sol_amount_lamports,
+        amount,
         slippage_bps,
+        swap_mode,
     ).await
 }
 
-/// Get a quick quote for selling a token for SOL
+/// Get a quote for selling a token for SOL. `swap_mode` picks whether
+/// `amount` is the token paid in (`ExactIn`) or the SOL amount wanted out
+/// (`ExactOut`).
 pub async fn get_sol_sell_quote(
     client: &JupiterClient,
     token_mint: &str,
-    token_amount: u64,
+    amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
 ) -> Result<QuoteResponse> {
     client.get_quote(
         token_mint,
         tokens::WSOL,
-        token_amount,
+        amount,
         slippage_bps,
+        swap_mode,
     ).await
 }
 
@@ -40,14 +48,16 @@ pub async fn get_sol_sell_quote(
 pub async fn get_usdc_sell_quote(
     client: &JupiterClient,
     token_mint: &str,
-    token_amount: u64,
+    amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
 ) -> Result<QuoteResponse> {
     client.get_quote(
         token_mint,
         tokens::USDC,
-        token_amount,
+        amount,
         slippage_bps,
+        swap_mode,
     ).await
 }
 
@@ -58,18 +68,17 @@ pub async fn get_quote(
     output_mint: &str,
     amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
 ) -> Result<QuoteResponse> {
-    client.get_quote(input_mint, output_mint, amount, slippage_bps).await
+    client.get_quote(input_mint, output_mint, amount, slippage_bps, swap_mode).await
 }
 
 /// Calculate minimum output amount from quote
 pub fn calculate_min_output(quote: &QuoteResponse) -> u64 {
-    // other_amount_threshold is the minimum we accept
-    quote.other_amount_threshold.parse::<u64>().unwrap_or(0)
+    quote.min_output()
 }
 
 /// Check if price impact is acceptable (< 5%)
 pub fn is_price_impact_acceptable(quote: &QuoteResponse, max_impact_pct: f64) -> bool {
-    let impact: f64 = quote.price_impact_pct.parse().unwrap_or(100.0);
-    impact < max_impact_pct
+    quote.price_impact() < max_impact_pct
 }
@@ -0,0 +1,222 @@
+/// Fee/compute-unit-tuned Jupiter swap submission
+///
+/// `JupiterClient::get_swap_transaction`/`get_swap_transaction_auto` hand back
+/// a serialized transaction and leave compute-unit sizing and priority-fee
+/// tuning, signing, submission, and confirmation polling to the caller. This
+/// module wires all of that into one call so landing a swap during
+/// congestion doesn't depend on every call site reimplementing it. It also
+/// rejects a quote whose `context_slot` is already behind the highest slot
+/// `rpc` has observed through its other read paths (e.g.
+/// `get_account_data_with_context`), since pricing a swap against a route
+/// that's already known to be stale is worse than refusing to send it. This
+/// is deliberately not checked against a freshly-fetched "now" slot - the
+/// chain advances roughly a slot every 400ms, so comparing against `now`
+/// would flag essentially every quote as stale instead of only the ones that
+/// are actually behind already-observed state.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    message::VersionedMessage,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::client::JupiterClient;
+use super::types::{QuoteResponse, SwapProvider, SwapResult};
+use crate::rpc::RpcManager;
+
+/// Headroom added on top of simulation's `units_consumed` before it's set as
+/// the transaction's compute-unit limit, so minor variance between
+/// simulation and the landed execution doesn't cause an out-of-compute
+/// failure.
+const COMPUTE_UNIT_SAFETY_MARGIN_PCT: u64 = 10;
+
+/// Percentile of recent `getRecentPrioritizationFees` samples used to price
+/// `compute_unit_price_micro_lamports` - see `JupiterClient::estimate_priority_fee`.
+const PRIORITY_FEE_PERCENTILE: u8 = 75;
+
+/// Compute-unit limit Jupiter is asked to price the priority fee against
+/// before the real post-simulation limit is known. Only used to size the
+/// fee request itself; the transaction's actual limit is patched in after
+/// simulation.
+const PLACEHOLDER_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// How often `confirm_transaction` re-polls `getSignatureStatuses` while
+/// waiting for a submission to land.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Build, fee-tune, sign, and submit a Jupiter swap for `quote`, returning
+/// once it confirms, fails on-chain, or its `last_valid_block_height`
+/// expires.
+///
+/// Concretely: asks Jupiter for a swap transaction priced with a placeholder
+/// compute-unit limit, decodes it, simulates it on `rpc`'s primary endpoint
+/// (then its fallbacks; no staked endpoint is selected) to read the actual
+/// `units_consumed`, patches the transaction's
+/// `SetComputeUnitLimit` instruction to that value plus
+/// [`COMPUTE_UNIT_SAFETY_MARGIN_PCT`], re-signs (the limit patch changes the
+/// message, so the old signature no longer verifies), and submits with
+/// preflight skipped while polling for confirmation.
+pub async fn send_smart_transaction(
+    client: &JupiterClient,
+    rpc: &RpcManager,
+    payer_keypair: &[u8],
+    quote: &QuoteResponse,
+) -> Result<SwapResult> {
+    let payer = Keypair::from_bytes(payer_keypair)
+        .map_err(|e| anyhow!("Invalid payer keypair: {}", e))?;
+    let user_pubkey = payer.pubkey().to_string();
+
+    if let Some(context_slot) = quote.context_slot {
+        if let Err(e) = rpc.ensure_min_context_slot(context_slot) {
+            return Ok(failed_result(quote, format!("Quote is stale: {}", e)));
+        }
+    }
+
+    let writable_accounts: Vec<String> = quote
+        .route_plan
+        .iter()
+        .map(|leg| leg.swap_info.amm_key.clone())
+        .collect();
+
+    let swap_response = client
+        .get_swap_transaction_auto(
+            &user_pubkey,
+            quote,
+            &writable_accounts,
+            PRIORITY_FEE_PERCENTILE,
+            PLACEHOLDER_COMPUTE_UNIT_LIMIT,
+        )
+        .await?;
+    let last_valid_block_height = swap_response.last_valid_block_height.unwrap_or(0);
+
+    let tx_bytes = BASE64
+        .decode(&swap_response.swap_transaction)
+        .map_err(|e| anyhow!("Failed to decode swap transaction: {}", e))?;
+    let unsigned_tx: VersionedTransaction = bincode::deserialize(&tx_bytes)
+        .map_err(|e| anyhow!("Failed to deserialize swap transaction: {}", e))?;
+
+    let sim = rpc.simulate_versioned_transaction(&unsigned_tx).await?;
+    if let Some(err) = sim.err {
+        return Ok(failed_result(quote, format!("Simulation failed: {:?}", err)));
+    }
+    let units_consumed = sim
+        .units_consumed
+        .ok_or_else(|| anyhow!("Simulation response had no units_consumed"))?;
+    let compute_unit_limit =
+        (units_consumed + units_consumed * COMPUTE_UNIT_SAFETY_MARGIN_PCT / 100) as u32;
+
+    let mut message = unsigned_tx.message;
+    if !patch_compute_unit_limit(&mut message, compute_unit_limit) {
+        warn!("Swap transaction had no SetComputeUnitLimit instruction to patch - sending with Jupiter's own dynamic limit");
+    }
+
+    let signed_tx = VersionedTransaction::try_new(message, &[&payer])
+        .map_err(|e| anyhow!("Failed to re-sign patched swap transaction: {}", e))?;
+
+    let signature = match rpc.send_versioned_transaction(&signed_tx).await {
+        Ok(sig) => sig,
+        Err(e) => return Ok(failed_result(quote, format!("Submission failed: {}", e))),
+    };
+    info!(
+        "Smart swap submitted: {} (compute-unit limit {}, valid until block {})",
+        signature, compute_unit_limit, last_valid_block_height
+    );
+
+    match confirm_transaction(rpc, &signature, last_valid_block_height).await {
+        Ok(true) => Ok(SwapResult {
+            success: true,
+            signature: Some(signature.to_string()),
+            error: None,
+            in_amount: quote.in_amount.to_string(),
+            out_amount: quote.out_amount.to_string(),
+            price_impact_pct: quote.price_impact_pct.to_string(),
+            provider: Some(SwapProvider::Jupiter),
+        }),
+        Ok(false) => Ok(failed_result(quote, format!("Transaction {} failed on-chain", signature))),
+        Err(e) => Ok(failed_result(quote, format!("Confirmation failed: {}", e))),
+    }
+}
+
+fn failed_result(quote: &QuoteResponse, error: String) -> SwapResult {
+    warn!("Smart swap failed: {}", error);
+    SwapResult {
+        success: false,
+        signature: None,
+        error: Some(error),
+        in_amount: quote.in_amount.to_string(),
+        out_amount: quote.out_amount.to_string(),
+        price_impact_pct: quote.price_impact_pct.to_string(),
+        provider: Some(SwapProvider::Jupiter),
+    }
+}
+
+/// Overwrite the data of the message's existing Compute Budget
+/// `SetComputeUnitLimit` instruction in place. The Compute Budget program
+/// takes no accounts, so rewriting just its instruction data - rather than
+/// inserting a new instruction - leaves every account index and the
+/// existing `SetComputeUnitPrice` instruction untouched. Returns `false` if
+/// no such instruction is found (e.g. Jupiter changes how it sizes compute
+/// budgets), in which case the caller falls back to Jupiter's own limit.
+fn patch_compute_unit_limit(message: &mut VersionedMessage, compute_unit_limit: u32) -> bool {
+    const SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT: u8 = 2;
+
+    let program_index = match message
+        .static_account_keys()
+        .iter()
+        .position(|key| *key == compute_budget::id())
+    {
+        Some(index) => index as u8,
+        None => return false,
+    };
+
+    let new_data = ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit).data;
+    let instructions = match message {
+        VersionedMessage::Legacy(m) => &mut m.instructions,
+        VersionedMessage::V0(m) => &mut m.instructions,
+    };
+
+    for ix in instructions.iter_mut() {
+        if ix.program_id_index == program_index
+            && ix.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT)
+        {
+            ix.data = new_data;
+            return true;
+        }
+    }
+    false
+}
+
+/// Poll `getSignatureStatuses` every [`CONFIRMATION_POLL_INTERVAL`] until
+/// `signature` lands (returning whether it succeeded) or `last_valid_block_height`
+/// is exceeded (the blockhash has expired, so it never will).
+async fn confirm_transaction(
+    rpc: &RpcManager,
+    signature: &Signature,
+    last_valid_block_height: u64,
+) -> Result<bool> {
+    loop {
+        let statuses = rpc.get_signature_statuses(&[*signature]).await?;
+        if let Some(Some(status)) = statuses.into_iter().next() {
+            return Ok(status.err.is_none());
+        }
+
+        if last_valid_block_height > 0 {
+            let current_height = rpc.get_block_height().await?;
+            if current_height > last_valid_block_height {
+                anyhow::bail!(
+                    "Blockhash expired waiting for {} (block height {} > last valid {})",
+                    signature,
+                    current_height,
+                    last_valid_block_height
+                );
+            }
+        }
+
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    }
+}
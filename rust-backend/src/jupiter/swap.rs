@@ -7,7 +7,54 @@ use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use tracing::{info, warn};
 
 use super::client::JupiterClient;
-use super::types::{QuoteResponse, SwapResult};
+use super::types::{QuoteRequest, QuoteResponse, SwapMode, SwapProvider, SwapResult};
+use crate::sanctum::SanctumClient;
+
+/// Quote `amount` in the given `swap_mode`, going through
+/// `get_quote_advanced` instead of the plain `get_quote` so `ExactOut`
+/// requests (amount is the desired output, input is variable) are
+/// expressed correctly.
+async fn quote_for_mode(
+    client: &JupiterClient,
+    input_mint: &str,
+    output_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+    swap_mode: SwapMode,
+) -> Result<QuoteResponse> {
+    client
+        .get_quote_advanced(QuoteRequest {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            amount,
+            slippage_bps: Some(slippage_bps),
+            only_direct_routes: None,
+            as_legacy_transaction: None,
+            max_accounts: None,
+            swap_mode: Some(swap_mode),
+            auto_slippage: None,
+        })
+        .await
+}
+
+/// Price-impact guard percentage for a quote. `ExactIn` uses Jupiter's own
+/// `price_impact_pct` (computed against output); `ExactOut` instead looks at
+/// how far `in_amount` overshoots `other_amount_threshold` - the worst-case
+/// input Jupiter quoted to still land the exact output - since that's the
+/// number that actually bounds what the caller pays in this mode.
+fn price_impact_guard_pct(quote: &QuoteResponse, swap_mode: SwapMode) -> f64 {
+    match swap_mode {
+        SwapMode::ExactIn => quote.price_impact(),
+        SwapMode::ExactOut => {
+            let in_amount = quote.in_amount as f64;
+            let threshold = quote.min_output() as f64;
+            if threshold <= 0.0 {
+                return 0.0;
+            }
+            ((in_amount - threshold) / threshold) * 100.0
+        }
+    }
+}
 
 /// Build swap transaction from quote
 pub async fn build_swap_transaction(
@@ -44,29 +91,26 @@ pub async fn execute_swap(
     output_mint: &str,
     amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
     priority_fee_lamports: Option<u64>,
     // sign_callback: impl FnOnce(&[u8]) -> Result<Vec<u8>>,
     // send_callback: impl FnOnce(Vec<u8>) -> Result<String>,
 ) -> Result<SwapResult> {
     // 1. Get quote
-    let quote = client.get_quote(
-        input_mint,
-        output_mint,
-        amount,
-        slippage_bps,
-    ).await?;
+    let quote = quote_for_mode(client, input_mint, output_mint, amount, slippage_bps, swap_mode).await?;
 
     // Check price impact
-    let impact: f64 = quote.price_impact_pct.parse().unwrap_or(0.0);
+    let impact = price_impact_guard_pct(&quote, swap_mode);
     if impact > 10.0 {
         warn!("High price impact detected: {}%", impact);
         return Ok(SwapResult {
             success: false,
             signature: None,
             error: Some(format!("Price impact too high: {}%", impact)),
-            in_amount: quote.in_amount.clone(),
-            out_amount: quote.out_amount.clone(),
-            price_impact_pct: quote.price_impact_pct.clone(),
+            in_amount: quote.in_amount.to_string(),
+            out_amount: quote.out_amount.to_string(),
+            price_impact_pct: quote.price_impact_pct.to_string(),
+            provider: Some(SwapProvider::Jupiter),
         });
     }
 
@@ -90,9 +134,10 @@ pub async fn execute_swap(
         success: true,
         signature: None, // Will be populated after signing
         error: None,
-        in_amount: quote.in_amount,
-        out_amount: quote.out_amount,
-        price_impact_pct: quote.price_impact_pct,
+        in_amount: quote.in_amount.to_string(),
+        out_amount: quote.out_amount.to_string(),
+        price_impact_pct: quote.price_impact_pct.to_string(),
+        provider: Some(SwapProvider::Jupiter),
     })
 }
 
@@ -104,15 +149,11 @@ pub async fn prepare_swap_for_signing(
     output_mint: &str,
     amount: u64,
     slippage_bps: u16,
+    swap_mode: SwapMode,
     priority_fee_lamports: Option<u64>,
 ) -> Result<(String, QuoteResponse)> {
     // 1. Get quote
-    let quote = client.get_quote(
-        input_mint,
-        output_mint,
-        amount,
-        slippage_bps,
-    ).await?;
+    let quote = quote_for_mode(client, input_mint, output_mint, amount, slippage_bps, swap_mode).await?;
 
     // 2. Get swap transaction
     let swap_response = client.get_swap_transaction(
@@ -124,3 +165,59 @@ pub async fn prepare_swap_for_signing(
     // Return the base64 transaction for frontend to sign via Turnkey
     Ok((swap_response.swap_transaction, quote))
 }
+
+/// Prepare a swap for signing, routing through whichever of Jupiter or
+/// Sanctum quotes the better `out_amount`.
+///
+/// Sanctum specializes in LST<->LST and LST<->SOL routing (mSOL, jitoSOL,
+/// bSOL, ...) and can out-price generic AMM aggregation for those pairs, so
+/// both are quoted and the winner builds the transaction. If one provider
+/// fails to quote, the other is used automatically; this only errors if
+/// both do.
+pub async fn prepare_best_swap_for_signing(
+    jupiter: &JupiterClient,
+    sanctum: &SanctumClient,
+    user_pubkey: &str,
+    input_mint: &str,
+    output_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+    priority_fee_lamports: Option<u64>,
+) -> Result<(String, SwapProvider)> {
+    let jupiter_quote = jupiter.get_quote(input_mint, output_mint, amount, slippage_bps, SwapMode::ExactIn).await;
+    let sanctum_quote = sanctum.get_quote(input_mint, output_mint, amount).await;
+
+    let jupiter_out = jupiter_quote.as_ref().ok().map(|q| q.out_amount);
+    let sanctum_out = sanctum_quote.as_ref().ok().and_then(|q| q.out_amount.parse::<u64>().ok());
+
+    let use_sanctum = match (jupiter_out, sanctum_out) {
+        (Some(j), Some(s)) => s > j,
+        (None, Some(_)) => true,
+        (Some(_), None) => false,
+        (None, None) => {
+            return Err(anyhow!(
+                "Both Jupiter and Sanctum failed to quote {} -> {}: jupiter={:?}, sanctum={:?}",
+                input_mint,
+                output_mint,
+                jupiter_quote.err(),
+                sanctum_quote.err(),
+            ));
+        }
+    };
+
+    if use_sanctum {
+        let quote = sanctum_quote.expect("checked Some above");
+        info!(
+            "Sanctum route won: {} -> {} ({} > {})",
+            quote.in_amount, quote.out_amount, quote.out_amount,
+            jupiter_out.map(|a| a.to_string()).unwrap_or_else(|| "n/a".to_string())
+        );
+        let swap = sanctum.get_swap_transaction(user_pubkey, &quote).await?;
+        Ok((swap.tx, SwapProvider::Sanctum))
+    } else {
+        let quote = jupiter_quote.expect("checked Some above");
+        info!("Jupiter route won: {} -> {}", quote.in_amount, quote.out_amount);
+        let swap_response = jupiter.get_swap_transaction(user_pubkey, &quote, priority_fee_lamports).await?;
+        Ok((swap_response.swap_transaction, SwapProvider::Jupiter))
+    }
+}
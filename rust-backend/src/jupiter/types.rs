@@ -4,6 +4,51 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Jupiter's quote API returns numeric fields (`otherAmountThreshold`,
+/// `priceImpactPct`, the `*Amount` fields) as JSON strings in some responses
+/// and as bare numbers in others. `deserialize_with = "numeric::from_str_or_num"`
+/// accepts either shape up front, the same trick cowprotocol's
+/// `HexOrDecimalU256` uses for hex-or-decimal amounts, so nothing downstream
+/// has to guess which wire shape it got.
+mod numeric {
+    use serde::{de, Deserialize, Deserializer};
+    use std::fmt;
+    use std::str::FromStr;
+
+    pub fn from_str_or_num<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrNumber<T> {
+            String(String),
+            Number(T),
+        }
+
+        match StringOrNumber::<T>::deserialize(deserializer)? {
+            StringOrNumber::String(s) => s.parse().map_err(de::Error::custom),
+            StringOrNumber::Number(n) => Ok(n),
+        }
+    }
+}
+
+/// Jupiter swap direction: quote a fixed input amount (the default) or
+/// solve for the input required to produce a fixed output amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapMode {
+    ExactIn,
+    ExactOut,
+}
+
+impl Default for SwapMode {
+    fn default() -> Self {
+        SwapMode::ExactIn
+    }
+}
+
 /// Jupiter Quote Request
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,20 +64,35 @@ pub struct QuoteRequest {
     pub as_legacy_transaction: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_accounts: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_mode: Option<SwapMode>,
+    /// Ask Jupiter to compute slippage dynamically instead of using
+    /// `slippage_bps`. The effective value Jupiter picked comes back on
+    /// `QuoteResponse::slippage_bps`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_slippage: Option<bool>,
 }
 
 /// Jupiter Quote Response
+///
+/// The canonical quote type for the crate - callers that need the minimum
+/// output or price impact should go through [`QuoteResponse::min_output`] /
+/// [`QuoteResponse::price_impact`] rather than parsing the fields directly.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteResponse {
     pub input_mint: String,
-    pub in_amount: String,
+    #[serde(deserialize_with = "numeric::from_str_or_num")]
+    pub in_amount: u64,
     pub output_mint: String,
-    pub out_amount: String,
-    pub other_amount_threshold: String,
+    #[serde(deserialize_with = "numeric::from_str_or_num")]
+    pub out_amount: u64,
+    #[serde(deserialize_with = "numeric::from_str_or_num")]
+    pub other_amount_threshold: u64,
     pub swap_mode: String,
     pub slippage_bps: u16,
-    pub price_impact_pct: String,
+    #[serde(deserialize_with = "numeric::from_str_or_num")]
+    pub price_impact_pct: f64,
     pub route_plan: Vec<RoutePlan>,
     #[serde(default)]
     pub context_slot: Option<u64>,
@@ -40,6 +100,20 @@ pub struct QuoteResponse {
     pub time_taken: Option<f64>,
 }
 
+impl QuoteResponse {
+    /// Minimum output amount this quote guarantees: `other_amount_threshold`
+    /// for an `ExactIn` quote, or the worst-case input Jupiter will still
+    /// accept to land the exact requested output for `ExactOut`.
+    pub fn min_output(&self) -> u64 {
+        self.other_amount_threshold
+    }
+
+    /// Price impact of this route, as a percentage (`5.0` == 5%).
+    pub fn price_impact(&self) -> f64 {
+        self.price_impact_pct
+    }
+}
+
 /// Route Plan Entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -112,6 +186,16 @@ pub struct TokenInfo {
     pub logo_uri: Option<String>,
 }
 
+/// Which provider ultimately supplied the quoted/built swap, for call sites
+/// that compare more than one (see
+/// `jupiter::swap::prepare_best_swap_for_signing`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SwapProvider {
+    Jupiter,
+    Sanctum,
+}
+
 /// Swap Result for frontend
 #[derive(Debug, Clone, Serialize)]
 pub struct SwapResult {
@@ -121,6 +205,8 @@ pub struct SwapResult {
     pub in_amount: String,
     pub out_amount: String,
     pub price_impact_pct: String,
+    /// Provider that produced this result, when more than one was compared.
+    pub provider: Option<SwapProvider>,
 }
 
 /// Common Solana Token Addresses
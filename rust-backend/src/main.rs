@@ -5,17 +5,40 @@ mod api;
 mod execution;
 mod jupiter;
 mod models;
+mod sanctum;
+mod telemetry;
+mod utils;
 
 use axum::{
     routing::get,
     Router,
 };
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
 use tower_http::cors::{CorsLayer, Any};
-use tracing::info;
+use tracing::{info, warn};
 
 use config::Config;
-use rpc::RpcManager;
+use rpc::{RpcManager, RpcRouter};
+use services::jito_bundle::JitoBundleClient;
+use services::migration_detector::MigrationDetector;
+use services::priority_fee::{PriorityFeeEstimate, PriorityFeeService};
+use services::price_coalescer::PriceCoalescer;
+use services::pubsub::PubSubHub;
+use services::sandwich_feed::SandwichMempoolFeed;
+use services::upstream_guard::{UpstreamGuard, PUMP_FUN_RATE_LIMIT};
+use services::yellowstone_geyser::{
+    AccountUpdate, GeyserSubscriber, TransactionSubscriber, WatchedAccounts,
+    PUMP_FUN_PROGRAM_ID, RAYDIUM_PROGRAM_ID, TRANSACTION_CHANNEL_CAPACITY,
+};
+use telemetry::Metrics;
+use utils::time::PrecisionTimer;
+
+/// How often the background poller refreshes `priorityFees` subscribers.
+const PRIORITY_FEE_BROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+const PRIORITY_FEE_BROADCAST_CAPACITY: usize = 64;
+const GEYSER_BROADCAST_CAPACITY: usize = 256;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -26,14 +49,143 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Starting WagYu Rust Backend...");
 
+    // Calibrate `_rdtsc`-to-nanosecond conversion against wall-clock time
+    // before anything on the MEV-detection hot path reports a latency -
+    // the hardcoded 3.5 GHz guess it replaces is wrong on most machines.
+    PrecisionTimer::calibrate();
+    let timer_ghz = PrecisionTimer::calibrated_ghz();
+    info!("Timer calibrated at {:.3} GHz", timer_ghz);
+
     // Load configuration
     let config = Config::load().await?;
     info!("Configuration loaded");
 
+    // Cross-cutting submission/quote latency + success/failure counters,
+    // rendered as Prometheus text at `/metrics`. Shared across every client
+    // that talks to an RPC or MEV relay so the scrape reflects the whole
+    // server, not just one handler's view.
+    let metrics = Arc::new(Metrics::new());
+
     // Initialize RPC manager
-    let rpc_manager = Arc::new(RpcManager::new(&config.rpc).await?);
+    let rpc_manager = Arc::new(RpcManager::new(&config.rpc, metrics.clone()).await?);
     info!("RPC manager initialized");
 
+    // Verify every endpoint's `staked: true` claim against gossip cluster
+    // info before the router ever routes to it - a hard-coded constructor
+    // saying "staked" doesn't make it so. Endpoints that fail verification
+    // are demoted (not dropped) so they're still usable as a last resort.
+    let mut staked_endpoints = rpc::build_staked_endpoints();
+    for endpoint in staked_endpoints.iter_mut().filter(|e| e.staked) {
+        match rpc::verify_staked_endpoint(endpoint).await {
+            Ok(report) if !report.verified => {
+                warn!(
+                    "Demoting {} ({}): failed staked-node verification: {}",
+                    endpoint.provider.name(),
+                    endpoint.url,
+                    report.reason.unwrap_or_default()
+                );
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Could not verify staked-node claim for {} ({}): {}",
+                endpoint.provider.name(),
+                endpoint.url,
+                e
+            ),
+        }
+    }
+
+    // Latency-aware router over the same staked/public endpoints, with a
+    // background health-check loop feeding real p50/p99/success-rate/weight
+    // into `/rpc/status` instead of the placeholder "always available"
+    // response it used to return.
+    let rpc_router = Arc::new(RpcRouter::new(staked_endpoints).await?);
+    rpc_router.clone().spawn_health_check_loop();
+
+    // Shared so its latency histograms reflect every submission made across
+    // the server, not just whichever handler happens to construct a client.
+    let jito_client = JitoBundleClient::new().with_metrics(metrics.clone());
+
+    // Initialize pub/sub hub and kick off its background poll loops so
+    // subscribers fan out from a single upstream poll instead of each
+    // WebSocket client re-hitting the rate-limited APIs.
+    let pubsub_hub = PubSubHub::new();
+    pubsub_hub.spawn_graduation_poll_loop();
+    pubsub_hub.spawn_bundle_status_poll_loop(jito_client.clone());
+    info!("Pub/sub hub initialized");
+
+    // Guards the Pump.fun upstream with a shared rate limiter + TTL cache so
+    // bursts of client requests collapse to one upstream fetch.
+    let pump_fun_guard = Arc::new(
+        UpstreamGuard::new(Some(&config.redis.url), PUMP_FUN_RATE_LIMIT)
+            .await
+            .with_metrics(metrics.clone())
+            .with_user_agent(config.pump_fun.user_agent.clone()),
+    );
+
+    // Debounces `/sse/price/:token_address` so a bursty upstream collapses
+    // to at most one flush per token per client-requested `min_interval`.
+    let price_coalescer = PriceCoalescer::new();
+
+    // One poller feeds every `/ws/trading` subscriber with priority-fee
+    // snapshots so sockets never hit RPC directly.
+    let priority_fee_service = Arc::new(PriorityFeeService::new(rpc_manager.clone()));
+    let (priority_fee_tx, _) = broadcast::channel(PRIORITY_FEE_BROADCAST_CAPACITY);
+    spawn_priority_fee_broadcast_loop(priority_fee_service.clone(), priority_fee_tx.clone());
+
+    // Geyser account-update stream: `/ws/trading` clients register interest
+    // by subscribing to the `accounts` channel, which adds to
+    // `geyser_accounts`; the subscriber loop reads that set to (re)issue its
+    // upstream subscription and relays decoded updates onto `geyser_tx`.
+    let geyser_accounts: WatchedAccounts = Arc::new(tokio::sync::RwLock::new(Default::default()));
+    let (geyser_tx, _) = broadcast::channel::<AccountUpdate>(GEYSER_BROADCAST_CAPACITY);
+    let geyser_subscriber = GeyserSubscriber::new(config.geyser.clone(), geyser_accounts.clone(), geyser_tx.clone());
+    tokio::spawn(geyser_subscriber.run());
+
+    // Geyser transaction-update stream: filtered to Pump.fun/Raydium program
+    // ids and fed to `MigrationDetector` as soon as a transaction touches
+    // both, instead of polling for graduations.
+    let (migration_tx, migration_rx) = tokio::sync::mpsc::channel(TRANSACTION_CHANNEL_CAPACITY);
+    let transaction_subscriber = TransactionSubscriber::new(
+        config.geyser.clone(),
+        vec![PUMP_FUN_PROGRAM_ID.to_string(), RAYDIUM_PROGRAM_ID.to_string()],
+        migration_tx,
+        rpc_manager.clone(),
+    );
+    tokio::spawn(transaction_subscriber.run());
+    spawn_migration_detector_loop(migration_rx);
+
+    // Live mempool feed for `SandwichDetector`: scoped to the same AMM
+    // programs plus whichever mints `sandwich_mints` has interest in, so
+    // the per-token activity windows `CopyTradeEngine`/`/mev/analyze` read
+    // reflect real front-running activity instead of an empty detector.
+    let sandwich_feed = Arc::new(SandwichMempoolFeed::new(config.geyser.clone(), rpc_manager.clone()));
+    sandwich_feed.clone().spawn();
+    let sandwich_detector = sandwich_feed.detector();
+    let sandwich_mints = sandwich_feed.watched_mints();
+
+    // Resting limit/stop-loss orders, watched against the swap stream
+    // `SwapStreamService` feeds it below and filled through the same
+    // mock-or-live Jupiter client every other route uses.
+    let conditional_orders = services::conditional_orders::ConditionalOrderEngine::new(
+        jupiter::JupiterClient::with_mock(config.mock_jupiter).with_metrics(metrics.clone()),
+    );
+
+    // OHLCV candles built from the swap stream; queried directly by
+    // `/candles/:pair/:interval` rather than through `SwapStreamService`
+    // itself, since the route only needs the read-only store.
+    let swap_stream = services::swap_stream::SwapStreamService::new(
+        config.rpc.websocket_url.clone(),
+        rpc_manager.clone(),
+    )
+    .with_order_engine(conditional_orders.clone());
+    let candles = swap_stream.candles();
+    tokio::spawn(async move {
+        if let Err(e) = swap_stream.monitor_swaps().await {
+            warn!("Swap stream monitor exited: {}", e);
+        }
+    });
+
     // Clone config for later use (before moving into Arc)
     let server_host = config.server.host.clone();
     let server_port = config.server.port;
@@ -52,7 +204,22 @@ async fn main() -> anyhow::Result<()> {
         .layer(cors)
         .with_state(AppState {
             rpc: rpc_manager,
+            rpc_router,
             config: Arc::new(config),
+            pubsub: pubsub_hub,
+            jito: jito_client,
+            pump_fun_guard,
+            price_coalescer,
+            priority_fee: priority_fee_service,
+            priority_fee_tx,
+            geyser_tx,
+            geyser_accounts,
+            metrics,
+            timer_ghz,
+            sandwich_detector,
+            sandwich_mints,
+            candles,
+            conditional_orders,
         });
 
     // Start server
@@ -69,8 +236,77 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+/// Poll `PriorityFeeService` on a fixed interval and fan the resulting
+/// snapshot out to every `priorityFees` subscriber on `/ws/trading`.
+fn spawn_priority_fee_broadcast_loop(
+    service: Arc<PriorityFeeService>,
+    tx: broadcast::Sender<PriorityFeeEstimate>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match service.get_dynamic_fee().await {
+                Ok(estimate) => {
+                    // No subscribers yet is not an error - broadcast just drops it.
+                    let _ = tx.send(estimate);
+                }
+                Err(e) => warn!("Priority fee broadcast loop failed to refresh: {}", e),
+            }
+            tokio::time::sleep(PRIORITY_FEE_BROADCAST_INTERVAL).await;
+        }
+    });
+}
+
+/// Drain `TransactionUpdate`s from the Yellowstone transaction subscriber
+/// through `MigrationDetector`, logging each detected Pump.fun -> Raydium
+/// migration. The channel closing (subscriber task gone) ends the loop.
+fn spawn_migration_detector_loop(
+    mut rx: tokio::sync::mpsc::Receiver<services::yellowstone_geyser::TransactionUpdate>,
+) {
+    tokio::spawn(async move {
+        let detector = MigrationDetector::new();
+        while let Some(update) = rx.recv().await {
+            match detector.detect_migration(&update).await {
+                Ok(Some(event)) => info!(
+                    "Detected migration at slot {}: tx {}",
+                    event.slot, update.signature
+                ),
+                Ok(None) => {}
+                Err(e) => warn!("Migration detection failed for tx {}: {}", update.signature, e),
+            }
+        }
+    });
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub rpc: Arc<RpcManager>,
+    /// Latency-aware router with real health probing over the same staked
+    /// endpoints `rpc` wraps - see `/rpc/status`.
+    pub rpc_router: Arc<RpcRouter>,
     pub config: Arc<Config>,
+    pub pubsub: Arc<PubSubHub>,
+    pub jito: JitoBundleClient,
+    pub pump_fun_guard: Arc<UpstreamGuard>,
+    pub price_coalescer: Arc<PriceCoalescer>,
+    pub priority_fee: Arc<PriorityFeeService>,
+    pub priority_fee_tx: broadcast::Sender<PriorityFeeEstimate>,
+    pub geyser_tx: broadcast::Sender<AccountUpdate>,
+    pub geyser_accounts: WatchedAccounts,
+    pub metrics: Arc<Metrics>,
+    /// `PrecisionTimer`'s calibrated TSC rate, snapshotted once at startup -
+    /// see `PrecisionTimer::calibrate`.
+    pub timer_ghz: f64,
+    /// Shared `SandwichDetector` populated by `SandwichMempoolFeed` from the
+    /// live mempool stream, rather than each handler standing up its own
+    /// empty detector with no history.
+    pub sandwich_detector: Arc<tokio::sync::Mutex<services::sandwich_detector::SandwichDetector>>,
+    /// Mints `SandwichMempoolFeed` currently watches - handlers add to this
+    /// to register interest in a token before trading it.
+    pub sandwich_mints: WatchedAccounts,
+    /// Live OHLCV candles built from the swap stream by `SwapStreamService`,
+    /// queried directly by `/candles/:pair/:interval`.
+    pub candles: Arc<services::swap_stream::CandleStore>,
+    /// Resting limit/stop-loss orders, watched against the swap stream and
+    /// filled through Jupiter - see `/orders/conditional`.
+    pub conditional_orders: Arc<services::conditional_orders::ConditionalOrderEngine>,
 }
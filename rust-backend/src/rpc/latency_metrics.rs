@@ -0,0 +1,142 @@
+/// Per-method latency histogram for `RpcManager`'s backend calls.
+///
+/// Previously the server's only visibility into RPC backend health was a
+/// static `health_check` returning "OK" - no signal on which call (or
+/// which endpoint) is slow. Buckets are exponentially spaced powers of two
+/// in microseconds rather than `telemetry::Metrics`'s linear millisecond
+/// buckets, since RPC read latency spans microseconds to seconds and a
+/// linear scale would need hundreds of buckets to resolve the fast end.
+/// Modeled on the benchrunner-style histograms used by production Solana
+/// RPC deployments.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+/// Bucket upper bounds in microseconds: 2^0 .. 2^19 (~524ms), plus an
+/// implicit `+Inf` bucket for anything slower.
+const LATENCY_BUCKETS_US: [u64; 20] = [
+    1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1_024, 2_048, 4_096, 8_192, 16_384, 32_768, 65_536,
+    131_072, 262_144, 524_288,
+];
+
+#[derive(Debug)]
+struct Histogram {
+    bucket_counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            bucket_counts: (0..LATENCY_BUCKETS_US.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, micros: u64) {
+        for (i, &boundary) in LATENCY_BUCKETS_US.iter().enumerate() {
+            if micros <= boundary {
+                self.bucket_counts[i].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(micros, Ordering::Relaxed);
+    }
+
+    /// Smallest bucket boundary whose cumulative count covers at least
+    /// `quantile` of all observations - an approximation bounded by bucket
+    /// width, the same tradeoff `EndpointStats::p50/p90/p99` makes.
+    fn percentile(&self, quantile: f64) -> u64 {
+        let total = self.count.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0;
+        }
+        let target = (total as f64 * quantile).ceil() as u64;
+        for (i, &boundary) in LATENCY_BUCKETS_US.iter().enumerate() {
+            if self.bucket_counts[i].load(Ordering::Relaxed) >= target {
+                return boundary;
+            }
+        }
+        LATENCY_BUCKETS_US[LATENCY_BUCKETS_US.len() - 1]
+    }
+}
+
+/// Latency histograms keyed by backend method name (e.g.
+/// `"simulate_transaction"`, `"get_balance"`), computing p50/p90/p99 on
+/// read rather than tracking them incrementally.
+pub struct RpcLatencyMetrics {
+    by_method: RwLock<HashMap<String, Histogram>>,
+}
+
+impl Default for RpcLatencyMetrics {
+    fn default() -> Self {
+        Self {
+            by_method: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl RpcLatencyMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record(&self, method: &str, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        let mut map = self.by_method.write().await;
+        map.entry(method.to_string()).or_insert_with(Histogram::new).record(micros);
+    }
+
+    /// Time `fut` and record its latency under `method`, returning its
+    /// result unchanged.
+    pub async fn timed<T, Fut: Future<Output = T>>(&self, method: &str, fut: Fut) -> T {
+        let start = Instant::now();
+        let result = fut.await;
+        self.record(method, start.elapsed()).await;
+        result
+    }
+
+    /// Render every recorded method's histogram in the Prometheus text
+    /// exposition format (https://prometheus.io/docs/instrumenting/exposition_formats/),
+    /// plus p50/p90/p99 gauges derived from the same buckets.
+    pub async fn render_prometheus(&self) -> String {
+        let map = self.by_method.read().await;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP rpc_call_latency_us RpcManager backend call latency in microseconds.");
+        let _ = writeln!(out, "# TYPE rpc_call_latency_us histogram");
+        for (method, hist) in map.iter() {
+            let labels = format!("method=\"{}\"", method);
+            for (i, boundary) in LATENCY_BUCKETS_US.iter().enumerate() {
+                let cumulative = hist.bucket_counts[i].load(Ordering::Relaxed);
+                let _ = writeln!(out, "rpc_call_latency_us_bucket{{{},le=\"{}\"}} {}", labels, boundary, cumulative);
+            }
+            let total = hist.count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "rpc_call_latency_us_bucket{{{},le=\"+Inf\"}} {}", labels, total);
+            let _ = writeln!(out, "rpc_call_latency_us_sum{{{}}} {}", labels, hist.sum_us.load(Ordering::Relaxed));
+            let _ = writeln!(out, "rpc_call_latency_us_count{{{}}} {}", labels, total);
+        }
+
+        for (quantile, name) in [(0.50, "p50"), (0.90, "p90"), (0.99, "p99")] {
+            let _ = writeln!(out, "# HELP rpc_call_latency_us_{} Approximate {} latency by method, in microseconds.", name, name);
+            let _ = writeln!(out, "# TYPE rpc_call_latency_us_{} gauge", name);
+            for (method, hist) in map.iter() {
+                let _ = writeln!(
+                    out,
+                    "rpc_call_latency_us_{}{{method=\"{}\"}} {}",
+                    name,
+                    method,
+                    hist.percentile(quantile)
+                );
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,200 @@
+/// Request-metrics informant for `RpcRouter`.
+///
+/// Modeled on OpenEthereum's RPC informant middleware: every routed call
+/// bumps counters here so operators have one place to see routing
+/// efficiency and provider reliability trends, instead of having to infer
+/// it from per-endpoint `EndpointStats`.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use super::RpcProvider;
+
+/// Interval between periodic summary log lines.
+const SUMMARY_LOG_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// The shape of a routed call, so the informant can break reliability down
+/// by call kind and not just by provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcMethodKind {
+    HealthCheck,
+    SendTransaction,
+    Generic,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    total: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    failovers: AtomicU64,
+    retries: AtomicU64,
+}
+
+impl Counters {
+    fn record_success(&self) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        self.successes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_failover(&self) {
+        self.failovers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> CountersSnapshot {
+        CountersSnapshot {
+            total: self.total.load(Ordering::Relaxed),
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            failovers: self.failovers.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountersSnapshot {
+    pub total: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub failovers: u64,
+    pub retries: u64,
+}
+
+/// Serializable snapshot of the whole informant, suitable for scraping from
+/// a metrics endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcMetricsSnapshot {
+    pub overall: CountersSnapshot,
+    pub by_provider: Vec<(RpcProvider, CountersSnapshot)>,
+    pub by_method: Vec<(RpcMethodKind, CountersSnapshot)>,
+    pub requests_per_second: f64,
+}
+
+pub struct RpcMetrics {
+    overall: Counters,
+    by_provider: RwLock<HashMap<RpcProvider, Counters>>,
+    by_method: RwLock<HashMap<RpcMethodKind, Counters>>,
+    window_start: RwLock<Instant>,
+    window_requests: AtomicU64,
+}
+
+impl Default for RpcMetrics {
+    fn default() -> Self {
+        Self {
+            overall: Counters::default(),
+            by_provider: RwLock::new(HashMap::new()),
+            by_method: RwLock::new(HashMap::new()),
+            window_start: RwLock::new(Instant::now()),
+            window_requests: AtomicU64::new(0),
+        }
+    }
+}
+
+impl RpcMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record_success(&self, provider: RpcProvider, method: RpcMethodKind) {
+        self.overall.record_success();
+        self.by_provider.write().await.entry(provider).or_default().record_success();
+        self.by_method.write().await.entry(method).or_default().record_success();
+        self.window_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub async fn record_failure(&self, provider: RpcProvider, method: RpcMethodKind) {
+        self.overall.record_failure();
+        self.by_provider.write().await.entry(provider).or_default().record_failure();
+        self.by_method.write().await.entry(method).or_default().record_failure();
+        self.window_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that routing had to fail over from one endpoint to another
+    /// for a single logical call.
+    pub async fn record_failover(&self, provider: RpcProvider) {
+        self.overall.record_failover();
+        self.by_provider.write().await.entry(provider).or_default().record_failover();
+    }
+
+    /// Record that a retry attempt was consumed (e.g. by
+    /// `send_transaction_with_retries`).
+    pub async fn record_retry(&self, provider: RpcProvider) {
+        self.overall.record_retry();
+        self.by_provider.write().await.entry(provider).or_default().record_retry();
+    }
+
+    /// Requests per second since the window was last reset by
+    /// [`Self::spawn_summary_log_loop`] (or since construction). Reading it
+    /// does not reset the window.
+    pub async fn requests_per_second(&self) -> f64 {
+        let elapsed = self.window_start.read().await.elapsed().as_secs_f64().max(0.001);
+        self.window_requests.load(Ordering::Relaxed) as f64 / elapsed
+    }
+
+    async fn reset_window(&self) {
+        *self.window_start.write().await = Instant::now();
+        self.window_requests.store(0, Ordering::Relaxed);
+    }
+
+    pub async fn snapshot(&self) -> RpcMetricsSnapshot {
+        let by_provider = self
+            .by_provider
+            .read()
+            .await
+            .iter()
+            .map(|(provider, counters)| (*provider, counters.snapshot()))
+            .collect();
+        let by_method = self
+            .by_method
+            .read()
+            .await
+            .iter()
+            .map(|(method, counters)| (*method, counters.snapshot()))
+            .collect();
+
+        RpcMetricsSnapshot {
+            overall: self.overall.snapshot(),
+            by_provider,
+            by_method,
+            requests_per_second: self.requests_per_second().await,
+        }
+    }
+
+    /// Spawn a background task that logs a summary line every
+    /// [`SUMMARY_LOG_INTERVAL`] and resets the rolling RPS window.
+    pub fn spawn_summary_log_loop(self: &Arc<Self>) {
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SUMMARY_LOG_INTERVAL);
+            loop {
+                interval.tick().await;
+                let snapshot = metrics.snapshot().await;
+                info!(
+                    "RPC informant: {} total ({} ok, {} failed), {} failovers, {} retries, {:.2} req/s",
+                    snapshot.overall.total,
+                    snapshot.overall.successes,
+                    snapshot.overall.failures,
+                    snapshot.overall.failovers,
+                    snapshot.overall.retries,
+                    snapshot.requests_per_second,
+                );
+                metrics.reset_window().await;
+            }
+        });
+    }
+}
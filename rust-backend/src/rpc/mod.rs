@@ -2,10 +2,19 @@ mod pool;
 mod private_rpc;
 pub mod staked_nodes;
 pub mod router;
+pub mod metrics;
+pub mod latency_metrics;
+pub mod tpu;
 
 pub use pool::RpcConnectionPool;
-pub use staked_nodes::{RpcProvider, StakedRpcEndpoint, build_staked_endpoints};
+pub use staked_nodes::{
+    build_staked_endpoints, verify_staked_endpoint, NodeVerificationReport, RpcProvider,
+    StakedRpcEndpoint,
+};
 pub use router::RpcRouter;
+pub use metrics::{RpcMetrics, RpcMethodKind};
+pub use latency_metrics::RpcLatencyMetrics;
+pub use tpu::{TpuBroadcaster, TpuOutcomeCounters, DEFAULT_TPU_FANOUT};
 use crate::config::RpcConfig;
 use solana_client::rpc_client::RpcClient as SolanaRpcClient;
 use solana_client::rpc_response::RpcKeyedAccount;
@@ -16,18 +25,60 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use solana_account_decoder::UiAccountEncoding;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use anyhow::Result;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::telemetry::Metrics;
+
+/// Wraps an RPC reply with the `RpcResponseContext` (`slot`, and optionally
+/// `apiVersion`) Solana attaches to every commitment-scoped read, so callers
+/// fanning reads out across multiple endpoints can tell which slot each one
+/// reflects instead of only seeing the bare value.
+#[derive(Debug, Clone)]
+pub struct ContextualResponse<T> {
+    pub slot: u64,
+    pub api_version: Option<String>,
+    pub value: T,
+}
+
+/// One mint's pre/post SPL token balance change from a confirmed
+/// transaction, keyed by the token account's index in the transaction's
+/// static account list. Returned by `RpcManager::get_token_balance_deltas`.
+#[derive(Debug, Clone)]
+pub struct TokenBalanceDelta {
+    pub mint: String,
+    pub decimals: u8,
+    /// `post - pre`, in the token's raw base units (negative = this
+    /// account's balance went down).
+    pub delta: i128,
+}
 
 pub struct RpcManager {
     primary: Arc<SolanaRpcClient>,
     private_rpc: Option<Arc<SolanaRpcClient>>,
     fallbacks: Vec<Arc<SolanaRpcClient>>,
     pool: Arc<RpcConnectionPool>,
+    tpu: Option<Arc<TpuBroadcaster>>,
+    /// Bounds in-flight requests to `parallel_rpc_requests` (see
+    /// `RpcConfig`), so a burst like the honeypot buy/sell round-trip or a
+    /// multi-slot migration backfill can't overwhelm the upstream provider.
+    request_limiter: Arc<Semaphore>,
+    metrics: Arc<Metrics>,
+    /// Per-method latency histograms for this manager's own backend calls
+    /// (simulate/balance/account/token-account lookups) - distinct from
+    /// `metrics`, which tracks submission/quote latency across providers.
+    latency: Arc<RpcLatencyMetrics>,
+    /// Highest slot seen in any context-bearing response recorded via
+    /// `record_context_slot` - see `ensure_min_context_slot`.
+    max_observed_slot: AtomicU64,
 }
 
 impl RpcManager {
-    pub async fn new(config: &RpcConfig) -> Result<Self> {
+    pub async fn new(config: &RpcConfig, metrics: Arc<Metrics>) -> Result<Self> {
         // Initialize primary RPC
         let primary = Arc::new(SolanaRpcClient::new_with_commitment(
             config.primary.clone(),
@@ -55,19 +106,89 @@ impl RpcManager {
             .collect();
 
         // Initialize connection pool
-        let pool = Arc::new(RpcConnectionPool::new(config.pool_size, config.clone()).await?);
+        let pool = Arc::new(
+            RpcConnectionPool::new(config.pool_size, config.clone(), metrics.clone()).await?,
+        );
+
+        // TPU broadcast is best-effort: it dials gossip up front, which can
+        // fail (firewalled node, bad websocket URL) without it being fatal
+        // for the rest of the server - submission just falls back to RPC.
+        let tpu_primary = primary.clone();
+        let websocket_url = config.websocket_url.clone();
+        let tpu = match tokio::task::spawn_blocking(move || {
+            TpuBroadcaster::new(tpu_primary, &websocket_url, DEFAULT_TPU_FANOUT)
+        })
+        .await
+        {
+            Ok(Ok(broadcaster)) => Some(Arc::new(broadcaster)),
+            Ok(Err(e)) => {
+                warn!("TPU broadcaster unavailable, falling back to RPC-only submission: {}", e);
+                None
+            }
+            Err(e) => {
+                warn!("TPU broadcaster init task panicked: {}", e);
+                None
+            }
+        };
 
         Ok(RpcManager {
             primary,
             private_rpc,
             fallbacks,
             pool,
+            tpu,
+            request_limiter: Arc::new(Semaphore::new(config.parallel_rpc_requests)),
+            metrics,
+            latency: Arc::new(RpcLatencyMetrics::new()),
+            max_observed_slot: AtomicU64::new(0),
         })
     }
 
+    /// Highest slot observed across every context-bearing response recorded
+    /// so far (see `record_context_slot`).
+    pub fn max_observed_slot(&self) -> u64 {
+        self.max_observed_slot.load(Ordering::Relaxed)
+    }
+
+    /// Record `slot` as observed, bumping `max_observed_slot` if it's newer.
+    pub(crate) fn record_context_slot(&self, slot: u64) {
+        self.max_observed_slot.fetch_max(slot, Ordering::Relaxed);
+    }
+
+    /// Reject `slot` if it's older than the highest slot this manager has
+    /// already observed elsewhere - used to catch a read (e.g. a Jupiter
+    /// quote's `context_slot`) that's stale relative to state already seen
+    /// through this `RpcManager`, which would otherwise silently price a
+    /// swap against an outdated route.
+    pub fn ensure_min_context_slot(&self, slot: u64) -> Result<()> {
+        let min = self.max_observed_slot();
+        if slot < min {
+            anyhow::bail!(
+                "Response context slot {} is older than the highest slot already observed ({})",
+                slot,
+                min
+            );
+        }
+        Ok(())
+    }
+
+    /// Shared latency histograms for this manager's backend calls, so API
+    /// handlers (token-safety, sandwich analysis) can record their own
+    /// end-to-end latency under the same registry - see `/rpc/metrics`.
+    pub fn latency_metrics(&self) -> Arc<RpcLatencyMetrics> {
+        self.latency.clone()
+    }
+
     pub async fn simulate_transaction(
         &self,
         tx: &Transaction,
+    ) -> Result<solana_client::rpc_response::RpcSimulateTransactionResult> {
+        self.latency.clone().timed("simulate_transaction", self.simulate_transaction_inner(tx)).await
+    }
+
+    async fn simulate_transaction_inner(
+        &self,
+        tx: &Transaction,
     ) -> Result<solana_client::rpc_response::RpcSimulateTransactionResult> {
         // Try primary RPC first
         let primary = self.primary.clone();
@@ -89,7 +210,129 @@ impl RpcManager {
         }
     }
 
+    /// Simulate a versioned transaction (e.g. a Jupiter swap, which is
+    /// always built as a v0 message) with signature verification skipped and
+    /// the recent blockhash replaced server-side, so `jupiter::smart_swap`
+    /// can read `units_consumed` off an unsigned, possibly stale-blockhash
+    /// template before it's patched and signed for real.
+    pub async fn simulate_versioned_transaction(
+        &self,
+        tx: &solana_sdk::transaction::VersionedTransaction,
+    ) -> Result<solana_client::rpc_response::RpcSimulateTransactionResult> {
+        use solana_client::rpc_config::RpcSimulateTransactionConfig;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+
+        let primary = self.primary.clone();
+        let tx_clone = tx.clone();
+        let config_clone = config.clone();
+        match tokio::task::spawn_blocking(move || primary.simulate_transaction_with_config(&tx_clone, config_clone)).await {
+            Ok(Ok(result)) => Ok(result.value),
+            Ok(Err(_)) | Err(_) => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    let tx_clone = tx.clone();
+                    let config_clone = config.clone();
+                    match tokio::task::spawn_blocking(move || fallback_clone.simulate_transaction_with_config(&tx_clone, config_clone)).await {
+                        Ok(Ok(result)) => return Ok(result.value),
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for versioned-transaction simulation")
+            }
+        }
+    }
+
+    /// Submit a signed versioned transaction with preflight skipped -
+    /// `jupiter::smart_swap` already simulated and fee-tuned it itself, so a
+    /// second preflight check here would only add latency.
+    pub async fn send_versioned_transaction(
+        &self,
+        tx: &solana_sdk::transaction::VersionedTransaction,
+    ) -> Result<Signature> {
+        use solana_client::rpc_config::RpcSendTransactionConfig;
+
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            preflight_commitment: Some(solana_sdk::commitment_config::CommitmentLevel::Confirmed),
+            max_retries: Some(3),
+            ..Default::default()
+        };
+
+        let primary = self.primary.clone();
+        let tx_clone = tx.clone();
+        let config_clone = config.clone();
+        match tokio::task::spawn_blocking(move || primary.send_transaction_with_config(&tx_clone, config_clone)).await {
+            Ok(Ok(sig)) => Ok(sig),
+            _ => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    let tx_clone = tx.clone();
+                    let config_clone = config.clone();
+                    match tokio::task::spawn_blocking(move || fallback_clone.send_transaction_with_config(&tx_clone, config_clone)).await {
+                        Ok(Ok(sig)) => return Ok(sig),
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for send_versioned_transaction")
+            }
+        }
+    }
+
+    /// Poll `getSignatureStatuses` for a submitted signature, the shape
+    /// `jupiter::smart_swap`'s confirmation loop reads to tell "still
+    /// in flight" (`Ok(None)`) apart from a landed-but-failed transaction
+    /// (`Ok(Some(status))` with `status.err` set).
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<solana_transaction_status::TransactionStatus>>> {
+        let primary = self.primary.clone();
+        let signatures = signatures.to_vec();
+        match tokio::task::spawn_blocking(move || primary.get_signature_statuses(&signatures)).await {
+            Ok(Ok(resp)) => Ok(resp.value),
+            Ok(Err(e)) => Err(e.into()),
+            Err(e) => Err(anyhow::anyhow!("Task join error: {}", e)),
+        }
+    }
+
+    /// Current block height of the primary RPC, compared against a swap's
+    /// `last_valid_block_height` to tell `jupiter::smart_swap`'s
+    /// confirmation loop when to give up instead of polling forever for a
+    /// transaction whose blockhash has already expired.
+    pub async fn get_block_height(&self) -> Result<u64> {
+        let primary = self.primary.clone();
+        match tokio::task::spawn_blocking(move || primary.get_block_height()).await {
+            Ok(Ok(height)) => Ok(height),
+            _ => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    match tokio::task::spawn_blocking(move || fallback_clone.get_block_height()).await {
+                        Ok(Ok(height)) => return Ok(height),
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for get_block_height")
+            }
+        }
+    }
+
     pub async fn get_account_data(&self, pubkey: &Pubkey) -> Result<solana_sdk::account::Account> {
+        self.latency.clone().timed("get_account_data", self.get_account_data_inner(pubkey)).await
+    }
+
+    async fn get_account_data_inner(&self, pubkey: &Pubkey) -> Result<solana_sdk::account::Account> {
+        let _permit = self
+            .request_limiter
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Request limiter closed: {}", e))?;
+
         let primary = self.primary.clone();
         let pubkey_clone = *pubkey;
         match tokio::task::spawn_blocking(move || primary.get_account(&pubkey_clone)).await {
@@ -108,7 +351,64 @@ impl RpcManager {
         }
     }
 
+    /// Same as [`Self::get_account_data`], but keeps the response's
+    /// `RpcResponseContext` instead of discarding it, and records the slot
+    /// via `record_context_slot` so later `ensure_min_context_slot` checks
+    /// (e.g. the swap path validating a Jupiter quote) see it.
+    pub async fn get_account_data_with_context(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<ContextualResponse<Option<solana_sdk::account::Account>>> {
+        let _permit = self
+            .request_limiter
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Request limiter closed: {}", e))?;
+
+        let primary = self.primary.clone();
+        let pubkey_clone = *pubkey;
+        let commitment = CommitmentConfig::confirmed();
+        let response = match tokio::task::spawn_blocking(move || {
+            primary.get_account_with_commitment(&pubkey_clone, commitment)
+        })
+        .await
+        {
+            Ok(Ok(response)) => response,
+            _ => {
+                let mut last_response = None;
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    let pubkey_clone = *pubkey;
+                    match tokio::task::spawn_blocking(move || {
+                        fallback_clone.get_account_with_commitment(&pubkey_clone, commitment)
+                    })
+                    .await
+                    {
+                        Ok(Ok(response)) => {
+                            last_response = Some(response);
+                            break;
+                        }
+                        _ => continue,
+                    }
+                }
+                last_response
+                    .ok_or_else(|| anyhow::anyhow!("All RPC endpoints failed for get_account_with_commitment"))?
+            }
+        };
+
+        self.record_context_slot(response.context.slot);
+        Ok(ContextualResponse {
+            slot: response.context.slot,
+            api_version: response.context.api_version.map(|v| v.to_string()),
+            value: response.value,
+        })
+    }
+
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
+        self.latency.clone().timed("get_balance", self.get_balance_inner(pubkey)).await
+    }
+
+    async fn get_balance_inner(&self, pubkey: &Pubkey) -> Result<u64> {
         let primary = self.primary.clone();
         let pubkey_clone = *pubkey;
         match tokio::task::spawn_blocking(move || primary.get_balance(&pubkey_clone)).await {
@@ -127,13 +427,35 @@ impl RpcManager {
         }
     }
 
+    /// `owner`'s token accounts across both the legacy SPL Token program and
+    /// Token-2022 - a wallet can hold accounts under either, and querying
+    /// only one silently drops the other's balances.
     pub async fn get_token_accounts(
         &self,
         owner: &Pubkey,
+    ) -> Result<Vec<RpcKeyedAccount>> {
+        self.latency.clone().timed("get_token_accounts", self.get_token_accounts_inner(owner)).await
+    }
+
+    async fn get_token_accounts_inner(
+        &self,
+        owner: &Pubkey,
+    ) -> Result<Vec<RpcKeyedAccount>> {
+        let mut accounts = self.get_token_accounts_for_program(owner, spl_token::id()).await?;
+        if let Ok(token_2022_accounts) = self.get_token_accounts_for_program(owner, spl_token_2022::id()).await {
+            accounts.extend(token_2022_accounts);
+        }
+        Ok(accounts)
+    }
+
+    async fn get_token_accounts_for_program(
+        &self,
+        owner: &Pubkey,
+        program_id: Pubkey,
     ) -> Result<Vec<RpcKeyedAccount>> {
         use solana_client::rpc_request::TokenAccountsFilter;
 
-        let filter = TokenAccountsFilter::ProgramId(spl_token::id());
+        let filter = TokenAccountsFilter::ProgramId(program_id);
 
         let primary = self.primary.clone();
         let owner_clone = *owner;
@@ -143,7 +465,7 @@ impl RpcManager {
                 for fallback in &self.fallbacks {
                     let fallback_clone = fallback.clone();
                     let owner_clone = *owner;
-                    let filter = TokenAccountsFilter::ProgramId(spl_token::id());
+                    let filter = TokenAccountsFilter::ProgramId(program_id);
                     match tokio::task::spawn_blocking(move || fallback_clone.get_token_accounts_by_owner(&owner_clone, filter)).await {
                         Ok(Ok(accounts)) => return Ok(accounts),
                         _ => continue,
@@ -158,9 +480,43 @@ impl RpcManager {
         &self,
         tx: &Transaction,
         use_private: bool,
+        prefer_tpu: bool,
+    ) -> Result<Signature> {
+        let started = Instant::now();
+        let result = self.send_transaction_inner(tx, use_private, prefer_tpu).await;
+        let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let provider = if use_private { "private_rpc" } else { "rpc" };
+        self.metrics
+            .observe("submit_transaction", provider, latency_ms, result.is_ok())
+            .await;
+        result
+    }
+
+    async fn send_transaction_inner(
+        &self,
+        tx: &Transaction,
+        use_private: bool,
+        prefer_tpu: bool,
     ) -> Result<Signature> {
         use solana_client::rpc_config::RpcSendTransactionConfig;
 
+        // TPU broadcast is the fastest path when available: it skips RPC
+        // forwarding entirely. Fall through to RPC on any failure, rather
+        // than erroring the whole send.
+        if prefer_tpu {
+            if let Some(tpu) = &self.tpu {
+                let tpu = tpu.clone();
+                let tx_clone = tx.clone();
+                let accepted = tokio::task::spawn_blocking(move || tpu.send(&tx_clone))
+                    .await
+                    .unwrap_or(false);
+                if accepted {
+                    return Ok(tx.signatures[0]);
+                }
+                warn!("TPU broadcast was not accepted by any leader, falling back to RPC");
+            }
+        }
+
         let config = RpcSendTransactionConfig {
             skip_preflight: false,
             preflight_commitment: Some(solana_sdk::commitment_config::CommitmentLevel::Confirmed),
@@ -203,10 +559,250 @@ impl RpcManager {
         }
     }
 
-    pub async fn get_recent_prioritization_fees(&self) -> Result<Vec<u64>> {
-        // Query recent prioritization fees from RPC
-        // This is a placeholder - actual implementation would use getRecentPrioritizationFees RPC method
-        // For now, return a default fee
-        Ok(vec![5000]) // 5000 lamports default
+    /// Broadcast `tx` directly to the next few leaders' TPU sockets,
+    /// bypassing RPC forwarding entirely. Returns whether it was accepted
+    /// onto at least one leader connection - not a landing confirmation,
+    /// just that the send didn't fail outright.
+    ///
+    /// `fanout` is accepted for API symmetry with the request that drove
+    /// this, but the underlying `TpuClient` fixes its fanout at
+    /// construction time (see [`tpu::DEFAULT_TPU_FANOUT`]); a mismatched
+    /// value here is only logged, not applied per-call.
+    pub async fn send_transaction_via_tpu(&self, tx: &Transaction, fanout: usize) -> Result<bool> {
+        let tpu = self
+            .tpu
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("TPU broadcaster not initialized"))?;
+
+        if fanout != DEFAULT_TPU_FANOUT {
+            warn!(
+                "send_transaction_via_tpu called with fanout={} but the broadcaster is fixed at {}",
+                fanout, DEFAULT_TPU_FANOUT
+            );
+        }
+
+        let tx_clone = tx.clone();
+        tokio::task::spawn_blocking(move || tpu.send(&tx_clone))
+            .await
+            .map_err(|e| anyhow::anyhow!("TPU send task panicked: {}", e))
+    }
+
+    /// Fraction of TPU sends so far that were accepted by at least one
+    /// leader connection. `None` if the TPU broadcaster never initialized.
+    pub fn tpu_landing_rate(&self) -> Option<f64> {
+        self.tpu.as_ref().map(|t| t.counters().landing_rate())
+    }
+
+    /// Current slot height of the primary RPC, used by the Yellowstone
+    /// transaction subscriber to size its backfill window on reconnect.
+    pub async fn get_slot(&self) -> Result<u64> {
+        let primary = self.primary.clone();
+        match tokio::task::spawn_blocking(move || primary.get_slot()).await {
+            Ok(Ok(slot)) => Ok(slot),
+            _ => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    match tokio::task::spawn_blocking(move || fallback_clone.get_slot()).await {
+                        Ok(Ok(slot)) => return Ok(slot),
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for get_slot")
+            }
+        }
+    }
+
+    /// Latest blockhash from the primary RPC, falling back in turn. Used by
+    /// callers that build and sign a transaction themselves (e.g. a tip
+    /// transfer) rather than going through `send_transaction`.
+    pub async fn get_latest_blockhash(&self) -> Result<solana_sdk::hash::Hash> {
+        let primary = self.primary.clone();
+        match tokio::task::spawn_blocking(move || primary.get_latest_blockhash()).await {
+            Ok(Ok(hash)) => Ok(hash),
+            _ => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    match tokio::task::spawn_blocking(move || fallback_clone.get_latest_blockhash()).await {
+                        Ok(Ok(hash)) => return Ok(hash),
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for get_latest_blockhash")
+            }
+        }
+    }
+
+    /// Fetch one confirmed block's transactions, decoded into the same
+    /// `TransactionUpdate` shape the live Yellowstone stream produces, so
+    /// `MigrationDetector` can't tell a transaction arrived via backfill
+    /// replay instead of the stream. Used to close the gap after the
+    /// transaction subscriber reconnects.
+    pub async fn get_block_transactions(
+        &self,
+        slot: u64,
+    ) -> Result<Vec<crate::services::yellowstone_geyser::TransactionUpdate>> {
+        use crate::services::yellowstone_geyser::TransactionUpdate;
+        use solana_client::rpc_config::RpcBlockConfig;
+        use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+
+        let _permit = self
+            .request_limiter
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Request limiter closed: {}", e))?;
+
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let primary = self.primary.clone();
+        let block = match tokio::task::spawn_blocking(move || primary.get_block_with_config(slot, config)).await {
+            Ok(Ok(block)) => block,
+            // A skipped slot has no block and isn't worth falling back for -
+            // the caller just moves on to the next slot in the range.
+            Ok(Err(e)) if e.to_string().contains("skipped") => return Ok(Vec::new()),
+            Ok(Err(e)) => return Err(e.into()),
+            Err(e) => return Err(anyhow::anyhow!("Task join error: {}", e)),
+        };
+
+        let updates = block
+            .transactions
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tx| {
+                let decoded = tx.transaction.decode()?;
+                let accounts = decoded
+                    .message
+                    .static_account_keys()
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect();
+                let logs = tx
+                    .meta
+                    .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+                    .unwrap_or_default();
+
+                Some(TransactionUpdate {
+                    signature: decoded.signatures[0].to_string(),
+                    slot,
+                    accounts,
+                    logs,
+                })
+            })
+            .collect();
+
+        Ok(updates)
+    }
+
+    /// Fetch a confirmed transaction's pre/post SPL token balances and diff
+    /// them by account index, via `getTransaction`. This is how
+    /// `SwapStreamService::decode_swap_event` recovers the mints and amounts
+    /// a `logsSubscribe` notification doesn't carry - the log lines alone
+    /// only confirm *that* a swap program ran, not which tokens moved.
+    pub async fn get_token_balance_deltas(&self, signature: &Signature) -> Result<Vec<TokenBalanceDelta>> {
+        use solana_client::rpc_config::RpcTransactionConfig;
+        use solana_transaction_status::UiTransactionEncoding;
+
+        let _permit = self
+            .request_limiter
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Request limiter closed: {}", e))?;
+
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let primary = self.primary.clone();
+        let signature = *signature;
+        let tx = match tokio::task::spawn_blocking(move || primary.get_transaction_with_config(&signature, config))
+            .await
+        {
+            Ok(Ok(tx)) => tx,
+            Ok(Err(e)) => return Err(e.into()),
+            Err(e) => return Err(anyhow::anyhow!("Task join error: {}", e)),
+        };
+
+        let meta = tx
+            .transaction
+            .meta
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} has no meta (still processing?)", signature))?;
+
+        let pre: Vec<_> = Option::<Vec<_>>::from(meta.pre_token_balances).unwrap_or_default();
+        let post: Vec<_> = Option::<Vec<_>>::from(meta.post_token_balances).unwrap_or_default();
+
+        let mut pre_amounts: std::collections::HashMap<u8, (String, u8, i128)> = std::collections::HashMap::new();
+        for balance in &pre {
+            let amount: i128 = balance.ui_token_amount.amount.parse().unwrap_or(0);
+            pre_amounts.insert(balance.account_index, (balance.mint.clone(), balance.ui_token_amount.decimals, amount));
+        }
+
+        let mut deltas = Vec::new();
+        let mut seen_indices = std::collections::HashSet::new();
+        for balance in &post {
+            seen_indices.insert(balance.account_index);
+            let post_amount: i128 = balance.ui_token_amount.amount.parse().unwrap_or(0);
+            let pre_amount = pre_amounts
+                .get(&balance.account_index)
+                .map(|(_, _, amount)| *amount)
+                .unwrap_or(0);
+            deltas.push(TokenBalanceDelta {
+                mint: balance.mint.clone(),
+                decimals: balance.ui_token_amount.decimals,
+                delta: post_amount - pre_amount,
+            });
+        }
+        // An account that was fully drained (closed) only shows up in `pre`.
+        for (index, (mint, decimals, amount)) in &pre_amounts {
+            if !seen_indices.contains(index) {
+                deltas.push(TokenBalanceDelta {
+                    mint: mint.clone(),
+                    decimals: *decimals,
+                    delta: -*amount,
+                });
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// Fetch per-slot prioritization fees paid by recent transactions, via
+    /// `getRecentPrioritizationFees`. When `addresses` is non-empty the
+    /// result is scoped to blocks that wrote to those accounts, which gives
+    /// a much more relevant signal than the chain-wide fee market.
+    pub async fn get_recent_prioritization_fees(&self, addresses: &[Pubkey]) -> Result<Vec<u64>> {
+        let addresses = addresses.to_vec();
+        let primary = self.primary.clone();
+        let addresses_clone = addresses.clone();
+        match tokio::task::spawn_blocking(move || {
+            primary.get_recent_prioritization_fees(&addresses_clone)
+        })
+        .await
+        {
+            Ok(Ok(fees)) => Ok(fees.into_iter().map(|f| f.prioritization_fee).collect()),
+            _ => {
+                for fallback in &self.fallbacks {
+                    let fallback_clone = fallback.clone();
+                    let addresses_clone = addresses.clone();
+                    match tokio::task::spawn_blocking(move || {
+                        fallback_clone.get_recent_prioritization_fees(&addresses_clone)
+                    })
+                    .await
+                    {
+                        Ok(Ok(fees)) => {
+                            return Ok(fees.into_iter().map(|f| f.prioritization_fee).collect())
+                        }
+                        _ => continue,
+                    }
+                }
+                anyhow::bail!("All RPC endpoints failed for get_recent_prioritization_fees")
+            }
+        }
     }
 }
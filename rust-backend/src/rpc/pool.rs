@@ -1,24 +1,35 @@
 use crate::config::RpcConfig;
+use crate::telemetry::Metrics;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Semaphore;
 
 pub struct RpcConnectionPool {
     semaphore: Arc<Semaphore>,
     config: RpcConfig,
+    metrics: Arc<Metrics>,
 }
 
 impl RpcConnectionPool {
-    pub async fn new(pool_size: usize, config: RpcConfig) -> anyhow::Result<Self> {
+    pub async fn new(pool_size: usize, config: RpcConfig, metrics: Arc<Metrics>) -> anyhow::Result<Self> {
         Ok(RpcConnectionPool {
             semaphore: Arc::new(Semaphore::new(pool_size)),
             config,
+            metrics,
         })
     }
 
+    /// Acquire a pool permit, recording how long the wait took under
+    /// `operation="pool_acquire"` so sustained contention shows up in
+    /// `/metrics` instead of only as elevated request latency.
     pub async fn acquire(&self) -> tokio::sync::SemaphorePermit<'_> {
-        self.semaphore.acquire().await.unwrap()
+        let started = Instant::now();
+        let permit = self.semaphore.acquire().await.unwrap();
+        let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+        self.metrics.observe("pool_acquire", "rpc", latency_ms, true).await;
+        permit
     }
 
     pub fn create_client(&self, url: String) -> RpcClient {
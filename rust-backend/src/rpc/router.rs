@@ -4,22 +4,200 @@
 /// Automatically routes requests to the fastest available endpoint.
 
 use anyhow::Result;
+use rand::Rng;
+use serde::Serialize;
 use solana_client::rpc_client::RpcClient as SolanaRpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
     signature::Signature,
     transaction::Transaction,
 };
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use super::metrics::{RpcMetrics, RpcMethodKind};
 use super::staked_nodes::{RpcProvider, StakedRpcEndpoint};
 
+/// Default probability of routing to a random non-incumbent endpoint instead
+/// of the current best, so stale or recovered endpoints keep getting
+/// re-sampled (modeled on Solana's `ClientOptimizer`).
+const DEFAULT_EXPLORE_RATIO: f64 = 0.10;
+
+/// Percentile used by [`EndpointStats::priority_score`] to rank endpoints,
+/// so a stable tail beats a good mean.
+const PRIORITY_PERCENTILE: f64 = 0.90;
+
+/// Latency histogram bucket upper bounds in milliseconds, plus one overflow
+/// bucket for anything above the last boundary.
+const LATENCY_BUCKET_BOUNDARIES_MS: [f64; 11] =
+    [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0];
+
+/// Fixed-bucket latency histogram with approximate percentile queries.
+///
+/// Cheap by design: recording is an O(log n) scan of the boundaries and a
+/// counter bump, and percentile queries walk cumulative bucket counts and
+/// linearly interpolate within the bucket containing the target rank.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; LATENCY_BUCKET_BOUNDARIES_MS.len() + 1],
+            count: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency_ms: f64) {
+        let bucket = LATENCY_BUCKET_BOUNDARIES_MS.partition_point(|&boundary| boundary < latency_ms);
+        self.buckets[bucket] += 1;
+        self.count += 1;
+    }
+
+    /// Approximate the `q`th percentile (0.0-1.0) in milliseconds.
+    fn percentile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return f64::INFINITY;
+        }
+
+        let target_rank = (q * self.count as f64).ceil().max(1.0);
+        let mut cumulative = 0u64;
+        let mut lower_bound = 0.0;
+
+        for (i, &bucket_count) in self.buckets.iter().enumerate() {
+            let upper_bound = LATENCY_BUCKET_BOUNDARIES_MS
+                .get(i)
+                .copied()
+                .unwrap_or(LATENCY_BUCKET_BOUNDARIES_MS[LATENCY_BUCKET_BOUNDARIES_MS.len() - 1] * 2.0);
+
+            if bucket_count > 0 && cumulative + bucket_count >= target_rank as u64 {
+                let rank_within_bucket = target_rank - cumulative as f64;
+                let fraction = rank_within_bucket / bucket_count as f64;
+                return lower_bound + fraction * (upper_bound - lower_bound);
+            }
+
+            cumulative += bucket_count;
+            lower_bound = upper_bound;
+        }
+
+        lower_bound
+    }
+
+    fn p50(&self) -> f64 {
+        self.percentile(0.50)
+    }
+
+    fn p90(&self) -> f64 {
+        self.percentile(0.90)
+    }
+
+    fn p99(&self) -> f64 {
+        self.percentile(0.99)
+    }
+}
+
+/// Circuit breaker cooldown the first time an endpoint trips.
+const INITIAL_BREAKER_COOLDOWN: Duration = Duration::from_secs(5);
+/// Ceiling on the exponentially grown cooldown after repeated trips.
+const MAX_BREAKER_COOLDOWN: Duration = Duration::from_secs(300);
+/// How long a `HalfOpen` probe may stay outstanding before it's considered
+/// abandoned and a fresh probe is granted, so a request that never reports
+/// back can't sideline the endpoint forever.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Circuit breaker state for an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Routing normally.
+    Closed,
+    /// Excluded from routing until the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; a single probe request is allowed through to
+    /// decide whether to close or re-open with a longer cooldown.
+    HalfOpen,
+}
+
+/// Per-endpoint circuit breaker with exponential-backoff cooldowns and
+/// half-open recovery probing, so a dead endpoint rejoins the rotation on
+/// its own instead of waiting for the periodic health check to happen to
+/// hit it (and instead of being sidelined forever by a stale success rate).
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    open_until: Instant,
+    cooldown: Duration,
+    probe_started_at: Option<Instant>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            open_until: Instant::now(),
+            cooldown: INITIAL_BREAKER_COOLDOWN,
+            probe_started_at: None,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Trip the breaker open, growing the cooldown exponentially if it was
+    /// already open or half-open (i.e. this is a repeated trip).
+    fn trip(&mut self) {
+        self.cooldown = if self.state == CircuitState::Closed {
+            INITIAL_BREAKER_COOLDOWN
+        } else {
+            (self.cooldown * 2).min(MAX_BREAKER_COOLDOWN)
+        };
+        self.state = CircuitState::Open;
+        self.open_until = Instant::now() + self.cooldown;
+        self.probe_started_at = None;
+    }
+
+    fn close(&mut self) {
+        self.state = CircuitState::Closed;
+        self.cooldown = INITIAL_BREAKER_COOLDOWN;
+        self.probe_started_at = None;
+    }
+
+    /// Whether a request should be routed to this endpoint right now.
+    /// Mutates `state` - `Open` flips to `HalfOpen` once the cooldown
+    /// elapses, granting exactly one probe.
+    fn allow_request(&mut self) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                if Instant::now() >= self.open_until {
+                    self.state = CircuitState::HalfOpen;
+                    self.probe_started_at = Some(Instant::now());
+                    true
+                } else {
+                    false
+                }
+            }
+            CircuitState::HalfOpen => match self.probe_started_at {
+                Some(started) if started.elapsed() < PROBE_TIMEOUT => false,
+                _ => {
+                    self.probe_started_at = Some(Instant::now());
+                    true
+                }
+            },
+        }
+    }
+}
+
 /// Endpoint health and latency statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EndpointStats {
     pub url: String,
     pub provider: RpcProvider,
@@ -28,18 +206,39 @@ pub struct EndpointStats {
     pub last_latency_ms: u64,
     /// Rolling average latency
     pub avg_latency_ms: u64,
+    /// Full latency distribution, so routing can favor stable tails rather
+    /// than just a good mean.
+    pub latency_histogram: LatencyHistogram,
     /// Success rate (0-100)
     pub success_rate: u8,
     /// Number of requests made
     pub total_requests: u64,
     /// Number of failed requests
     pub failed_requests: u64,
+    /// Slot observed on the last successful health check - lets `/rpc/status`
+    /// show whether an endpoint is keeping up with the chain, not just
+    /// whether it answers at all.
+    pub last_slot: Option<u64>,
     /// Last check time
+    #[serde(skip)]
     pub last_check: Instant,
-    /// Is currently available
-    pub is_available: bool,
+    /// Circuit breaker, wrapped so it stays mutable even through a shared
+    /// read lock on the endpoint list - selection only ever takes
+    /// `endpoints.read()`, but granting a half-open probe requires flipping
+    /// state at selection time.
+    #[serde(skip)]
+    circuit: Arc<Mutex<CircuitBreaker>>,
+    /// Last-observed circuit state, refreshed on every `record_success`/
+    /// `record_failure` call, for display/export; the live source of truth
+    /// for routing decisions is always `circuit` itself.
+    pub circuit_state: CircuitState,
     /// Weight for load balancing
     pub weight: u32,
+    /// `weight` scaled by measured success rate and tail latency - recomputed
+    /// on every `record_success`/`record_failure`, so traffic shifts toward
+    /// the fastest healthy staked node instead of staying pinned to
+    /// whatever static weight it was configured with.
+    pub effective_weight: u32,
 }
 
 impl EndpointStats {
@@ -50,23 +249,43 @@ impl EndpointStats {
             staked: endpoint.staked,
             last_latency_ms: 100, // Assume 100ms default
             avg_latency_ms: 100,
+            latency_histogram: LatencyHistogram::default(),
             success_rate: 100,
             total_requests: 0,
             failed_requests: 0,
+            last_slot: None,
             last_check: Instant::now(),
-            is_available: true,
+            circuit: Arc::new(Mutex::new(CircuitBreaker::default())),
+            circuit_state: CircuitState::Closed,
             weight: endpoint.weight,
+            effective_weight: endpoint.weight,
         }
     }
 
     fn record_success(&mut self, latency_ms: u64) {
         self.total_requests += 1;
         self.last_latency_ms = latency_ms;
-        // Exponential moving average
+        // Exponential moving average (kept for display; routing uses the
+        // histogram's p90 below)
         self.avg_latency_ms = (self.avg_latency_ms * 9 + latency_ms) / 10;
+        self.latency_histogram.record(latency_ms as f64);
         self.update_success_rate();
         self.last_check = Instant::now();
-        self.is_available = true;
+
+        let mut breaker = self.circuit.lock().unwrap();
+        if breaker.state != CircuitState::Closed {
+            // Probe succeeded (or a success slipped in while open, e.g. a
+            // hedged race already in flight) - close the breaker and decay
+            // the failure counters so stale lifetime stats don't keep
+            // dragging success_rate/priority_score down forever.
+            breaker.close();
+            self.total_requests = 1;
+            self.failed_requests = 0;
+            self.success_rate = 100;
+        }
+        self.circuit_state = breaker.state;
+        drop(breaker);
+        self.recompute_effective_weight();
     }
 
     fn record_failure(&mut self) {
@@ -74,11 +293,37 @@ impl EndpointStats {
         self.failed_requests += 1;
         self.update_success_rate();
         self.last_check = Instant::now();
-        
-        // Mark as unavailable if too many failures
-        if self.success_rate < 50 {
-            self.is_available = false;
+
+        let mut breaker = self.circuit.lock().unwrap();
+        if self.success_rate < 50 || breaker.state == CircuitState::HalfOpen {
+            breaker.trip();
         }
+        self.circuit_state = breaker.state;
+        drop(breaker);
+        self.recompute_effective_weight();
+    }
+
+    /// Scale the configured `weight` by measured tail latency and success
+    /// rate: a dead or consistently-slow endpoint decays toward zero weight,
+    /// a fast healthy one is boosted, within a bounded multiplier so one
+    /// great sample can't instantly dominate routing.
+    fn recompute_effective_weight(&mut self) {
+        if self.circuit_state == CircuitState::Open {
+            self.effective_weight = 0;
+            return;
+        }
+
+        let tail_latency_ms = self.latency_histogram.percentile(PRIORITY_PERCENTILE);
+        let speed_factor = if tail_latency_ms.is_finite() && tail_latency_ms > 0.0 {
+            (100.0 / tail_latency_ms).clamp(0.1, 10.0)
+        } else {
+            1.0
+        };
+        let success_factor = self.success_rate as f64 / 100.0;
+
+        self.effective_weight = ((self.weight.max(1) as f64) * speed_factor * success_factor)
+            .round()
+            .max(1.0) as u32;
     }
 
     fn update_success_rate(&mut self) {
@@ -88,20 +333,62 @@ impl EndpointStats {
         }
     }
 
-    /// Calculate priority score (lower is better)
+    /// The 50th/90th/99th percentile latency observed so far, in ms.
+    pub fn p50(&self) -> f64 {
+        self.latency_histogram.p50()
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.latency_histogram.p90()
+    }
+
+    pub fn p99(&self) -> f64 {
+        self.latency_histogram.p99()
+    }
+
+    /// Whether this endpoint should be considered for routing right now.
+    /// Queries (and, if a cooldown just elapsed, mutates) the circuit
+    /// breaker - call this exactly once per selection pass per endpoint, as
+    /// it's what grants a half-open probe.
+    fn is_available(&self) -> bool {
+        self.circuit.lock().unwrap().allow_request()
+    }
+
+    /// Non-mutating health read for display purposes (e.g. `/rpc/status`) -
+    /// unlike `is_available`, this never consumes a half-open probe slot.
+    pub fn is_healthy(&self) -> bool {
+        self.circuit_state != CircuitState::Open
+    }
+
+    /// Calculate priority score (lower is better). Driven by
+    /// [`PRIORITY_PERCENTILE`] rather than the EMA so routing favors
+    /// endpoints with a stable tail over ones with merely a good mean. Uses
+    /// the cached `circuit_state` rather than `is_available()` so scoring
+    /// doesn't itself consume a half-open probe slot that selection's
+    /// `is_available()` filter already granted.
     fn priority_score(&self) -> u64 {
-        if !self.is_available {
+        if self.circuit_state == CircuitState::Open {
             return u64::MAX;
         }
 
+        let tail_latency_ms = self.latency_histogram.percentile(PRIORITY_PERCENTILE);
+        let tail_latency_ms = if tail_latency_ms.is_finite() {
+            tail_latency_ms as u64
+        } else {
+            self.avg_latency_ms
+        };
+
         // Combine latency and success rate for scoring
         // Staked nodes get a bonus (multiply by 0.8)
         let staked_bonus = if self.staked { 80 } else { 100 };
-        let latency_score = self.avg_latency_ms * staked_bonus / 100;
-        
-        // Weight affects priority inversely
-        let weight_factor = 100 / self.weight.max(1) as u64;
-        
+        let latency_score = tail_latency_ms * staked_bonus / 100;
+
+        // Weight affects priority inversely - uses the dynamically adjusted
+        // `effective_weight` rather than the static configured `weight`, so
+        // scoring already reflects measured health on top of the tail
+        // latency above.
+        let weight_factor = 100 / self.effective_weight.max(1) as u64;
+
         latency_score * weight_factor
     }
 }
@@ -112,6 +399,66 @@ pub struct RpcRouter {
     endpoints: RwLock<Vec<(StakedRpcEndpoint, EndpointStats, Arc<SolanaRpcClient>)>>,
     /// Health check interval
     health_check_interval: Duration,
+    /// Probability of exploring a random non-incumbent endpoint instead of
+    /// exploiting the current best (see [`DEFAULT_EXPLORE_RATIO`]).
+    explore_ratio: f64,
+    /// Counts every exploration draw, win or lose - useful for tuning
+    /// `explore_ratio` against observed churn.
+    experiment_count: AtomicU64,
+    /// Number of staked endpoints fanned out to by
+    /// [`Self::send_transaction_hedged`].
+    hedge_fanout: usize,
+    /// Aggregate request counters broken down by provider and method, for
+    /// operator-facing observability beyond per-endpoint `EndpointStats`.
+    metrics: Arc<RpcMetrics>,
+}
+
+/// Default number of staked endpoints raced concurrently by
+/// `send_transaction_hedged`.
+const DEFAULT_HEDGE_FANOUT: usize = 3;
+
+/// Default ceiling for `send_transaction_with_retries`.
+pub const MAX_RPC_CALL_RETRIES: u32 = 5;
+
+/// How many slots behind the cluster's observed max an endpoint can fall
+/// before `health_check` treats it as a failure - a slot or two of drift
+/// between endpoints is normal, more than that means it isn't keeping up
+/// with the chain regardless of how fast it answers `getSlot`.
+const MAX_SLOT_LAG: u64 = 8;
+
+/// Outcome of a single attempt inside `send_transaction_with_retries`, so
+/// callers can tell a transient drop from a preflight rejection instead of
+/// only seeing the final error.
+#[derive(Debug, Clone)]
+pub enum SendAttemptOutcome {
+    /// The endpoint accepted the transaction and returned a signature.
+    Landed { endpoint: String, signature: Signature },
+    /// Transient failure (timeout, rate limit, node behind) - worth retrying.
+    Dropped { endpoint: String, reason: String },
+    /// The node rejected the transaction outright (e.g. simulation failure);
+    /// retrying the same bytes won't help.
+    FailedPreflight { endpoint: String, reason: String },
+    /// The blockhash expired; a fresh one was fetched and the transaction
+    /// was rebuilt before the next attempt.
+    BlockhashExpired { endpoint: String },
+}
+
+/// Result of `send_transaction_with_retries`: the winning signature plus the
+/// full attempt history for observability.
+#[derive(Debug, Clone)]
+pub struct RetrySendResult {
+    pub signature: Signature,
+    pub attempts: Vec<SendAttemptOutcome>,
+}
+
+fn is_blockhash_expired(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    lower.contains("blockhash not found") || lower.contains("block height exceeded")
+}
+
+fn is_preflight_rejection(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    lower.contains("preflight") || lower.contains("simulation failed") || lower.contains("insufficient funds")
 }
 
 impl RpcRouter {
@@ -133,29 +480,113 @@ impl RpcRouter {
         Ok(Self {
             endpoints: RwLock::new(endpoint_data),
             health_check_interval: Duration::from_secs(30),
+            explore_ratio: DEFAULT_EXPLORE_RATIO,
+            experiment_count: AtomicU64::new(0),
+            hedge_fanout: DEFAULT_HEDGE_FANOUT,
+            metrics: Arc::new(RpcMetrics::new()),
         })
     }
 
-    /// Get the best endpoint based on latency and availability
+    /// The request-metrics informant for this router. Callers can clone the
+    /// `Arc` to export `snapshot()` from a metrics endpoint, or call
+    /// `spawn_summary_log_loop` once at startup.
+    pub fn metrics(&self) -> Arc<RpcMetrics> {
+        self.metrics.clone()
+    }
+
+    async fn provider_for(&self, client: &Arc<SolanaRpcClient>) -> RpcProvider {
+        self.endpoints
+            .read()
+            .await
+            .iter()
+            .find(|(_, _, c)| Arc::ptr_eq(c, client))
+            .map(|(ep, _, _)| ep.provider)
+            .unwrap_or(RpcProvider::Custom)
+    }
+
+    /// Override the default exploration probability.
+    pub fn with_explore_ratio(mut self, explore_ratio: f64) -> Self {
+        self.explore_ratio = explore_ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Override the default number of staked endpoints raced by
+    /// `send_transaction_hedged`.
+    pub fn with_hedge_fanout(mut self, hedge_fanout: usize) -> Self {
+        self.hedge_fanout = hedge_fanout.max(1);
+        self
+    }
+
+    /// Number of exploration draws made so far.
+    pub fn experiment_count(&self) -> u64 {
+        self.experiment_count.load(Ordering::Relaxed)
+    }
+
+    /// Pick the exploit (min-score) candidate or, with probability
+    /// `explore_ratio`, a uniformly random *other* available candidate -
+    /// Solana's `ClientOptimizer` epsilon-greedy strategy. The explored
+    /// endpoint's measured latency feeds its stats, so once its score beats
+    /// the incumbent's it becomes the min-score pick on later calls, and a
+    /// recovered endpoint doesn't need to wait for the incumbent to visibly
+    /// degrade first.
+    fn epsilon_greedy_pick<'a>(
+        &self,
+        candidates: &'a [(&'a StakedRpcEndpoint, &'a EndpointStats, &'a Arc<SolanaRpcClient>)],
+    ) -> Option<&'a Arc<SolanaRpcClient>> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let incumbent = candidates
+            .iter()
+            .min_by_key(|(_, stats, _)| stats.priority_score())?;
+
+        if candidates.len() == 1 || rand::thread_rng().gen::<f64>() >= self.explore_ratio {
+            return Some(incumbent.2);
+        }
+
+        self.experiment_count.fetch_add(1, Ordering::Relaxed);
+
+        // Always route the exploration request itself to the random pick so
+        // its latency gets measured; it's already "promoted" ahead of the
+        // incumbent for this call since we're returning it regardless of
+        // score, exactly like a recovered endpoint needs to be re-tried to
+        // prove itself before `priority_score` alone would pick it again.
+        let others: Vec<_> = candidates
+            .iter()
+            .filter(|(ep, _, _)| ep.url != incumbent.0.url)
+            .collect();
+        let explored = others[rand::thread_rng().gen_range(0..others.len())];
+
+        Some(explored.2)
+    }
+
+    /// Get the best endpoint based on latency and availability, with
+    /// epsilon-greedy exploration of other available endpoints.
     pub async fn get_best_endpoint(&self) -> Option<Arc<SolanaRpcClient>> {
         let endpoints = self.endpoints.read().await;
-        
-        endpoints
+
+        let candidates: Vec<_> = endpoints
             .iter()
-            .filter(|(_, stats, _)| stats.is_available)
-            .min_by_key(|(_, stats, _)| stats.priority_score())
-            .map(|(_, _, client)| client.clone())
+            .filter(|(_, stats, _)| stats.is_available())
+            .map(|(ep, stats, client)| (ep, stats, client))
+            .collect();
+
+        self.epsilon_greedy_pick(&candidates).cloned()
     }
 
-    /// Get the best staked endpoint (for transaction sending)
+    /// Get the best staked endpoint (for transaction sending), with the same
+    /// epsilon-greedy exploration as [`Self::get_best_endpoint`].
     pub async fn get_best_staked_endpoint(&self) -> Option<Arc<SolanaRpcClient>> {
         let endpoints = self.endpoints.read().await;
-        
-        endpoints
+
+        let candidates: Vec<_> = endpoints
             .iter()
-            .filter(|(ep, stats, _)| stats.is_available && ep.staked && ep.send_transactions)
-            .min_by_key(|(_, stats, _)| stats.priority_score())
-            .map(|(_, _, client)| client.clone())
+            .filter(|(ep, stats, _)| stats.is_available() && ep.staked && ep.send_transactions)
+            .map(|(ep, stats, client)| (ep, stats, client))
+            .collect();
+
+        self.epsilon_greedy_pick(&candidates).cloned()
     }
 
     /// Get all available endpoints sorted by priority
@@ -164,7 +595,7 @@ impl RpcRouter {
         
         let mut available: Vec<_> = endpoints
             .iter()
-            .filter(|(_, stats, _)| stats.is_available)
+            .filter(|(_, stats, _)| stats.is_available())
             .collect();
         
         available.sort_by_key(|(_, stats, _)| stats.priority_score());
@@ -175,29 +606,36 @@ impl RpcRouter {
     /// Send transaction using the best available endpoint with failover
     pub async fn send_transaction(&self, tx: &Transaction) -> Result<Signature> {
         let endpoints = self.get_sorted_endpoints().await;
-        
+
         if endpoints.is_empty() {
             anyhow::bail!("No available RPC endpoints");
         }
 
+        let mut tried_staked = false;
+
         // Try staked endpoints first
         if let Some(staked) = self.get_best_staked_endpoint().await {
+            tried_staked = true;
             let start = Instant::now();
             let tx_clone = tx.clone();
             let staked_clone = staked.clone();
-            
+            let provider = self.provider_for(&staked).await;
+
             match tokio::task::spawn_blocking(move || staked_clone.send_transaction(&tx_clone)).await {
                 Ok(Ok(sig)) => {
                     self.record_success(&staked, start.elapsed().as_millis() as u64).await;
+                    self.metrics.record_success(provider, RpcMethodKind::SendTransaction).await;
                     return Ok(sig);
                 }
                 Ok(Err(e)) => {
                     warn!("Staked endpoint failed: {}", e);
                     self.record_failure(&staked).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::SendTransaction).await;
                 }
                 Err(e) => {
                     warn!("Staked endpoint task error: {}", e);
                     self.record_failure(&staked).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::SendTransaction).await;
                 }
             }
         }
@@ -207,18 +645,25 @@ impl RpcRouter {
             let start = Instant::now();
             let tx_clone = tx.clone();
             let client_clone = client.clone();
-            
+            let provider = self.provider_for(client).await;
+            if tried_staked {
+                self.metrics.record_failover(provider).await;
+            }
+
             match tokio::task::spawn_blocking(move || client_clone.send_transaction(&tx_clone)).await {
                 Ok(Ok(sig)) => {
                     self.record_success(client, start.elapsed().as_millis() as u64).await;
+                    self.metrics.record_success(provider, RpcMethodKind::SendTransaction).await;
                     return Ok(sig);
                 }
                 Ok(Err(e)) => {
                     debug!("Endpoint failed: {}", e);
                     self.record_failure(client).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::SendTransaction).await;
                 }
                 Err(_) => {
                     self.record_failure(client).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::SendTransaction).await;
                 }
             }
         }
@@ -226,6 +671,200 @@ impl RpcRouter {
         anyhow::bail!("All RPC endpoints failed for send_transaction")
     }
 
+    /// Fan the signed transaction out to the top `hedge_fanout` staked
+    /// endpoints concurrently and return as soon as any of them lands it,
+    /// instead of paying each endpoint's timeout sequentially like
+    /// `send_transaction` does. Mirrors lite-rpc spreading a transaction to
+    /// many stake-weighted nodes to minimize inclusion latency.
+    pub async fn send_transaction_hedged(&self, tx: &Transaction) -> Result<Signature> {
+        let candidates: Vec<Arc<SolanaRpcClient>> = {
+            let endpoints = self.endpoints.read().await;
+            let mut staked: Vec<_> = endpoints
+                .iter()
+                .filter(|(ep, stats, _)| stats.is_available() && ep.staked && ep.send_transactions)
+                .collect();
+            staked.sort_by_key(|(_, stats, _)| stats.priority_score());
+            staked
+                .into_iter()
+                .take(self.hedge_fanout)
+                .map(|(_, _, client)| client.clone())
+                .collect()
+        };
+
+        if candidates.is_empty() {
+            anyhow::bail!("No available staked RPC endpoints for send_transaction_hedged");
+        }
+
+        let mut in_flight = tokio::task::JoinSet::new();
+        for client in candidates {
+            let tx_clone = tx.clone();
+            let client_for_send = client.clone();
+            in_flight.spawn(async move {
+                let start = Instant::now();
+                let result =
+                    tokio::task::spawn_blocking(move || client_for_send.send_transaction(&tx_clone))
+                        .await;
+                (client, start.elapsed().as_millis() as u64, result)
+            });
+        }
+
+        let mut winner: Option<Signature> = None;
+        let mut last_err: Option<anyhow::Error> = None;
+
+        while let Some(joined) = in_flight.join_next().await {
+            let (client, latency_ms, result) = match joined {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    last_err = Some(anyhow::anyhow!("hedged task panicked: {e}"));
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(Ok(sig)) => {
+                    if winner.is_none() {
+                        self.record_success(&client, latency_ms).await;
+                        winner = Some(sig);
+                        // Dedupe: any other endpoint landing the same
+                        // signature is just corroboration, not a second
+                        // win. Drop the remaining in-flight attempts since
+                        // we already have a landed signature.
+                        in_flight.abort_all();
+                    } else {
+                        self.record_success(&client, latency_ms).await;
+                    }
+                }
+                Ok(Err(e)) => {
+                    self.record_failure(&client).await;
+                    last_err = Some(anyhow::anyhow!(e));
+                }
+                Err(e) => {
+                    self.record_failure(&client).await;
+                    last_err = Some(anyhow::anyhow!("hedged send failed: {e}"));
+                }
+            }
+        }
+
+        winner.ok_or_else(|| {
+            last_err.unwrap_or_else(|| anyhow::anyhow!("All hedged RPC endpoints failed"))
+        })
+    }
+
+    /// Send with exponential-backoff retries for transient RPC errors.
+    ///
+    /// Unlike `send_transaction`, a blockhash-expired error is recoverable:
+    /// if `rebuild` is supplied, a fresh `get_latest_blockhash` is fetched
+    /// from the best available endpoint and used to rebuild and re-sign the
+    /// transaction before the next attempt. Without `rebuild`, a
+    /// blockhash-expired error is still recorded as an attempt but retried
+    /// with the same bytes, which will keep failing until the caller
+    /// resubmits with a fresh blockhash itself.
+    pub async fn send_transaction_with_retries<F>(
+        &self,
+        mut tx: Transaction,
+        max_retries: u32,
+        rebuild: Option<F>,
+    ) -> Result<RetrySendResult>
+    where
+        F: Fn(Hash) -> Transaction,
+    {
+        let max_retries = max_retries.max(1);
+        let mut attempts = Vec::new();
+        let mut backoff = Duration::from_millis(200);
+
+        for attempt in 0..max_retries {
+            let client = match self.get_best_staked_endpoint().await {
+                Some(client) => client,
+                None => self
+                    .get_best_endpoint()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("No available RPC endpoints"))?,
+            };
+            let endpoint_url = {
+                let endpoints = self.endpoints.read().await;
+                endpoints
+                    .iter()
+                    .find(|(_, _, c)| Arc::ptr_eq(c, &client))
+                    .map(|(ep, _, _)| ep.url.clone())
+                    .unwrap_or_else(|| "unknown".to_string())
+            };
+
+            let provider = self.provider_for(&client).await;
+            let start = Instant::now();
+            let tx_clone = tx.clone();
+            let client_clone = client.clone();
+            let result =
+                tokio::task::spawn_blocking(move || client_clone.send_transaction(&tx_clone)).await;
+
+            match result {
+                Ok(Ok(sig)) => {
+                    self.record_success(&client, start.elapsed().as_millis() as u64).await;
+                    self.metrics.record_success(provider, RpcMethodKind::Generic).await;
+                    attempts.push(SendAttemptOutcome::Landed {
+                        endpoint: endpoint_url,
+                        signature: sig,
+                    });
+                    return Ok(RetrySendResult { signature: sig, attempts });
+                }
+                Ok(Err(e)) => {
+                    self.record_failure(&client).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::Generic).await;
+                    let message = e.to_string();
+
+                    if is_blockhash_expired(&message) {
+                        attempts.push(SendAttemptOutcome::BlockhashExpired {
+                            endpoint: endpoint_url.clone(),
+                        });
+                        if let Some(rebuild_fn) = rebuild.as_ref() {
+                            let blockhash_client = client.clone();
+                            let blockhash = tokio::task::spawn_blocking(move || {
+                                blockhash_client.get_latest_blockhash()
+                            })
+                            .await
+                            .map_err(|e| anyhow::anyhow!("Blockhash fetch task panicked: {}", e))??;
+                            tx = rebuild_fn(blockhash);
+                        } else {
+                            warn!(
+                                "Blockhash expired on {} with no rebuild closure supplied; retrying as-is",
+                                endpoint_url
+                            );
+                        }
+                    } else if is_preflight_rejection(&message) {
+                        attempts.push(SendAttemptOutcome::FailedPreflight {
+                            endpoint: endpoint_url,
+                            reason: message,
+                        });
+                    } else {
+                        attempts.push(SendAttemptOutcome::Dropped {
+                            endpoint: endpoint_url,
+                            reason: message,
+                        });
+                    }
+                }
+                Err(e) => {
+                    self.record_failure(&client).await;
+                    self.metrics.record_failure(provider, RpcMethodKind::Generic).await;
+                    attempts.push(SendAttemptOutcome::Dropped {
+                        endpoint: endpoint_url,
+                        reason: format!("task panicked: {}", e),
+                    });
+                }
+            }
+
+            if attempt + 1 < max_retries {
+                self.metrics.record_retry(provider).await;
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(Duration::from_secs(5));
+            }
+        }
+
+        anyhow::bail!(
+            "send_transaction_with_retries exhausted {} attempts: {:?}",
+            max_retries,
+            attempts
+        )
+    }
+
     /// Record a successful request
     async fn record_success(&self, client: &Arc<SolanaRpcClient>, latency_ms: u64) {
         let mut endpoints = self.endpoints.write().await;
@@ -248,38 +887,97 @@ impl RpcRouter {
         }
     }
 
-    /// Run health checks on all endpoints
+    /// Run health checks on all endpoints. A lightweight `getSlot` call
+    /// doubles as both a liveness probe and a staleness signal: its latency
+    /// feeds the same histogram real traffic does, and the returned slot is
+    /// recorded so `/rpc/status` can show whether an endpoint is keeping up
+    /// with the chain rather than just answering at all. Probing happens in
+    /// two passes so the second pass can compare every endpoint against the
+    /// cluster's max observed slot from *this same round* rather than a
+    /// value measured on a previous, possibly stale, run.
     pub async fn health_check(&self) {
-        // Collect client clones and provider names first (release read lock)
-        let checks: Vec<(Arc<SolanaRpcClient>, String)> = {
+        // Collect client clones and providers first (release read lock)
+        let checks: Vec<(Arc<SolanaRpcClient>, RpcProvider)> = {
             let endpoints = self.endpoints.read().await;
             endpoints.iter()
-                .map(|(ep, _, client)| (client.clone(), ep.provider.name().to_string()))
+                .map(|(ep, _, client)| (client.clone(), ep.provider))
                 .collect()
         };
-        
-        for (client, provider_name) in checks {
+
+        let mut probed = Vec::with_capacity(checks.len());
+        for (client, provider) in checks {
             let start = Instant::now();
             let client_clone = client.clone();
-            
+
             let result = tokio::task::spawn_blocking(move || client_clone.get_slot())
                 .await;
-            
+
             let latency = start.elapsed().as_millis() as u64;
-            
+            probed.push((client, provider, result, latency));
+        }
+
+        let cluster_max_slot = probed
+            .iter()
+            .filter_map(|(_, _, result, _)| result.as_ref().ok().and_then(|r| r.as_ref().ok()).copied())
+            .max();
+
+        for (client, provider, result, latency) in probed {
             match result {
-                Ok(Ok(_)) => {
-                    self.record_success(&client, latency).await;
-                    debug!("{} health check OK ({}ms)", provider_name, latency);
+                Ok(Ok(slot)) => {
+                    self.record_slot(&client, slot).await;
+
+                    let behind = cluster_max_slot.map(|max| max.saturating_sub(slot)).unwrap_or(0);
+                    if behind > MAX_SLOT_LAG {
+                        // Answers fine, but it isn't keeping up with the
+                        // chain - treat it as a failure so the circuit
+                        // breaker and `effective_weight` demote it the same
+                        // way they would an outright error, rather than
+                        // bolting on a second ejection/cooldown mechanism.
+                        self.record_failure(&client).await;
+                        self.metrics.record_failure(provider, RpcMethodKind::HealthCheck).await;
+                        warn!(
+                            "{} health check OK but {} slots behind cluster max (slot {})",
+                            provider.name(), behind, slot
+                        );
+                    } else {
+                        self.record_success(&client, latency).await;
+                        self.metrics.record_success(provider, RpcMethodKind::HealthCheck).await;
+                        debug!("{} health check OK ({}ms, slot {})", provider.name(), latency, slot);
+                    }
                 }
                 _ => {
                     self.record_failure(&client).await;
-                    warn!("{} health check FAILED", provider_name);
+                    self.metrics.record_failure(provider, RpcMethodKind::HealthCheck).await;
+                    warn!("{} health check FAILED", provider.name());
                 }
             }
         }
     }
 
+    /// Record the slot observed on a successful health check.
+    async fn record_slot(&self, client: &Arc<SolanaRpcClient>, slot: u64) {
+        let mut endpoints = self.endpoints.write().await;
+        for (_, stats, c) in endpoints.iter_mut() {
+            if Arc::ptr_eq(c, client) {
+                stats.last_slot = Some(slot);
+                break;
+            }
+        }
+    }
+
+    /// Run `health_check` on `health_check_interval`, forever. Spawned once
+    /// at startup so `/rpc/status` reflects measured health instead of the
+    /// placeholder "always available" response it used to return.
+    pub fn spawn_health_check_loop(self: Arc<Self>) {
+        let interval = self.health_check_interval;
+        tokio::spawn(async move {
+            loop {
+                self.health_check().await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
     /// Get statistics for all endpoints
     pub async fn get_stats(&self) -> Vec<EndpointStats> {
         let endpoints = self.endpoints.read().await;
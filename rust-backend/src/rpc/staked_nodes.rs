@@ -162,6 +162,118 @@ impl StakedRpcEndpoint {
     }
 }
 
+/// `weight` divisor applied to an endpoint that fails [`verify_staked_endpoint`],
+/// so it's still usable as a last resort but `RpcRouter`'s priority-score
+/// routing strongly prefers everything else first.
+const DEMOTED_WEIGHT_DIVISOR: u32 = 10;
+
+/// Outcome of [`verify_staked_endpoint`] for one endpoint.
+#[derive(Debug, Clone)]
+pub struct NodeVerificationReport {
+    pub verified: bool,
+    /// Why verification failed; `None` when `verified` is true.
+    pub reason: Option<String>,
+}
+
+/// Verify a `staked: true` endpoint's claim against gossip cluster info
+/// rather than trusting the hard-coded constructor.
+///
+/// Fetches `getClusterNodes` and the endpoint's own `getIdentity`, then
+/// checks that: the node's pubkey actually appears in the cluster node
+/// list, its `shred_version` matches the version most other cluster nodes
+/// report (a mismatched shred version means it's on a different/stale
+/// cluster fork of gossip), and it advertises a reachable gossip and TPU
+/// address. On any failure the endpoint's `staked` flag is cleared and its
+/// `weight` divided down by [`DEMOTED_WEIGHT_DIVISOR`] so it falls to the
+/// back of the routing queue instead of silently keeping its trusted
+/// status.
+pub async fn verify_staked_endpoint(
+    endpoint: &mut StakedRpcEndpoint,
+) -> anyhow::Result<NodeVerificationReport> {
+    use solana_client::rpc_client::RpcClient;
+
+    let url = endpoint.url.clone();
+    let client = RpcClient::new(url);
+
+    let identity_client = RpcClient::new(endpoint.url.clone());
+    let identity = tokio::task::spawn_blocking(move || identity_client.get_identity())
+        .await
+        .map_err(|e| anyhow::anyhow!("getIdentity task panicked: {}", e))??;
+
+    let cluster_nodes = tokio::task::spawn_blocking(move || client.get_cluster_nodes())
+        .await
+        .map_err(|e| anyhow::anyhow!("getClusterNodes task panicked: {}", e))??;
+
+    let report = evaluate_node_verification(&identity.to_string(), &cluster_nodes);
+
+    if !report.verified {
+        endpoint.staked = false;
+        endpoint.weight = (endpoint.weight / DEMOTED_WEIGHT_DIVISOR).max(1);
+    }
+
+    Ok(report)
+}
+
+/// Pure decision logic behind [`verify_staked_endpoint`], split out so the
+/// gossip-mismatch/missing-address cases are unit-testable without a live
+/// `getIdentity`/`getClusterNodes` round trip.
+fn evaluate_node_verification(
+    identity: &str,
+    cluster_nodes: &[solana_client::rpc_response::RpcContactInfo],
+) -> NodeVerificationReport {
+    let cluster_shred_version = most_common_shred_version(cluster_nodes);
+    let node = cluster_nodes.iter().find(|n| n.pubkey == identity);
+
+    match node {
+        None => NodeVerificationReport {
+            verified: false,
+            reason: Some(format!("identity {} not found in getClusterNodes", identity)),
+        },
+        Some(node) => {
+            if node.gossip.is_none() {
+                NodeVerificationReport {
+                    verified: false,
+                    reason: Some("node advertises no reachable gossip address".to_string()),
+                }
+            } else if node.tpu.is_none() {
+                NodeVerificationReport {
+                    verified: false,
+                    reason: Some("node advertises no reachable TPU address".to_string()),
+                }
+            } else if cluster_shred_version.is_some() && node.shred_version != cluster_shred_version {
+                NodeVerificationReport {
+                    verified: false,
+                    reason: Some(format!(
+                        "shred version {:?} does not match cluster's {:?}",
+                        node.shred_version, cluster_shred_version
+                    )),
+                }
+            } else {
+                NodeVerificationReport {
+                    verified: true,
+                    reason: None,
+                }
+            }
+        }
+    }
+}
+
+/// The most frequently reported `shred_version` across `nodes`, ignoring
+/// unset ones - a healthy cluster has every node on the same version, so the
+/// mode is a reasonable proxy for "the cluster's current shred version"
+/// without a dedicated RPC method to ask for it directly.
+fn most_common_shred_version(
+    nodes: &[solana_client::rpc_response::RpcContactInfo],
+) -> Option<u16> {
+    let mut counts: HashMap<u16, u32> = HashMap::new();
+    for node in nodes {
+        if let Some(version) = node.shred_version {
+            *counts.entry(version).or_insert(0) += 1;
+        }
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count).map(|(version, _)| version)
+}
+
 /// Build list of staked endpoints from environment variables
 pub fn build_staked_endpoints() -> Vec<StakedRpcEndpoint> {
     let mut endpoints = Vec::new();
@@ -193,3 +305,74 @@ pub fn build_staked_endpoints() -> Vec<StakedRpcEndpoint> {
 
     endpoints
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcContactInfo;
+
+    fn contact_info(
+        pubkey: &str,
+        gossip: Option<std::net::SocketAddr>,
+        tpu: Option<std::net::SocketAddr>,
+        shred_version: Option<u16>,
+    ) -> RpcContactInfo {
+        RpcContactInfo {
+            pubkey: pubkey.to_string(),
+            gossip,
+            tpu,
+            rpc: None,
+            pubsub: None,
+            version: None,
+            feature_set: None,
+            shred_version,
+        }
+    }
+
+    fn addr() -> std::net::SocketAddr {
+        "127.0.0.1:8001".parse().unwrap()
+    }
+
+    #[test]
+    fn test_evaluate_node_verification_passes_matching_shred_version() {
+        let nodes = vec![
+            contact_info("node-a", Some(addr()), Some(addr()), Some(42)),
+            contact_info("node-b", Some(addr()), Some(addr()), Some(42)),
+        ];
+
+        let report = evaluate_node_verification("node-a", &nodes);
+        assert!(report.verified);
+        assert!(report.reason.is_none());
+    }
+
+    #[test]
+    fn test_evaluate_node_verification_rejects_shred_version_mismatch() {
+        let nodes = vec![
+            contact_info("node-a", Some(addr()), Some(addr()), Some(99)),
+            contact_info("node-b", Some(addr()), Some(addr()), Some(42)),
+            contact_info("node-c", Some(addr()), Some(addr()), Some(42)),
+        ];
+
+        let report = evaluate_node_verification("node-a", &nodes);
+        assert!(!report.verified);
+        assert!(report.reason.unwrap().contains("shred version"));
+    }
+
+    #[test]
+    fn test_evaluate_node_verification_rejects_missing_gossip_address() {
+        let nodes = vec![contact_info("node-a", None, Some(addr()), Some(42))];
+
+        let report = evaluate_node_verification("node-a", &nodes);
+        assert!(!report.verified);
+        assert!(report.reason.unwrap().contains("gossip"));
+    }
+
+    #[test]
+    fn test_evaluate_node_verification_rejects_unknown_identity() {
+        let nodes = vec![contact_info("node-a", Some(addr()), Some(addr()), Some(42))];
+
+        let report = evaluate_node_verification("node-z", &nodes);
+        assert!(!report.verified);
+        assert!(report.reason.unwrap().contains("not found"));
+    }
+}
@@ -0,0 +1,100 @@
+/// Direct TPU transaction broadcast.
+///
+/// Wraps `solana_client::tpu_client::TpuClient`, which resolves the cluster's
+/// leader schedule and gossip contact info and keeps a pool of connections
+/// open to the next few leaders' TPU ports. Sending straight to the leader
+/// cuts the RPC-forwarding hop off the critical path, which matters most
+/// during congestion when RPC providers themselves start queuing or
+/// dropping transactions.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use solana_client::rpc_client::RpcClient as SolanaRpcClient;
+use solana_client::tpu_client::{TpuClient, TpuClientConfig};
+use solana_sdk::transaction::Transaction;
+use tracing::warn;
+
+/// Number of upcoming slots whose leaders a transaction is fanned out to by
+/// default (`TpuClientConfig::fanout_slots`), which covers the handful of
+/// slots around a leader rotation boundary.
+pub const DEFAULT_TPU_FANOUT: usize = 4;
+
+/// Per-attempt send outcomes, so callers can compute a landing rate instead
+/// of just seeing individual successes/failures.
+#[derive(Debug, Default)]
+pub struct TpuOutcomeCounters {
+    attempts: AtomicU64,
+    accepted: AtomicU64,
+}
+
+impl TpuOutcomeCounters {
+    fn record(&self, accepted: bool) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+        if accepted {
+            self.accepted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn attempts(&self) -> u64 {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    pub fn accepted(&self) -> u64 {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of attempts that were queued onto at least one leader
+    /// connection. `0.0` when nothing has been sent yet.
+    pub fn landing_rate(&self) -> f64 {
+        let attempts = self.attempts();
+        if attempts == 0 {
+            0.0
+        } else {
+            self.accepted() as f64 / attempts as f64
+        }
+    }
+}
+
+/// Broadcasts transactions directly to the current/upcoming leaders' TPU
+/// sockets instead of routing through an RPC provider.
+pub struct TpuBroadcaster {
+    client: TpuClient,
+    counters: TpuOutcomeCounters,
+}
+
+impl TpuBroadcaster {
+    /// Build a broadcaster for the given RPC client, resolving leader
+    /// schedule and gossip contact info up front. This dials gossip, so it's
+    /// blocking - callers construct it from `spawn_blocking`.
+    pub fn new(rpc_client: Arc<SolanaRpcClient>, websocket_url: &str, fanout_slots: usize) -> Result<Self> {
+        let config = TpuClientConfig {
+            fanout_slots: fanout_slots as u64,
+            ..TpuClientConfig::default()
+        };
+
+        let client = TpuClient::new(rpc_client, websocket_url, config)
+            .map_err(|e| anyhow!("Failed to initialize TPU client: {}", e))?;
+
+        Ok(Self {
+            client,
+            counters: TpuOutcomeCounters::default(),
+        })
+    }
+
+    /// Broadcast `transaction` to the configured fanout of leaders. Returns
+    /// whether it was accepted onto at least one leader connection - not a
+    /// landing confirmation, just that the send didn't fail outright.
+    pub fn send(&self, transaction: &Transaction) -> bool {
+        let accepted = self.client.send_transaction(transaction);
+        self.counters.record(accepted);
+        if !accepted {
+            warn!("TPU broadcast rejected by every leader connection in the fanout");
+        }
+        accepted
+    }
+
+    pub fn counters(&self) -> &TpuOutcomeCounters {
+        &self.counters
+    }
+}
@@ -0,0 +1,135 @@
+/// Sanctum Infinity Pool API Client
+///
+/// HTTP client for Sanctum's LST swap router, which specializes in
+/// LST<->LST and LST<->SOL routing (mSOL, jitoSOL, bSOL, ...) and tends to
+/// out-price generic AMM aggregation for those pairs. Mirrors
+/// `JupiterClient`'s quote/swap shape so the two can be compared
+/// head-to-head in `jupiter::swap::prepare_best_swap_for_signing`.
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use tracing::{info, warn};
+
+use super::types::{SanctumQuoteResponse, SanctumSwapResponse};
+
+/// Sanctum API base URL
+const SANCTUM_API_BASE: &str = "https://api.sanctum.so/v1";
+
+/// Sanctum API Client
+#[derive(Clone)]
+pub struct SanctumClient {
+    http: Client,
+    base_url: String,
+}
+
+impl SanctumClient {
+    /// Create a new Sanctum client with default settings
+    pub fn new() -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: SANCTUM_API_BASE.to_string(),
+        }
+    }
+
+    /// Create with custom base URL (for testing)
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: base_url.to_string(),
+        }
+    }
+
+    /// Get a quote for an LST swap
+    ///
+    /// # Arguments
+    /// * `input_mint` - Input token mint address
+    /// * `output_mint` - Output token mint address
+    /// * `amount` - Amount in smallest units
+    pub async fn get_quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+    ) -> Result<SanctumQuoteResponse> {
+        let url = format!("{}/swap/quote", self.base_url);
+
+        info!(
+            "Getting Sanctum quote: {} -> {} (amount: {})",
+            input_mint, output_mint, amount
+        );
+
+        let response = self.http
+            .get(&url)
+            .query(&[
+                ("input", input_mint),
+                ("output", output_mint),
+                ("amount", &amount.to_string()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            warn!("Sanctum quote failed: {} - {}", status, text);
+            return Err(anyhow!("Sanctum quote failed: {} - {}", status, text));
+        }
+
+        let quote: SanctumQuoteResponse = response.json().await?;
+
+        info!(
+            "Sanctum quote received: {} {} -> {} {}",
+            quote.in_amount, input_mint, quote.out_amount, output_mint
+        );
+
+        Ok(quote)
+    }
+
+    /// Build an unsigned swap transaction from a Sanctum quote
+    ///
+    /// # Arguments
+    /// * `user_pubkey` - User's wallet public key
+    /// * `quote` - Quote response from `get_quote`
+    pub async fn get_swap_transaction(
+        &self,
+        user_pubkey: &str,
+        quote: &SanctumQuoteResponse,
+    ) -> Result<SanctumSwapResponse> {
+        let url = format!("{}/swap/build-tx", self.base_url);
+
+        let request = serde_json::json!({
+            "signer": user_pubkey,
+            "inputMint": quote.input_mint,
+            "outputMint": quote.output_mint,
+            "amount": quote.in_amount,
+        });
+
+        info!("Building Sanctum swap transaction for user: {}", user_pubkey);
+
+        let response = self.http
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            warn!("Sanctum swap failed: {} - {}", status, text);
+            return Err(anyhow!("Sanctum swap failed: {} - {}", status, text));
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+impl Default for SanctumClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,11 @@
+/// Sanctum LST Swap Integration
+///
+/// Quote/swap client for Sanctum's Infinity liquid-staking-token pool, used
+/// alongside Jupiter as an alternate route for LST<->LST and LST<->SOL
+/// swaps (see `jupiter::swap::prepare_best_swap_for_signing`).
+
+pub mod client;
+pub mod types;
+
+pub use client::SanctumClient;
+pub use types::*;
@@ -0,0 +1,25 @@
+/// Sanctum SDK Type Definitions
+///
+/// Common types for Sanctum's Infinity LST swap router.
+use serde::{Deserialize, Serialize};
+
+/// Sanctum Quote Response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctumQuoteResponse {
+    pub input_mint: String,
+    pub in_amount: String,
+    pub output_mint: String,
+    pub out_amount: String,
+    #[serde(default)]
+    pub price_impact_pct: String,
+}
+
+/// Sanctum Swap Response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctumSwapResponse {
+    /// Base64-encoded unsigned transaction, same shape as Jupiter's
+    /// `SwapResponse::swap_transaction`.
+    pub tx: String,
+}
@@ -3,6 +3,9 @@
 /// Submits transactions through bloXroute for MEV protection.
 /// bloXroute provides private transaction submission and bundle services.
 
+use std::sync::Arc;
+use std::time::Instant;
+
 use anyhow::{anyhow, Result};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use reqwest::{Client, header};
@@ -10,6 +13,8 @@ use serde::{Deserialize, Serialize};
 use solana_sdk::transaction::Transaction;
 use tracing::{info, warn};
 
+use crate::telemetry::Metrics;
+
 /// bloXroute Solana endpoints
 pub const BLOXROUTE_SOLANA_ENDPOINT: &str = "https://solana.gateway.blxrbdn.com";
 
@@ -52,6 +57,9 @@ pub struct BloxrouteClient {
     http: Client,
     endpoint: String,
     api_key: Option<String>,
+    /// Cross-cutting submission metrics (see `telemetry::Metrics`). `None`
+    /// when constructed without `with_metrics`, e.g. in tests.
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl BloxrouteClient {
@@ -64,6 +72,7 @@ impl BloxrouteClient {
                 .expect("Failed to create HTTP client"),
             endpoint: BLOXROUTE_SOLANA_ENDPOINT.to_string(),
             api_key,
+            metrics: None,
         }
     }
 
@@ -76,14 +85,29 @@ impl BloxrouteClient {
                 .expect("Failed to create HTTP client"),
             endpoint: endpoint.to_string(),
             api_key,
+            metrics: None,
         }
     }
 
+    /// Record submission latency/outcome into the shared `/metrics` endpoint.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Check if client is configured with API key
     pub fn is_configured(&self) -> bool {
         self.api_key.is_some() && !self.api_key.as_ref().unwrap().is_empty()
     }
 
+    /// Record an operation's outcome into the shared `/metrics` endpoint, if
+    /// one was wired in via `with_metrics`.
+    async fn observe(&self, operation: &str, latency_ms: f64, success: bool) {
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(operation, "bloxroute", latency_ms, success).await;
+        }
+    }
+
     /// Submit a single transaction with MEV protection
     pub async fn submit_transaction(
         &self,
@@ -115,17 +139,20 @@ impl BloxrouteClient {
             header::HeaderValue::from_static("application/json"),
         );
 
+        let started_at = Instant::now();
         let response = self.http
             .post(format!("{}/api/v1/transaction", self.endpoint))
             .headers(headers)
             .json(&request)
             .send()
             .await?;
+        let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             warn!("bloXroute submission failed: {} - {}", status, text);
+            self.observe("submit_transaction", latency_ms, false).await;
             return Err(anyhow!("bloXroute failed: {} - {}", status, text));
         }
 
@@ -133,15 +160,20 @@ impl BloxrouteClient {
 
         if let Some(error) = blox_response.error {
             warn!("bloXroute error: {} - {}", error.code, error.message);
+            self.observe("submit_transaction", latency_ms, false).await;
             return Err(anyhow!("bloXroute error: {}", error.message));
         }
 
         match blox_response.signature {
             Some(sig) => {
                 info!("Transaction submitted via bloXroute: {}", sig);
+                self.observe("submit_transaction", latency_ms, true).await;
                 Ok(sig)
             }
-            None => Err(anyhow!("No signature returned from bloXroute")),
+            None => {
+                self.observe("submit_transaction", latency_ms, false).await;
+                Err(anyhow!("No signature returned from bloXroute"))
+            }
         }
     }
 
@@ -177,16 +209,19 @@ impl BloxrouteClient {
             header::HeaderValue::from_str(&self.api_key.as_ref().unwrap())?,
         );
 
+        let started_at = Instant::now();
         let response = self.http
             .post(format!("{}/api/v1/bundle", self.endpoint))
             .headers(headers)
             .json(&request)
             .send()
             .await?;
+        let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
+            self.observe("submit_bundle", latency_ms, false).await;
             return Err(anyhow!("bloXroute bundle failed: {} - {}", status, text));
         }
 
@@ -195,9 +230,13 @@ impl BloxrouteClient {
         match blox_response.signature {
             Some(sig) => {
                 info!("Bundle submitted to bloXroute: {}", sig);
+                self.observe("submit_bundle", latency_ms, true).await;
                 Ok(sig)
             }
-            None => Err(anyhow!("No bundle ID returned from bloXroute")),
+            None => {
+                self.observe("submit_bundle", latency_ms, false).await;
+                Err(anyhow!("No bundle ID returned from bloXroute"))
+            }
         }
     }
 }
@@ -0,0 +1,310 @@
+/// Conditional (limit/stop-loss) order engine.
+///
+/// `JupiterClient`/`build_swap_transaction` only ever did immediate
+/// quote+swap - there was no notion of "buy token X when price ≤ P" or
+/// "sell position when price crosses a stop trigger" resting in the
+/// background. This tracks that resting-order state itself (independent of
+/// any on-chain orderbook) and watches it against prices pushed in from the
+/// candle/swap stream: `on_price_update` compares `trigger_price` against
+/// each pending order watching that mint and, on a crossing, quotes and
+/// builds the swap transaction the same way `execute_swap` does. Like
+/// `execute_swap`, the built transaction is left unsigned - Turnkey (or a
+/// local wallet) on the frontend still does the signing and sends it.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::jupiter::{build_swap_transaction, JupiterClient, SwapMode};
+
+/// Which side of `trigger_price` fires the order - a limit buy triggers
+/// when price falls to or below it, a stop-loss sell when it rises to or
+/// above (or, for a short, falls to or below) it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerDirection {
+    /// Fires when the watched price is `<= trigger_price`.
+    AtOrBelow,
+    /// Fires when the watched price is `>= trigger_price`.
+    AtOrAbove,
+}
+
+impl TriggerDirection {
+    fn crossed(&self, price: f64, trigger_price: f64) -> bool {
+        match self {
+            TriggerDirection::AtOrBelow => price <= trigger_price,
+            TriggerDirection::AtOrAbove => price >= trigger_price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderKind {
+    Limit,
+    StopLoss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Pending,
+    Triggered,
+    Filled,
+    Cancelled,
+}
+
+/// The swap produced once an order triggers - an unsigned transaction, same
+/// as everything else `build_swap_transaction` hands back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderFill {
+    pub unsigned_transaction_base64: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub filled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalOrder {
+    pub id: u64,
+    pub owner_pubkey: String,
+    pub kind: OrderKind,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    pub slippage_bps: u16,
+    /// The mint whose price is compared against `trigger_price` - the mint
+    /// being bought for a limit order, or the position's mint for a
+    /// stop-loss.
+    pub watched_mint: String,
+    pub trigger_direction: TriggerDirection,
+    pub trigger_price: f64,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub fill: Option<OrderFill>,
+}
+
+/// Request body for placing a new conditional order.
+#[derive(Debug, Deserialize)]
+pub struct PlaceOrderRequest {
+    pub owner_pubkey: String,
+    pub kind: OrderKind,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    pub slippage_bps: u16,
+    pub watched_mint: String,
+    pub trigger_direction: TriggerDirection,
+    pub trigger_price: f64,
+}
+
+/// Resting limit/stop-loss orders, watched against live prices and filled
+/// through `JupiterClient` on a crossing. Shared as
+/// `Arc<ConditionalOrderEngine>`, the same shape as `PriceCoalescer`/
+/// `CandleStore`.
+pub struct ConditionalOrderEngine {
+    orders: Mutex<HashMap<u64, ConditionalOrder>>,
+    next_id: AtomicU64,
+    jupiter: JupiterClient,
+}
+
+impl ConditionalOrderEngine {
+    pub fn new(jupiter: JupiterClient) -> Arc<Self> {
+        Arc::new(Self {
+            orders: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            jupiter,
+        })
+    }
+
+    pub fn place_order(&self, req: PlaceOrderRequest) -> ConditionalOrder {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let order = ConditionalOrder {
+            id,
+            owner_pubkey: req.owner_pubkey,
+            kind: req.kind,
+            input_mint: req.input_mint,
+            output_mint: req.output_mint,
+            amount: req.amount,
+            slippage_bps: req.slippage_bps,
+            watched_mint: req.watched_mint,
+            trigger_direction: req.trigger_direction,
+            trigger_price: req.trigger_price,
+            status: OrderStatus::Pending,
+            created_at: Utc::now(),
+            triggered_at: None,
+            fill: None,
+        };
+        self.orders.lock().unwrap().insert(id, order.clone());
+        order
+    }
+
+    /// All orders, optionally narrowed to one owner - newest first.
+    pub fn list_orders(&self, owner_pubkey: Option<&str>) -> Vec<ConditionalOrder> {
+        let orders = self.orders.lock().unwrap();
+        let mut matching: Vec<ConditionalOrder> = orders
+            .values()
+            .filter(|order| owner_pubkey.map_or(true, |owner| order.owner_pubkey == owner))
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        matching
+    }
+
+    /// Cancel a still-pending order. Errors if it doesn't exist or has
+    /// already triggered/filled/been cancelled.
+    pub fn cancel_order(&self, id: u64) -> Result<(), String> {
+        let mut orders = self.orders.lock().unwrap();
+        match orders.get_mut(&id) {
+            Some(order) if order.status == OrderStatus::Pending => {
+                order.status = OrderStatus::Cancelled;
+                Ok(())
+            }
+            Some(order) => Err(format!("Order {} is {:?}, not pending", id, order.status)),
+            None => Err(format!("No order with id {}", id)),
+        }
+    }
+
+    /// Check every pending order watching `mint` against `price`, firing
+    /// (quoting and building the swap transaction) whichever have crossed
+    /// their trigger.
+    pub async fn on_price_update(&self, mint: &str, price: f64) {
+        let due: Vec<ConditionalOrder> = {
+            let orders = self.orders.lock().unwrap();
+            orders
+                .values()
+                .filter(|order| {
+                    order.status == OrderStatus::Pending
+                        && order.watched_mint == mint
+                        && order.trigger_direction.crossed(price, order.trigger_price)
+                })
+                .cloned()
+                .collect()
+        };
+
+        for order in due {
+            self.mark_triggered(order.id);
+            self.fire(&order).await;
+        }
+    }
+
+    fn mark_triggered(&self, id: u64) {
+        if let Some(order) = self.orders.lock().unwrap().get_mut(&id) {
+            order.status = OrderStatus::Triggered;
+            order.triggered_at = Some(Utc::now());
+        }
+    }
+
+    /// Quote and build the swap transaction for a just-triggered order. On
+    /// success the order moves to `Filled` with the built transaction
+    /// attached; on failure it's left `Triggered` rather than retried every
+    /// tick, since the trigger condition has already fired.
+    async fn fire(&self, order: &ConditionalOrder) {
+        let quote = match self
+            .jupiter
+            .get_quote(&order.input_mint, &order.output_mint, order.amount, order.slippage_bps, SwapMode::ExactIn)
+            .await
+        {
+            Ok(quote) => quote,
+            Err(e) => {
+                warn!("Conditional order {} triggered but quote failed: {}", order.id, e);
+                return;
+            }
+        };
+
+        let tx_bytes = match build_swap_transaction(&self.jupiter, &order.owner_pubkey, &quote, None).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Conditional order {} triggered but build_swap_transaction failed: {}", order.id, e);
+                return;
+            }
+        };
+
+        let fill = OrderFill {
+            unsigned_transaction_base64: BASE64.encode(&tx_bytes),
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            filled_at: Utc::now(),
+        };
+
+        if let Some(stored) = self.orders.lock().unwrap().get_mut(&order.id) {
+            stored.status = OrderStatus::Filled;
+            stored.fill = Some(fill);
+        }
+
+        info!("Conditional order {} filled ({:?})", order.id, order.kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jupiter::{MockPricing, QuoteProvider};
+
+    fn engine() -> Arc<ConditionalOrderEngine> {
+        ConditionalOrderEngine::new(JupiterClient::with_provider(QuoteProvider::Mock(MockPricing::default())))
+    }
+
+    fn place(engine: &ConditionalOrderEngine, direction: TriggerDirection, trigger_price: f64) -> ConditionalOrder {
+        engine.place_order(PlaceOrderRequest {
+            owner_pubkey: "owner1".to_string(),
+            kind: OrderKind::Limit,
+            input_mint: "USDC".to_string(),
+            output_mint: "SOL".to_string(),
+            amount: 1_000_000,
+            slippage_bps: 50,
+            watched_mint: "SOL".to_string(),
+            trigger_direction: direction,
+            trigger_price,
+        })
+    }
+
+    #[test]
+    fn test_place_and_list_orders() {
+        let engine = engine();
+        let order = place(&engine, TriggerDirection::AtOrBelow, 100.0);
+        assert_eq!(order.status, OrderStatus::Pending);
+
+        let listed = engine.list_orders(Some("owner1"));
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, order.id);
+    }
+
+    #[test]
+    fn test_cancel_pending_order() {
+        let engine = engine();
+        let order = place(&engine, TriggerDirection::AtOrBelow, 100.0);
+
+        engine.cancel_order(order.id).unwrap();
+        let listed = engine.list_orders(None);
+        assert_eq!(listed[0].status, OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_cancel_already_triggered_order_errors() {
+        let engine = engine();
+        let order = place(&engine, TriggerDirection::AtOrBelow, 100.0);
+        engine.mark_triggered(order.id);
+
+        assert!(engine.cancel_order(order.id).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_on_price_update_fills_order_once_trigger_crosses() {
+        let engine = engine();
+        let order = place(&engine, TriggerDirection::AtOrBelow, 100.0);
+
+        // Above the trigger - should not fire yet.
+        engine.on_price_update("SOL", 150.0).await;
+        assert_eq!(engine.list_orders(None)[0].status, OrderStatus::Pending);
+
+        // At or below the trigger - fires and fills via the mock provider.
+        engine.on_price_update("SOL", 100.0).await;
+        let listed = engine.list_orders(None);
+        assert_eq!(listed[0].id, order.id);
+        assert_eq!(listed[0].status, OrderStatus::Filled);
+        assert!(listed[0].fill.is_some());
+    }
+}
@@ -1,5 +1,29 @@
+/// Whale copy-trading: mirrors a detected whale swap, but closes the loop
+/// from `SandwichDetector`'s risk score to actual MEV-protected execution
+/// instead of stopping at an advice string. `process_whale_swap` simulates
+/// the mirrored trade, checks sandwich risk on the same token/side, then
+/// submits naked, as a tipped Jito bundle, or not at all, per
+/// `ProtectionPolicy`.
+use std::sync::Arc;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use solana_sdk::transaction::Transaction;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::jupiter::types::tokens::WSOL;
+use crate::jupiter::{JupiterClient, SwapMode};
+use crate::rpc::RpcManager;
+use crate::services::honeypot_analyzer::fee_bps_from_quote;
+use crate::services::jito_bundle::JitoBundleClient;
+use crate::services::sandwich_detector::{PendingTransaction, PoolState, SandwichDetector, SandwichSeverity};
+use crate::services::tx_simulator::TransactionSimulator;
+
+/// Slippage tolerance used only to price the quote this module derives a
+/// [`PoolState`] from - not the mirrored swap itself, which `mirrored_tx`
+/// already carries pre-built.
+const POOL_QUOTE_SLIPPAGE_BPS: u16 = 100;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhaleSwap {
@@ -10,18 +34,187 @@ pub struct WhaleSwap {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-pub struct CopyTradeEngine;
+/// Maps `SandwichSeverity` to a tip-lamports schedule and a submit
+/// decision, so the judgment call `get_protection_advice` only describes in
+/// prose ("strongly recommend a bundle", "do not submit without
+/// protection") becomes something `CopyTradeEngine` can act on without a
+/// human in the loop.
+#[derive(Debug, Clone, Copy)]
+pub struct ProtectionPolicy {
+    pub medium_tip_lamports: u64,
+    pub high_tip_lamports: u64,
+    pub critical_tip_lamports: u64,
+}
+
+impl Default for ProtectionPolicy {
+    fn default() -> Self {
+        Self {
+            medium_tip_lamports: 10_000,
+            high_tip_lamports: 50_000,
+            critical_tip_lamports: 200_000,
+        }
+    }
+}
+
+impl ProtectionPolicy {
+    /// Tip to attach when submitting as a Jito bundle at this severity.
+    /// `Low` never reaches this - `should_use_bundle` is false for it.
+    pub fn tip_lamports(&self, severity: SandwichSeverity) -> u64 {
+        match severity {
+            SandwichSeverity::Low => 0,
+            SandwichSeverity::Medium => self.medium_tip_lamports,
+            SandwichSeverity::High => self.high_tip_lamports,
+            SandwichSeverity::Critical => self.critical_tip_lamports,
+        }
+    }
+
+    /// Whether this severity should be submitted as a protected Jito bundle
+    /// rather than a naked RPC send.
+    pub fn should_use_bundle(&self, severity: SandwichSeverity) -> bool {
+        severity >= SandwichSeverity::Medium
+    }
+
+    /// `Critical` risk is never allowed to land as a naked send - if the
+    /// bundle path fails, the trade is blocked rather than falling back.
+    pub fn should_block_without_bundle(&self, severity: SandwichSeverity) -> bool {
+        severity == SandwichSeverity::Critical
+    }
+}
+
+/// Outcome of `CopyTradeEngine::process_whale_swap`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum CopyTradeOutcome {
+    /// Submitted without Jito protection - no meaningful sandwich risk.
+    SubmittedDirect { signature: String },
+    /// Submitted as a Jito bundle with a tip sized from `ProtectionPolicy`.
+    SubmittedProtected {
+        bundle_id: String,
+        tip_lamports: u64,
+        severity: SandwichSeverity,
+    },
+    /// `Critical` risk and bundle submission was unavailable or failed -
+    /// not sent at all rather than risk a naked, targeted fill.
+    Blocked { severity: SandwichSeverity },
+}
+
+pub struct CopyTradeEngine {
+    rpc: Arc<RpcManager>,
+    jito: JitoBundleClient,
+    jupiter: JupiterClient,
+    detector: Arc<Mutex<SandwichDetector>>,
+    policy: ProtectionPolicy,
+}
 
 impl CopyTradeEngine {
-    pub fn new() -> Self {
-        CopyTradeEngine
+    /// `detector` is normally `SandwichMempoolFeed::detector()` so the risk
+    /// check below sees real front-running activity instead of an empty,
+    /// freshly-constructed detector with no history.
+    pub fn new(rpc: Arc<RpcManager>, jito: JitoBundleClient, detector: Arc<Mutex<SandwichDetector>>) -> Self {
+        Self {
+            rpc,
+            jito,
+            jupiter: JupiterClient::new(),
+            detector,
+            policy: ProtectionPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: ProtectionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record a mempool transaction so later `process_whale_swap` calls for
+    /// the same token have front-running activity to detect against - fed
+    /// by whatever upstream source `SandwichDetector::record_transaction`
+    /// expects elsewhere (geyser/transaction subscriber, mempool stream).
+    pub async fn record_transaction(&self, tx: PendingTransaction) {
+        self.detector.lock().await.record_transaction(tx);
     }
 
-    pub async fn process_whale_swap(&self, swap: &WhaleSwap) -> Result<()> {
-        // In production, this would:
-        // 1. Check if swap meets copy-trade criteria
-        // 2. Simulate transaction
-        // 3. Execute if safe
-        Ok(())
+    /// Mirror `swap` by submitting `mirrored_tx`: simulate it, check
+    /// sandwich risk on `swap`'s token/side, then submit naked, as a
+    /// protected bundle, or not at all, per `ProtectionPolicy`.
+    /// `payer_keypair` signs the Jito tip transfer when a bundle is used.
+    pub async fn process_whale_swap(
+        &self,
+        swap: &WhaleSwap,
+        mirrored_tx: Transaction,
+        payer_keypair: &[u8],
+    ) -> Result<CopyTradeOutcome> {
+        let simulator = TransactionSimulator::new(self.rpc.clone());
+        let report = simulator.simulate(&mirrored_tx).await?;
+        if !report.will_succeed {
+            anyhow::bail!(
+                "Mirrored trade for {} (whale {}) failed simulation: {:?}",
+                swap.token_out,
+                swap.wallet,
+                report.error
+            );
+        }
+
+        let is_buy = swap.token_in == WSOL;
+        let pool = self.estimate_pool_state(swap, is_buy).await;
+        let severity = {
+            let detector = self.detector.lock().await;
+            match &pool {
+                Some(pool) => detector.analyze_sandwich_risk_with_pool(&swap.token_out, swap.amount, is_buy, pool),
+                None => detector.analyze_sandwich_risk(&swap.token_out, swap.amount, is_buy),
+            }
+            .map(|alert| alert.severity)
+            .unwrap_or(SandwichSeverity::Low)
+        };
+
+        if !self.policy.should_use_bundle(severity) {
+            let signature = self.rpc.send_transaction(&mirrored_tx, false, false).await?;
+            return Ok(CopyTradeOutcome::SubmittedDirect {
+                signature: signature.to_string(),
+            });
+        }
+
+        let tip_lamports = self.policy.tip_lamports(severity);
+        match self.jito.submit_bundle_with_tip(vec![mirrored_tx], tip_lamports, payer_keypair).await {
+            Ok(submission) => {
+                info!(
+                    "Protected whale-mirror of {} ({:?} risk): bundle {} tip {} lamports",
+                    swap.token_out, severity, submission.bundle_id, tip_lamports
+                );
+                Ok(CopyTradeOutcome::SubmittedProtected {
+                    bundle_id: submission.bundle_id,
+                    tip_lamports,
+                    severity,
+                })
+            }
+            Err(e) if self.policy.should_block_without_bundle(severity) => {
+                warn!(
+                    "Bundle submission failed for {:?}-risk whale-mirror of {}, blocking rather than falling back to a naked send: {}",
+                    severity, swap.token_out, e
+                );
+                Ok(CopyTradeOutcome::Blocked { severity })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Derive a constant-product [`PoolState`] for `swap`'s token from a
+    /// fresh Jupiter quote, so `process_whale_swap` can use
+    /// `analyze_sandwich_risk_with_pool`'s AMM-simulated profit estimate
+    /// instead of the flat slippage heuristic. Only meaningful on the buy
+    /// side - `analyze_sandwich_risk_with_pool` ignores `pool` for sells -
+    /// so returns `None` without a quote round trip otherwise, or if the
+    /// quote fails or its price impact is too small to invert into reserves.
+    async fn estimate_pool_state(&self, swap: &WhaleSwap, is_buy: bool) -> Option<PoolState> {
+        if !is_buy {
+            return None;
+        }
+
+        let quote = self
+            .jupiter
+            .get_quote(&swap.token_in, &swap.token_out, swap.amount, POOL_QUOTE_SLIPPAGE_BPS, SwapMode::ExactIn)
+            .await
+            .ok()?;
+        let fee_bps = fee_bps_from_quote(&quote);
+        PoolState::from_quote(swap.amount, quote.out_amount, quote.price_impact(), fee_bps)
     }
 }
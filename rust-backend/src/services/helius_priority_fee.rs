@@ -0,0 +1,233 @@
+/// Helius-style `getPriorityFeeEstimate` priority-fee estimation.
+///
+/// [`crate::services::priority_fee::PriorityFeeService`] derives a fee by
+/// sampling `getRecentPrioritizationFees` itself and percentile-smoothing it
+/// client-side. This module instead defers to a staked provider's own
+/// `getPriorityFeeEstimate` RPC method (Helius, and compatible providers),
+/// which factors in mempool/leader-schedule visibility a raw recent-fees
+/// sample doesn't have. Falls back to a static default when no staked
+/// endpoint is configured or the call itself fails, so callers never have to
+/// special-case "no provider" themselves.
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::jupiter::RoutePlan;
+use crate::rpc::staked_nodes::{build_staked_endpoints, StakedRpcEndpoint};
+
+/// Micro-lamports-per-CU fee recommended when no staked provider is
+/// configured, or `getPriorityFeeEstimate` errors or returns an unusable
+/// response.
+const FALLBACK_MICRO_LAMPORTS: u64 = 10_000;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `priorityLevel` tiers accepted by `getPriorityFeeEstimate`, in ascending
+/// order of how aggressively they bid against the recent fee distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriorityLevel {
+    Min,
+    Low,
+    Medium,
+    High,
+    VeryHigh,
+    UnsafeMax,
+}
+
+impl PriorityLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            PriorityLevel::Min => "Min",
+            PriorityLevel::Low => "Low",
+            PriorityLevel::Medium => "Medium",
+            PriorityLevel::High => "High",
+            PriorityLevel::VeryHigh => "VeryHigh",
+            PriorityLevel::UnsafeMax => "UnsafeMax",
+        }
+    }
+}
+
+/// What to estimate the fee against - `getPriorityFeeEstimate` accepts
+/// exactly one of the two.
+pub enum FeeEstimateTarget {
+    /// Base64-serialized transaction; the provider derives the accounts it
+    /// touches itself.
+    Transaction(String),
+    /// Explicit account pubkeys (base58) to scope the sample to.
+    Accounts(Vec<String>),
+}
+
+#[derive(Debug, Serialize)]
+struct PriorityFeeRpcRequest {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriorityFeeRpcResponse {
+    #[serde(default)]
+    result: Option<PriorityFeeRpcResult>,
+    #[serde(default)]
+    error: Option<PriorityFeeRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriorityFeeRpcResult {
+    #[serde(rename = "priorityFeeEstimate")]
+    priority_fee_estimate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriorityFeeRpcError {
+    code: i64,
+    message: String,
+}
+
+/// Queries a staked provider's `getPriorityFeeEstimate` method for a
+/// recommended micro-lamports-per-CU fee.
+pub struct HeliusPriorityFeeEstimator {
+    http: Client,
+    endpoints: Vec<StakedRpcEndpoint>,
+}
+
+impl HeliusPriorityFeeEstimator {
+    /// Builds against the endpoints `rpc::build_staked_endpoints()` derives
+    /// from the environment (`TRITON_API_KEY`/`HELIUS_API_KEY`/etc).
+    pub fn new() -> Self {
+        Self::with_endpoints(build_staked_endpoints())
+    }
+
+    pub fn with_endpoints(endpoints: Vec<StakedRpcEndpoint>) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            endpoints,
+        }
+    }
+
+    fn staked_endpoint(&self) -> Option<&StakedRpcEndpoint> {
+        self.endpoints.iter().find(|e| e.staked && e.provider.is_staked())
+    }
+
+    /// Recommend a micro-lamports-per-CU fee for `target` at the given
+    /// `priority_level`. `lookback_slots` and `include_vote` are passed
+    /// through to the provider unset unless given. Falls back to
+    /// [`FALLBACK_MICRO_LAMPORTS`] when no staked endpoint is configured, the
+    /// request fails, or the provider returns an error or an unusable
+    /// response.
+    pub async fn estimate(
+        &self,
+        target: FeeEstimateTarget,
+        priority_level: PriorityLevel,
+        lookback_slots: Option<u16>,
+        include_vote: Option<bool>,
+    ) -> Result<u64> {
+        let endpoint = match self.staked_endpoint() {
+            Some(endpoint) => endpoint,
+            None => {
+                warn!("No staked RPC provider configured - falling back to a static priority fee");
+                return Ok(FALLBACK_MICRO_LAMPORTS);
+            }
+        };
+
+        let mut options = json!({
+            "priorityLevel": priority_level.as_str(),
+            "includeAllPriorityFeeLevels": false,
+        });
+        if let Some(slots) = lookback_slots {
+            options["lookbackSlots"] = json!(slots);
+        }
+        if let Some(vote) = include_vote {
+            options["includeVote"] = json!(vote);
+        }
+
+        let mut params = serde_json::Map::new();
+        match target {
+            FeeEstimateTarget::Transaction(tx) => {
+                params.insert("transaction".to_string(), json!(tx));
+            }
+            FeeEstimateTarget::Accounts(accounts) => {
+                params.insert("accountKeys".to_string(), json!(accounts));
+            }
+        }
+        params.insert("options".to_string(), options);
+
+        let request = PriorityFeeRpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method: "getPriorityFeeEstimate",
+            params: vec![serde_json::Value::Object(params)],
+        };
+
+        let response = match self.http.post(&endpoint.url).json(&request).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(
+                    "getPriorityFeeEstimate request to {} failed: {} - falling back to a static priority fee",
+                    endpoint.provider.name(),
+                    e
+                );
+                return Ok(FALLBACK_MICRO_LAMPORTS);
+            }
+        };
+
+        let parsed: PriorityFeeRpcResponse = match response.json().await {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                warn!(
+                    "Failed to parse {} getPriorityFeeEstimate response: {} - falling back to a static priority fee",
+                    endpoint.provider.name(),
+                    e
+                );
+                return Ok(FALLBACK_MICRO_LAMPORTS);
+            }
+        };
+
+        if let Some(err) = parsed.error {
+            warn!(
+                "{} getPriorityFeeEstimate returned an error ({}): {} - falling back to a static priority fee",
+                endpoint.provider.name(),
+                err.code,
+                err.message
+            );
+            return Ok(FALLBACK_MICRO_LAMPORTS);
+        }
+
+        match parsed.result {
+            Some(result) => Ok(result.priority_fee_estimate.round() as u64),
+            None => {
+                warn!(
+                    "{} getPriorityFeeEstimate returned neither a result nor an error - falling back to a static priority fee",
+                    endpoint.provider.name()
+                );
+                Ok(FALLBACK_MICRO_LAMPORTS)
+            }
+        }
+    }
+
+    /// Estimate against a quote's `RoutePlan`: each leg's `SwapInfo.amm_key`
+    /// is the pool account most likely to be write-locked and contended, so
+    /// together they're a good proxy for the accounts the swap will touch.
+    pub async fn estimate_for_route(
+        &self,
+        route_plan: &[RoutePlan],
+        priority_level: PriorityLevel,
+    ) -> Result<u64> {
+        let accounts = route_plan.iter().map(|leg| leg.swap_info.amm_key.clone()).collect();
+        self.estimate(FeeEstimateTarget::Accounts(accounts), priority_level, None, None)
+            .await
+    }
+}
+
+impl Default for HeliusPriorityFeeEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
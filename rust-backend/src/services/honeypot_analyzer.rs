@@ -1,27 +1,407 @@
+use crate::jupiter::types::QuoteResponse;
+use crate::jupiter::{tokens, JupiterClient, SwapMode};
 use crate::rpc::RpcManager;
-use solana_sdk::pubkey::Pubkey;
+use crate::services::sandwich_detector::PoolState;
+use solana_program::program_pack::Pack;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use spl_token::state::Mint as LegacyMint;
+use spl_token_2022::extension::{
+    default_account_state::DefaultAccountState,
+    mint_close_authority::MintCloseAuthority,
+    permanent_delegate::PermanentDelegate,
+    transfer_fee::TransferFeeConfig,
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use spl_token_2022::state::{AccountState, Mint as Token2022Mint};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use anyhow::Result;
 
+/// Probe amount for the round-trip buy/sell simulation: small enough that
+/// its own price impact on a shallow pool doesn't swamp the tax signal
+/// we're trying to measure.
+const PROBE_SOL_LAMPORTS: u64 = 10_000_000; // 0.01 SOL
+/// Generous slippage tolerance for probe quotes - we only read `out_amount`
+/// and `price_impact_pct`, never build a transaction from these.
+const PROBE_SLIPPAGE_BPS: u16 = 100;
+/// Round-trip loss beyond what both legs' own price impact already
+/// explains, above which we report an estimated sell tax.
+const SELL_TAX_WARN_THRESHOLD: f64 = 0.10;
+/// Fee a constant-product AMM pool charges when the quote's own route data
+/// doesn't give us one - the standard Raydium/pump.fun swap fee.
+const DEFAULT_AMM_FEE_BPS: u16 = 30;
+/// AMM-curve-derived sell tax (i.e. round-trip loss the pool's own
+/// constant-product math doesn't already explain) beyond which we flag the
+/// token as a honeypot outright, not just a warning.
+const HONEYPOT_SELL_TAX_THRESHOLD: f64 = 0.5;
+/// Trade sizes, as multiples of [`PROBE_SOL_LAMPORTS`], at which the
+/// price-impact curve is sampled so callers can see how thin the pool is
+/// before committing to a larger trade.
+const PRICE_IMPACT_CURVE_MULTIPLES: [u64; 5] = [1, 5, 20, 100, 500];
+
+/// Price impact of a hypothetical buy of `sol_in_lamports`, simulated on the
+/// constant-product curve derived from the probe quote - lets a caller see
+/// how illiquid the pool is at sizes larger than the probe itself without
+/// spending additional Jupiter quote calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceImpactPoint {
+    pub sol_in_lamports: u64,
+    pub price_impact_pct: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyScore {
     pub score: f64, // 0.0 to 1.0, higher is safer
     pub is_honeypot: bool,
     pub can_freeze: bool,
     pub can_mint: bool,
-    pub can_burn: bool,
     pub has_liquidity: bool,
+    /// Token-2022 `TransferFeeConfig` extension is present, i.e. transfers
+    /// are taxed at the protocol level regardless of pool behavior.
+    pub has_transfer_fee: bool,
+    /// Current transfer fee in basis points, if `has_transfer_fee`.
+    pub transfer_fee_bps: Option<u16>,
+    /// Token-2022 `PermanentDelegate` extension is present: a fixed
+    /// authority can move tokens out of any holder's account at will.
+    pub has_permanent_delegate: bool,
+    /// Token-2022 `DefaultAccountState` extension defaults new token
+    /// accounts to frozen, so holders can't transfer without being
+    /// explicitly thawed first.
+    pub default_frozen: bool,
+    /// Token-2022 `MintCloseAuthority` extension is present.
+    pub has_mint_close_authority: bool,
+    /// Estimated tax on the buy leg, from the mint's own `TransferFeeConfig`
+    /// if it's a Token-2022 token - that fee applies to every transfer, the
+    /// buy swap included, regardless of what the AMM round trip shows.
+    pub effective_buy_tax_pct: f64,
+    /// Round-trip loss the probe's buy-side price impact doesn't already
+    /// explain, isolated by simulating the sell leg on the constant-product
+    /// curve derived from the buy quote and comparing against the real sell
+    /// quote. This is the actual honeypot signal - mint/freeze-authority
+    /// bytes only describe what a token *could* do.
+    pub effective_sell_tax_pct: f64,
+    /// Price impact at several trade sizes on the curve derived from the
+    /// probe quote, so callers can see how thin the pool is before trading
+    /// larger than [`PROBE_SOL_LAMPORTS`].
+    pub price_impact_curve: Vec<PriceImpactPoint>,
     pub warnings: Vec<String>,
 }
 
+/// Result of probing a mint with a small SOL->token->SOL round trip.
+struct RoundTripProbe {
+    has_liquidity: bool,
+    is_honeypot: bool,
+    warnings: Vec<String>,
+    score_penalty: f64,
+    effective_sell_tax_pct: f64,
+    price_impact_curve: Vec<PriceImpactPoint>,
+}
+
+/// Result of unpacking a mint account's SPL Token / Token-2022 state and
+/// extensions.
+struct MintInspection {
+    can_mint: bool,
+    can_freeze: bool,
+    has_transfer_fee: bool,
+    transfer_fee_bps: Option<u16>,
+    has_permanent_delegate: bool,
+    default_frozen: bool,
+    has_mint_close_authority: bool,
+    warnings: Vec<String>,
+    score_penalty: f64,
+}
+
+/// Unpack a mint account via the real SPL Token or Token-2022 layout
+/// (dispatching on the account's owner program), rather than indexing into
+/// raw bytes - Token-2022's TLV extension area sits past the base struct
+/// and shifts every fixed offset a hand-rolled check would assume.
+fn inspect_mint(account: &Account) -> Result<MintInspection> {
+    let mut warnings = Vec::new();
+    let mut score_penalty = 0.0;
+
+    if account.owner == spl_token_2022::id() {
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&account.data)
+            .map_err(|e| anyhow::anyhow!("Failed to unpack Token-2022 mint: {}", e))?;
+
+        let can_mint = state.base.mint_authority.is_some();
+        let can_freeze = state.base.freeze_authority.is_some();
+        if can_mint {
+            score_penalty += 0.3;
+            warnings.push("Token has active mint authority".to_string());
+        }
+        if can_freeze {
+            score_penalty += 0.4;
+            warnings.push("Token has freeze authority - can freeze accounts".to_string());
+        }
+
+        let transfer_fee_bps = state
+            .get_extension::<TransferFeeConfig>()
+            .ok()
+            .map(|ext| u16::from(ext.newer_transfer_fee.transfer_fee_basis_points));
+        let has_transfer_fee = transfer_fee_bps.map(|bps| bps > 0).unwrap_or(false);
+        if has_transfer_fee {
+            score_penalty += 0.3;
+            warnings.push(format!(
+                "TransferFeeConfig extension charges {} bps on every transfer - hidden sell tax",
+                transfer_fee_bps.unwrap_or(0)
+            ));
+        }
+
+        let has_permanent_delegate = state.get_extension::<PermanentDelegate>().is_ok();
+        if has_permanent_delegate {
+            score_penalty += 0.5;
+            warnings.push(
+                "PermanentDelegate extension lets a fixed authority move tokens out of any holder's account"
+                    .to_string(),
+            );
+        }
+
+        let default_frozen = state
+            .get_extension::<DefaultAccountState>()
+            .map(|ext| ext.state == u8::from(AccountState::Frozen))
+            .unwrap_or(false);
+        if default_frozen {
+            score_penalty += 0.5;
+            warnings.push(
+                "DefaultAccountState extension freezes every new token account until explicitly thawed"
+                    .to_string(),
+            );
+        }
+
+        let has_mint_close_authority = state.get_extension::<MintCloseAuthority>().is_ok();
+        if has_mint_close_authority {
+            warnings.push("MintCloseAuthority extension is set".to_string());
+        }
+
+        Ok(MintInspection {
+            can_mint,
+            can_freeze,
+            has_transfer_fee,
+            transfer_fee_bps,
+            has_permanent_delegate,
+            default_frozen,
+            has_mint_close_authority,
+            warnings,
+            score_penalty,
+        })
+    } else {
+        let mint = LegacyMint::unpack(&account.data)
+            .map_err(|e| anyhow::anyhow!("Failed to unpack SPL Token mint: {}", e))?;
+
+        let can_mint = mint.mint_authority.is_some();
+        let can_freeze = mint.freeze_authority.is_some();
+        if can_mint {
+            score_penalty += 0.3;
+            warnings.push("Token has active mint authority".to_string());
+        }
+        if can_freeze {
+            score_penalty += 0.4;
+            warnings.push("Token has freeze authority - can freeze accounts".to_string());
+        }
+
+        Ok(MintInspection {
+            can_mint,
+            can_freeze,
+            has_transfer_fee: false,
+            transfer_fee_bps: None,
+            has_permanent_delegate: false,
+            default_frozen: false,
+            has_mint_close_authority: false,
+            warnings,
+            score_penalty,
+        })
+    }
+}
+
+/// Swap fee of the quote's first route leg, in basis points
+/// (`fee_amount / in_amount`), falling back to [`DEFAULT_AMM_FEE_BPS`] when
+/// the route has no legs or the fee fields don't parse.
+pub(crate) fn fee_bps_from_quote(quote: &QuoteResponse) -> u16 {
+    quote
+        .route_plan
+        .first()
+        .and_then(|leg| {
+            let in_amount: u64 = leg.swap_info.in_amount.parse().ok()?;
+            let fee_amount: u64 = leg.swap_info.fee_amount.parse().ok()?;
+            if in_amount == 0 {
+                return None;
+            }
+            Some(((fee_amount as u128) * 10_000 / (in_amount as u128)) as u16)
+        })
+        .unwrap_or(DEFAULT_AMM_FEE_BPS)
+}
+
+/// Re-simulate the sell leg on `pool_after_buy` (reserves already advanced
+/// past the probe buy) and compare against the real `sol_back` the sell
+/// quote returned: the fraction the curve itself doesn't explain is the
+/// effective sell tax.
+fn amm_sell_tax_pct(pool_after_buy: &PoolState, token_out: u64, sol_back: u64) -> f64 {
+    let (predicted_sol_back, _) = pool_after_buy.sell_token(token_out);
+    if predicted_sol_back == 0 {
+        return 0.0;
+    }
+    (1.0 - sol_back as f64 / predicted_sol_back as f64).max(0.0)
+}
+
+/// Price impact of a hypothetical buy at each of
+/// [`PRICE_IMPACT_CURVE_MULTIPLES`] times [`PROBE_SOL_LAMPORTS`], simulated
+/// on `pool` rather than spending additional Jupiter quote calls.
+fn price_impact_curve(pool: &PoolState, fee_bps: u16) -> Vec<PriceImpactPoint> {
+    PRICE_IMPACT_CURVE_MULTIPLES
+        .iter()
+        .map(|multiple| {
+            let amount_in = PROBE_SOL_LAMPORTS * multiple;
+            let (out, _) = pool.buy_token(amount_in);
+            let amount_in_after_fee = amount_in as f64 * (10_000.0 - fee_bps as f64) / 10_000.0;
+            let spot_out = amount_in_after_fee * pool.reserve_token as f64 / pool.reserve_sol as f64;
+            let price_impact_pct = if spot_out > 0.0 {
+                (1.0 - out as f64 / spot_out).max(0.0) * 100.0
+            } else {
+                100.0
+            };
+            PriceImpactPoint { sol_in_lamports: amount_in, price_impact_pct }
+        })
+        .collect()
+}
+
 pub struct HoneypotAnalyzer {
     rpc: Arc<RpcManager>,
+    jupiter: JupiterClient,
 }
 
 impl HoneypotAnalyzer {
-    pub fn new(rpc: Arc<RpcManager>) -> Self {
-        HoneypotAnalyzer { rpc }
+    pub fn new(rpc: Arc<RpcManager>, jupiter: JupiterClient) -> Self {
+        HoneypotAnalyzer { rpc, jupiter }
+    }
+
+    /// Quote a tiny SOL->mint buy, then immediately quote mint->SOL for the
+    /// resulting `out_amount`. A token you can buy but not sell (or that
+    /// taxes the sell far beyond slippage) is the real honeypot signal -
+    /// mint-authority/freeze-authority bytes only tell you what the token
+    /// *could* do, not what actually happens at the AMM.
+    ///
+    /// Beyond the real round-trip quotes, the buy quote's own price impact
+    /// is inverted into a constant-product [`PoolState`] (see
+    /// [`PoolState::from_quote`]) so the sell leg can be re-simulated on the
+    /// curve itself: the gap between what the curve predicts and what the
+    /// real sell quote returns isolates an actual token-level tax from
+    /// ordinary AMM slippage, and the same curve gives the price-impact
+    /// curve at larger trade sizes for free. Building and submitting the
+    /// sell transaction through `TransactionSimulator` to catch a hard
+    /// revert isn't wired up here - that requires a funded wallet pubkey
+    /// with an existing token account, which this analyzer doesn't have.
+    async fn probe_round_trip(&self, mint: &str) -> RoundTripProbe {
+        let buy_quote = match self
+            .jupiter
+            .get_quote(tokens::WSOL, mint, PROBE_SOL_LAMPORTS, PROBE_SLIPPAGE_BPS, SwapMode::ExactIn)
+            .await
+        {
+            Ok(quote) => quote,
+            Err(e) => {
+                return RoundTripProbe {
+                    has_liquidity: false,
+                    is_honeypot: true,
+                    warnings: vec![format!("No buy-side route for probe quote: {}", e)],
+                    score_penalty: 1.0,
+                    effective_sell_tax_pct: 0.0,
+                    price_impact_curve: Vec::new(),
+                };
+            }
+        };
+
+        let token_out = buy_quote.out_amount;
+        if token_out == 0 {
+            return RoundTripProbe {
+                has_liquidity: false,
+                is_honeypot: true,
+                warnings: vec!["Buy-side probe quote returned no output".to_string()],
+                score_penalty: 1.0,
+                effective_sell_tax_pct: 0.0,
+                price_impact_curve: Vec::new(),
+            };
+        }
+
+        let sell_quote = match self
+            .jupiter
+            .get_quote(mint, tokens::WSOL, token_out, PROBE_SLIPPAGE_BPS, SwapMode::ExactIn)
+            .await
+        {
+            Ok(quote) => quote,
+            Err(e) => {
+                return RoundTripProbe {
+                    has_liquidity: false,
+                    is_honeypot: true,
+                    warnings: vec![format!(
+                        "No sell-side route for probe amount - likely a honeypot: {}",
+                        e
+                    )],
+                    score_penalty: 1.0,
+                    effective_sell_tax_pct: 0.0,
+                    price_impact_curve: Vec::new(),
+                };
+            }
+        };
+
+        let sol_back = sell_quote.out_amount;
+        if sol_back == 0 {
+            return RoundTripProbe {
+                has_liquidity: false,
+                is_honeypot: true,
+                warnings: vec!["Sell-side probe quote returned zero output - likely a honeypot".to_string()],
+                score_penalty: 1.0,
+                effective_sell_tax_pct: 0.0,
+                price_impact_curve: Vec::new(),
+            };
+        }
+
+        // Retention after both legs, then back out the portion already
+        // explained by each leg's own price impact to isolate the part
+        // that looks like an actual transfer tax.
+        let retention = sol_back as f64 / PROBE_SOL_LAMPORTS as f64;
+        let round_trip_loss = (1.0 - retention).max(0.0);
+        let buy_impact = buy_quote.price_impact() / 100.0;
+        let sell_impact = sell_quote.price_impact() / 100.0;
+        let estimated_tax = (round_trip_loss - buy_impact - sell_impact).max(0.0);
+
+        let mut warnings = Vec::new();
+        let mut score_penalty = 0.0;
+        if estimated_tax > SELL_TAX_WARN_THRESHOLD {
+            warnings.push(format!(
+                "Round-trip probe lost {:.1}% beyond expected slippage - possible sell tax",
+                estimated_tax * 100.0
+            ));
+            score_penalty = estimated_tax.min(1.0);
+        }
+
+        let fee_bps = fee_bps_from_quote(&buy_quote);
+        let (sell_tax_fraction, price_impact_curve, mut is_honeypot) =
+            match PoolState::from_quote(PROBE_SOL_LAMPORTS, token_out, buy_quote.price_impact(), fee_bps) {
+                Some(pool) => {
+                    let (_, pool_after_buy) = pool.buy_token(PROBE_SOL_LAMPORTS);
+                    let sell_tax = amm_sell_tax_pct(&pool_after_buy, token_out, sol_back);
+                    let curve = price_impact_curve(&pool, fee_bps);
+                    (sell_tax, curve, false)
+                }
+                None => (0.0, Vec::new(), false),
+            };
+
+        if sell_tax_fraction > HONEYPOT_SELL_TAX_THRESHOLD {
+            is_honeypot = true;
+            warnings.push(format!(
+                "AMM round-trip simulation: selling back the probe lost {:.1}% beyond what the pool's own curve explains - likely a honeypot tax",
+                sell_tax_fraction * 100.0
+            ));
+            score_penalty = score_penalty.max(sell_tax_fraction.min(1.0));
+        }
+
+        RoundTripProbe {
+            has_liquidity: true,
+            is_honeypot,
+            warnings,
+            score_penalty,
+            effective_sell_tax_pct: sell_tax_fraction * 100.0,
+            price_impact_curve,
+        }
     }
 
     pub async fn analyze_token(&self, mint: &Pubkey) -> Result<SafetyScore> {
@@ -38,61 +418,49 @@ impl HoneypotAnalyzer {
                 is_honeypot: true,
                 can_freeze: true,
                 can_mint: true,
-                can_burn: true,
                 has_liquidity: false,
+                has_transfer_fee: false,
+                transfer_fee_bps: None,
+                has_permanent_delegate: false,
+                default_frozen: false,
+                has_mint_close_authority: false,
+                effective_buy_tax_pct: 0.0,
+                effective_sell_tax_pct: 0.0,
+                price_impact_curve: Vec::new(),
                 warnings: vec!["Token account does not exist".to_string()],
             });
         }
 
-        // Parse mint account data
-        // In a real implementation, we would deserialize the SPL Token mint account
-        // For now, we'll do basic checks
-
-        let mut can_freeze = false;
-        let mut can_mint = false;
-        let mut can_burn = false;
-
-        // Check mint authority (if None, minting is disabled - good)
-        // If Some(pubkey), check if it's a known malicious address
-        // For now, we'll assume if authority exists, it can mint
-        if mint_account.data.len() >= 36 {
-            // SPL Token mint account structure:
-            // - Option<Pubkey> mint_authority (36 bytes)
-            // - u64 supply
-            // - u8 decimals
-            // - bool is_initialized
-            // - Option<Pubkey> freeze_authority
-
-            // Check if mint authority exists (first 36 bytes)
-            let has_mint_authority = mint_account.data[0] != 0;
-            if has_mint_authority {
-                can_mint = true;
-                score -= 0.3;
-                warnings.push("Token has active mint authority".to_string());
-            }
+        // Parse the mint via the real SPL Token / Token-2022 layout,
+        // walking the TLV extension area for Token-2022 mints.
+        let inspection = inspect_mint(&mint_account)?;
+        score -= inspection.score_penalty;
+        warnings.extend(inspection.warnings);
 
-            // Check freeze authority (around byte 73)
-            if mint_account.data.len() >= 73 {
-                let has_freeze_authority = mint_account.data[73] != 0;
-                if has_freeze_authority {
-                    can_freeze = true;
-                    score -= 0.4;
-                    warnings.push("Token has freeze authority - can freeze accounts".to_string());
-                }
-            }
-        }
-
-        // Check for liquidity (this would require querying Raydium/Orca pools)
-        // For now, we'll assume liquidity exists if we can't determine otherwise
-        let has_liquidity = true; // Placeholder
+        // Probe actual buy/sell-ability with a tiny round-trip quote rather
+        // than guessing from raw mint bytes.
+        let probe = self.probe_round_trip(&mint.to_string()).await;
+        let has_liquidity = probe.has_liquidity;
+        score -= probe.score_penalty;
+        warnings.extend(probe.warnings);
 
         if !has_liquidity {
-            score -= 0.2;
             warnings.push("No liquidity detected".to_string());
         }
 
-        // Determine if it's a honeypot
-        let is_honeypot = can_freeze || (can_mint && score < 0.5);
+        // `TransferFeeConfig` taxes every transfer at the protocol level,
+        // the buy swap included, regardless of what the AMM round trip
+        // shows - the buy-side counterpart to `probe.effective_sell_tax_pct`.
+        let effective_buy_tax_pct = inspection.transfer_fee_bps.unwrap_or(0) as f64 / 100.0;
+
+        // A permanent delegate or default-frozen accounts make the token
+        // unsafe to hold regardless of what the legacy freeze-authority
+        // check or the round-trip probe concluded.
+        let is_honeypot = probe.is_honeypot
+            || inspection.can_freeze
+            || inspection.has_permanent_delegate
+            || inspection.default_frozen
+            || (inspection.can_mint && score < 0.5);
 
         // Ensure score is between 0.0 and 1.0
         score = score.max(0.0).min(1.0);
@@ -100,10 +468,17 @@ impl HoneypotAnalyzer {
         Ok(SafetyScore {
             score,
             is_honeypot,
-            can_freeze,
-            can_mint,
-            can_burn: can_mint, // If can mint, can effectively burn by minting to burn address
+            can_freeze: inspection.can_freeze,
+            can_mint: inspection.can_mint,
             has_liquidity,
+            has_transfer_fee: inspection.has_transfer_fee,
+            transfer_fee_bps: inspection.transfer_fee_bps,
+            has_permanent_delegate: inspection.has_permanent_delegate,
+            default_frozen: inspection.default_frozen,
+            has_mint_close_authority: inspection.has_mint_close_authority,
+            effective_buy_tax_pct,
+            effective_sell_tax_pct: probe.effective_sell_tax_pct,
+            price_impact_curve: probe.price_impact_curve,
             warnings,
         })
     }
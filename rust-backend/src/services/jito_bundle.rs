@@ -1,19 +1,49 @@
 /// Jito Bundle Service
-/// 
+///
 /// Submits transaction bundles to Jito Block Engine for MEV protection.
 /// Bundles are atomic - all transactions succeed or all fail together.
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use anyhow::{anyhow, Result};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use solana_sdk::transaction::Transaction;
+use solana_client::rpc_client::RpcClient as SolanaRpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::telemetry::Metrics;
+
+/// Jito's documented minimum tip, in lamports.
+pub const JITO_MIN_TIP_LAMPORTS: u64 = 1_000;
+
 /// Jito Block Engine endpoints
 pub const JITO_MAINNET_BLOCK_ENGINE: &str = "https://mainnet.block-engine.jito.wtf";
 pub const JITO_MAINNET_BUNDLES: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
 
+/// Jito Block Engine regions, each with its own bundle-submission endpoint.
+/// `submit_bundle` picks the fastest one by measured p90 latency rather than
+/// always hitting the generic `mainnet` endpoint.
+pub const JITO_REGIONS: [(&str, &str); 5] = [
+    ("amsterdam", "https://amsterdam.mainnet.block-engine.jito.wtf/api/v1/bundles"),
+    ("frankfurt", "https://frankfurt.mainnet.block-engine.jito.wtf/api/v1/bundles"),
+    ("ny", "https://ny.mainnet.block-engine.jito.wtf/api/v1/bundles"),
+    ("tokyo", "https://tokyo.mainnet.block-engine.jito.wtf/api/v1/bundles"),
+    ("slc", "https://slc.mainnet.block-engine.jito.wtf/api/v1/bundles"),
+];
+
 /// Jito tip accounts (rotate for load balancing)
 pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
     "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
@@ -26,6 +56,79 @@ pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
     "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
 ];
 
+/// Exponential bucket boundaries (ms) for the per-endpoint latency histogram.
+const LATENCY_BUCKET_BOUNDARIES_MS: [f64; 9] = [5.0, 10.0, 20.0, 40.0, 80.0, 160.0, 320.0, 640.0, 1280.0];
+
+/// Fixed-bucket latency histogram with approximate percentile queries.
+///
+/// Buckets are exponential (see [`LATENCY_BUCKET_BOUNDARIES_MS`]) plus one
+/// overflow bucket for anything above the last boundary. Percentiles are
+/// computed by walking cumulative counts and linearly interpolating within
+/// the bucket that contains the target rank.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: vec![0; LATENCY_BUCKET_BOUNDARIES_MS.len() + 1],
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, latency_ms: f64) {
+        let bucket = LATENCY_BUCKET_BOUNDARIES_MS
+            .iter()
+            .position(|&boundary| latency_ms <= boundary)
+            .unwrap_or(LATENCY_BUCKET_BOUNDARIES_MS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+    }
+
+    /// Approximate the `q`th percentile (0.0-1.0) in milliseconds.
+    fn percentile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return f64::INFINITY;
+        }
+
+        let target_rank = (q * self.count as f64).ceil().max(1.0);
+        let mut cumulative = 0u64;
+        let mut lower_bound = 0.0;
+
+        for (i, &bucket_count) in self.buckets.iter().enumerate() {
+            let upper_bound = LATENCY_BUCKET_BOUNDARIES_MS
+                .get(i)
+                .copied()
+                .unwrap_or(LATENCY_BUCKET_BOUNDARIES_MS[LATENCY_BUCKET_BOUNDARIES_MS.len() - 1] * 2.0);
+
+            if bucket_count > 0 && cumulative + bucket_count >= target_rank as u64 {
+                let rank_within_bucket = target_rank - cumulative as f64;
+                let fraction = rank_within_bucket / bucket_count as f64;
+                return lower_bound + fraction * (upper_bound - lower_bound);
+            }
+
+            cumulative += bucket_count;
+            lower_bound = upper_bound;
+        }
+
+        lower_bound
+    }
+}
+
+/// Per-region percentiles, as returned by the `/jito/latency` route.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointLatencyStats {
+    pub region: String,
+    pub endpoint: String,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub samples: u64,
+}
+
 /// Bundle submission request to Jito
 #[derive(Debug, Serialize)]
 pub struct JitoBundleRequest {
@@ -60,7 +163,7 @@ pub struct BundleStatusResponse {
     pub result: Option<BundleStatus>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BundleStatus {
     pub bundle_id: String,
     pub status: String, // "Invalid", "Pending", "Landed", "Failed"
@@ -68,51 +171,205 @@ pub struct BundleStatus {
     pub landed_slot: Option<u64>,
 }
 
+/// Result of `submit_bundle_with_tip`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TipBundleSubmission {
+    pub bundle_id: String,
+    pub tip_signature: String,
+}
+
+/// Error from `confirm_bundle`, distinguishing an explicit terminal failure
+/// from simply running out of time before one was observed.
+#[derive(Debug)]
+pub enum ConfirmBundleError {
+    /// The bundle reached a terminal "Failed" or "Invalid" status.
+    Failed(BundleStatus),
+    /// `timeout` elapsed with no terminal status observed.
+    TimedOut { bundle_id: String, waited: Duration },
+}
+
+impl std::fmt::Display for ConfirmBundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfirmBundleError::Failed(status) => {
+                write!(f, "bundle {} reached terminal status {}", status.bundle_id, status.status)
+            }
+            ConfirmBundleError::TimedOut { bundle_id, waited } => {
+                write!(f, "timed out after {:?} waiting for bundle {} to confirm", waited, bundle_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfirmBundleError {}
+
 /// Jito Bundle Client
 #[derive(Clone)]
 pub struct JitoBundleClient {
     http: Client,
-    endpoint: String,
-    tip_account_index: usize,
+    /// `Some` pins the client to one endpoint (used by `with_endpoint` for
+    /// tests); `None` means region selection picks the fastest endpoint.
+    pinned_endpoint: Option<String>,
+    tip_account_index: Arc<AtomicUsize>,
+    /// Shared so every clone (e.g. the one handed to a background poll task)
+    /// observes and contributes to the same latency picture.
+    histograms: Arc<RwLock<HashMap<String, LatencyHistogram>>>,
+    round_robin_index: Arc<AtomicUsize>,
+    /// Used only to fetch a recent blockhash when building the tip
+    /// transaction in `submit_bundle_with_tip`.
+    rpc: Arc<SolanaRpcClient>,
+    /// Cross-cutting submission metrics (see `telemetry::Metrics`). `None`
+    /// when constructed without `with_metrics`, e.g. in tests.
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl JitoBundleClient {
-    /// Create a new Jito bundle client
+    /// Create a new Jito bundle client that routes across all known regions.
     pub fn new() -> Self {
         Self {
             http: Client::builder()
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
-            endpoint: JITO_MAINNET_BUNDLES.to_string(),
-            tip_account_index: 0,
+            pinned_endpoint: None,
+            tip_account_index: Arc::new(AtomicUsize::new(0)),
+            histograms: Arc::new(RwLock::new(HashMap::new())),
+            round_robin_index: Arc::new(AtomicUsize::new(0)),
+            rpc: Arc::new(SolanaRpcClient::new_with_commitment(
+                "https://api.mainnet-beta.solana.com".to_string(),
+                CommitmentConfig::confirmed(),
+            )),
+            metrics: None,
         }
     }
 
-    /// Create with custom endpoint (for testing)
+    /// Create with custom endpoint (for testing) - disables region routing.
     pub fn with_endpoint(endpoint: &str) -> Self {
         Self {
             http: Client::builder()
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
-            endpoint: endpoint.to_string(),
-            tip_account_index: 0,
+            pinned_endpoint: Some(endpoint.to_string()),
+            tip_account_index: Arc::new(AtomicUsize::new(0)),
+            histograms: Arc::new(RwLock::new(HashMap::new())),
+            round_robin_index: Arc::new(AtomicUsize::new(0)),
+            rpc: Arc::new(SolanaRpcClient::new_with_commitment(
+                "https://api.mainnet-beta.solana.com".to_string(),
+                CommitmentConfig::confirmed(),
+            )),
+            metrics: None,
         }
     }
 
+    /// Use a shared RPC client (e.g. the server's primary endpoint) for
+    /// blockhash lookups instead of the public mainnet-beta endpoint.
+    pub fn with_rpc(mut self, rpc: Arc<SolanaRpcClient>) -> Self {
+        self.rpc = rpc;
+        self
+    }
+
+    /// Record submission latency/outcome into the shared `/metrics` endpoint
+    /// in addition to this client's own per-region histograms.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Get the next tip account (round-robin)
-    pub fn get_tip_account(&mut self) -> &'static str {
-        let account = JITO_TIP_ACCOUNTS[self.tip_account_index];
-        self.tip_account_index = (self.tip_account_index + 1) % JITO_TIP_ACCOUNTS.len();
-        account
+    pub fn get_tip_account(&self) -> &'static str {
+        let index = self.tip_account_index.fetch_add(1, Ordering::Relaxed) % JITO_TIP_ACCOUNTS.len();
+        JITO_TIP_ACCOUNTS[index]
+    }
+
+    /// Record an observed submit round-trip latency for one endpoint.
+    async fn record_latency(&self, endpoint: &str, latency_ms: f64) {
+        let mut histograms = self.histograms.write().await;
+        histograms
+            .entry(endpoint.to_string())
+            .or_insert_with(LatencyHistogram::new)
+            .record(latency_ms);
+    }
+
+    /// Current p90 latency for an endpoint, or `f64::INFINITY` if unmeasured
+    /// (so untested endpoints are preferred over slow known ones exactly
+    /// once, giving the router a chance to learn about them).
+    async fn p90(&self, endpoint: &str) -> f64 {
+        let histograms = self.histograms.read().await;
+        histograms
+            .get(endpoint)
+            .map(|h| h.percentile(0.90))
+            .unwrap_or(f64::INFINITY)
+    }
+
+    /// Percentile latency for one endpoint, identified by region name.
+    pub async fn percentile(&self, region: &str, q: f64) -> Option<f64> {
+        let endpoint = JITO_REGIONS.iter().find(|(name, _)| *name == region)?.1;
+        let histograms = self.histograms.read().await;
+        Some(
+            histograms
+                .get(endpoint)
+                .map(|h| h.percentile(q))
+                .unwrap_or(f64::INFINITY),
+        )
+    }
+
+    /// Snapshot of per-region latency percentiles, for the `/jito/latency` route.
+    pub async fn latency_stats(&self) -> Vec<EndpointLatencyStats> {
+        let histograms = self.histograms.read().await;
+        JITO_REGIONS
+            .iter()
+            .map(|(region, endpoint)| {
+                let histogram = histograms.get(*endpoint);
+                EndpointLatencyStats {
+                    region: region.to_string(),
+                    endpoint: endpoint.to_string(),
+                    p50_ms: histogram.map(|h| h.percentile(0.50)).unwrap_or(f64::INFINITY),
+                    p90_ms: histogram.map(|h| h.percentile(0.90)).unwrap_or(f64::INFINITY),
+                    p99_ms: histogram.map(|h| h.percentile(0.99)).unwrap_or(f64::INFINITY),
+                    samples: histogram.map(|h| h.count).unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+
+    /// Choose the best endpoint to submit to: the pinned one if set,
+    /// otherwise a region with no latency data yet (so each is tried once),
+    /// then the region with the lowest current p90, falling back to
+    /// round-robin when no region has latency data yet.
+    async fn select_endpoint(&self) -> String {
+        if let Some(pinned) = &self.pinned_endpoint {
+            return pinned.clone();
+        }
+
+        let mut best: Option<(&str, f64)> = None;
+        let mut unmeasured: Option<&str> = None;
+        for (_, endpoint) in JITO_REGIONS.iter() {
+            let p90 = self.p90(endpoint).await;
+            if !p90.is_finite() {
+                unmeasured = unmeasured.or(Some(*endpoint));
+            } else if best.map(|(_, b)| p90 < b).unwrap_or(true) {
+                best = Some((endpoint, p90));
+            }
+        }
+
+        match (best, unmeasured) {
+            (Some(_), Some(endpoint)) => endpoint.to_string(),
+            (Some((endpoint, _)), None) => endpoint.to_string(),
+            _ => {
+                // No endpoint has data yet - round-robin instead of always
+                // hitting the first region.
+                let index = self.round_robin_index.fetch_add(1, Ordering::Relaxed) % JITO_REGIONS.len();
+                JITO_REGIONS[index].1.to_string()
+            }
+        }
     }
 
     /// Submit a bundle of transactions to Jito
-    /// 
+    ///
     /// # Arguments
     /// * `transactions` - Signed transactions to bundle
-    /// 
+    ///
     /// # Returns
     /// Bundle UUID on success
     pub async fn submit_bundle(&self, transactions: Vec<Transaction>) -> Result<String> {
@@ -137,18 +394,36 @@ impl JitoBundleClient {
             params: vec![encoded_txs],
         };
 
-        info!("Submitting bundle with {} transactions to Jito", transactions.len());
+        let endpoint = self.select_endpoint().await;
+        info!(
+            "Submitting bundle with {} transactions to Jito ({})",
+            transactions.len(),
+            endpoint
+        );
 
-        let response = self.http
-            .post(&self.endpoint)
-            .json(&request)
-            .send()
-            .await?;
+        let started_at = Instant::now();
+        let response = self.http.post(&endpoint).json(&request).send().await;
+        let latency_ms = started_at.elapsed().as_secs_f64() * 1000.0;
+
+        let response = match response {
+            Ok(response) => {
+                self.record_latency(&endpoint, latency_ms).await;
+                response
+            }
+            Err(e) => {
+                // Treat a hard connection failure as worst-case latency so
+                // this endpoint isn't chosen again until it recovers.
+                self.record_latency(&endpoint, LATENCY_BUCKET_BOUNDARIES_MS[LATENCY_BUCKET_BOUNDARIES_MS.len() - 1] * 2.0).await;
+                self.observe_submission(latency_ms, false).await;
+                return Err(anyhow!("Jito request failed: {}", e));
+            }
+        };
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             warn!("Jito bundle submission failed: {} - {}", status, text);
+            self.observe_submission(latency_ms, false).await;
             return Err(anyhow!("Jito bundle failed: {} - {}", status, text));
         }
 
@@ -156,20 +431,34 @@ impl JitoBundleClient {
 
         if let Some(error) = bundle_response.error {
             warn!("Jito bundle error: {} - {}", error.code, error.message);
+            self.observe_submission(latency_ms, false).await;
             return Err(anyhow!("Jito error: {}", error.message));
         }
 
         match bundle_response.result {
             Some(bundle_id) => {
                 info!("Bundle submitted successfully: {}", bundle_id);
+                self.observe_submission(latency_ms, true).await;
                 Ok(bundle_id)
             }
-            None => Err(anyhow!("No bundle ID returned from Jito")),
+            None => {
+                self.observe_submission(latency_ms, false).await;
+                Err(anyhow!("No bundle ID returned from Jito"))
+            }
+        }
+    }
+
+    /// Record `submit_bundle`'s outcome into the shared `/metrics` endpoint,
+    /// if one was wired in via `with_metrics`.
+    async fn observe_submission(&self, latency_ms: f64, success: bool) {
+        if let Some(metrics) = &self.metrics {
+            metrics.observe("submit_bundle", "jito", latency_ms, success).await;
         }
     }
 
     /// Check the status of a submitted bundle
     pub async fn get_bundle_status(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let endpoint = self.select_endpoint().await;
         let request = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -178,7 +467,7 @@ impl JitoBundleClient {
         });
 
         let response = self.http
-            .post(&self.endpoint)
+            .post(&endpoint)
             .json(&request)
             .send()
             .await?;
@@ -189,22 +478,95 @@ impl JitoBundleClient {
             .ok_or_else(|| anyhow!("Bundle status not found"))
     }
 
-    /// Submit bundle with tip transaction included
-    /// Creates a tip transaction to the Jito tip account
+    /// Poll `get_bundle_status` until the bundle reaches a terminal state or
+    /// `timeout` elapses, backing off exponentially (200ms, doubling up to a
+    /// 2s cap) between polls. Transient HTTP errors are swallowed and
+    /// retried rather than treated as fatal; only an explicit "Failed" or
+    /// "Invalid" status short-circuits with [`ConfirmBundleError::Failed`].
+    pub async fn confirm_bundle(
+        &self,
+        bundle_id: &str,
+        timeout: Duration,
+    ) -> std::result::Result<BundleStatus, ConfirmBundleError> {
+        const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+        const MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+        let deadline = Instant::now() + timeout;
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            match self.get_bundle_status(bundle_id).await {
+                Ok(status) if status.status == "Landed" => return Ok(status),
+                Ok(status) if status.status == "Failed" || status.status == "Invalid" => {
+                    return Err(ConfirmBundleError::Failed(status));
+                }
+                Ok(_) => {}
+                Err(e) => warn!("confirm_bundle: transient error polling {}: {}", bundle_id, e),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(ConfirmBundleError::TimedOut {
+                    bundle_id: bundle_id.to_string(),
+                    waited: timeout,
+                });
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            tokio::time::sleep(backoff.min(remaining)).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Submit bundle with tip transaction included.
+    ///
+    /// Builds and signs a transfer from `payer_keypair` to the next
+    /// round-robin tip account, appends it as the last transaction of the
+    /// bundle (Jito requires the tip to land in the bundle's final tx), and
+    /// submits. Returns both the bundle ID and the tip transaction's signature.
     pub async fn submit_bundle_with_tip(
-        &mut self,
-        transactions: Vec<Transaction>,
-        _tip_lamports: u64,
-        _payer_keypair: &[u8], // We'd need the keypair to sign the tip tx
-    ) -> Result<String> {
-        // In production, you would:
-        // 1. Create a transfer instruction to the tip account
-        // 2. Build and sign the tip transaction
-        // 3. Append it to the bundle
-        // 4. Submit the bundle
-        
-        // For now, just submit without tip (tip can be included in last tx of bundle)
-        self.submit_bundle(transactions).await
+        &self,
+        mut transactions: Vec<Transaction>,
+        tip_lamports: u64,
+        payer_keypair: &[u8],
+    ) -> Result<TipBundleSubmission> {
+        if tip_lamports < JITO_MIN_TIP_LAMPORTS {
+            return Err(anyhow!(
+                "Tip of {} lamports is below Jito's minimum of {} lamports",
+                tip_lamports,
+                JITO_MIN_TIP_LAMPORTS
+            ));
+        }
+        if transactions.is_empty() {
+            return Err(anyhow!("Cannot submit empty bundle"));
+        }
+
+        let payer = Keypair::from_bytes(payer_keypair)
+            .map_err(|e| anyhow!("Invalid payer keypair: {}", e))?;
+        let tip_account = Pubkey::from_str(self.get_tip_account())
+            .map_err(|e| anyhow!("Invalid tip account: {}", e))?;
+
+        let rpc = self.rpc.clone();
+        let blockhash = tokio::task::spawn_blocking(move || rpc.get_latest_blockhash())
+            .await
+            .map_err(|e| anyhow!("Blockhash fetch task panicked: {}", e))??;
+
+        let transfer_ix = system_instruction::transfer(&payer.pubkey(), &tip_account, tip_lamports);
+        let tip_tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            blockhash,
+        );
+        let tip_signature = tip_tx.signatures[0];
+
+        transactions.push(tip_tx);
+
+        let bundle_id = self.submit_bundle(transactions).await?;
+
+        Ok(TipBundleSubmission {
+            bundle_id,
+            tip_signature: tip_signature.to_string(),
+        })
     }
 }
 
@@ -220,13 +582,78 @@ mod tests {
 
     #[test]
     fn test_tip_account_rotation() {
-        let mut client = JitoBundleClient::new();
-        
+        let client = JitoBundleClient::new();
+
         let first = client.get_tip_account();
         let second = client.get_tip_account();
-        
+
         assert_ne!(first, second);
         assert_eq!(first, JITO_TIP_ACCOUNTS[0]);
         assert_eq!(second, JITO_TIP_ACCOUNTS[1]);
     }
+
+    #[test]
+    fn test_histogram_percentiles_monotonic() {
+        let mut histogram = LatencyHistogram::new();
+        for latency in [3.0, 8.0, 15.0, 30.0, 60.0, 500.0, 1000.0] {
+            histogram.record(latency);
+        }
+
+        let p50 = histogram.percentile(0.50);
+        let p90 = histogram.percentile(0.90);
+        let p99 = histogram.percentile(0.99);
+
+        assert!(p50 <= p90);
+        assert!(p90 <= p99);
+    }
+
+    #[test]
+    fn test_histogram_empty_is_infinite() {
+        let histogram = LatencyHistogram::new();
+        assert_eq!(histogram.percentile(0.90), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_tip_instruction_targets_known_tip_account() {
+        let payer = Keypair::new();
+        let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[0]).unwrap();
+        let transfer_ix = system_instruction::transfer(&payer.pubkey(), &tip_account, JITO_MIN_TIP_LAMPORTS);
+        let tip_tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            solana_sdk::hash::Hash::default(),
+        );
+
+        let targets_known_tip_account = tip_tx
+            .message
+            .account_keys
+            .iter()
+            .any(|key| JITO_TIP_ACCOUNTS.contains(&key.to_string().as_str()));
+        assert!(targets_known_tip_account);
+    }
+
+    #[tokio::test]
+    async fn test_submit_bundle_with_tip_rejects_below_minimum() {
+        let client = JitoBundleClient::new();
+        let payer = Keypair::new();
+
+        let result = client
+            .submit_bundle_with_tip(vec![], JITO_MIN_TIP_LAMPORTS - 1, &payer.to_bytes())
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_confirm_bundle_times_out_when_unreachable() {
+        // Port 0 on localhost never accepts connections, so every poll
+        // errors and the loop should fall through to a timeout rather than
+        // hanging or treating the transient error as fatal.
+        let client = JitoBundleClient::with_endpoint("http://127.0.0.1:0");
+
+        let result = client.confirm_bundle("test-bundle", Duration::from_millis(300)).await;
+
+        assert!(matches!(result, Err(ConfirmBundleError::TimedOut { .. })));
+    }
 }
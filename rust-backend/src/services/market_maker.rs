@@ -0,0 +1,218 @@
+/// XYK and linear liquidity-replication strategies.
+///
+/// Instead of trading discretely, these build a ladder of resting
+/// conditional orders that approximate an AMM's continuous payoff: the XYK
+/// strategy mirrors a constant-product (`x*y=k`) curve with geometrically
+/// spaced rungs sized from the curve's own `Δreserve` between each rung's
+/// price bounds, and the linear strategy spreads equal-sized orders evenly
+/// across the range. Either ladder is submitted straight through
+/// `ConditionalOrderEngine`, whose resting-order book *is* the inventory
+/// tracker here - there's no separate position store to keep in sync.
+use serde::{Deserialize, Serialize};
+
+use crate::services::conditional_orders::{
+    ConditionalOrder, ConditionalOrderEngine, OrderKind, PlaceOrderRequest, TriggerDirection,
+};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicationStrategy {
+    /// Replicates a constant-product (`x*y=k`) curve using `reserve_x`/`reserve_y`.
+    Xyk,
+    /// Distributes `total_size` uniformly across the range, equal size per tick.
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LadderSide {
+    /// Resting buy of `base_mint`, triggered as price falls to `price`.
+    Bid,
+    /// Resting sell of `base_mint`, triggered as price rises to `price`.
+    Ask,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LadderOrder {
+    pub price: f64,
+    /// Size of the rung, denominated in `base_mint`.
+    pub size: f64,
+    pub side: LadderSide,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LadderRequest {
+    pub owner_pubkey: String,
+    pub strategy: ReplicationStrategy,
+    /// Token being provided as liquidity - ladder sizes are denominated in this mint.
+    pub base_mint: String,
+    /// The mint `base_mint` is priced against.
+    pub quote_mint: String,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Target constant-product reserves (`k = reserve_x * reserve_y`) - only
+    /// read for `ReplicationStrategy::Xyk`.
+    #[serde(default)]
+    pub reserve_x: f64,
+    #[serde(default)]
+    pub reserve_y: f64,
+    /// Total `base_mint` size to spread across the ladder - only read for
+    /// `ReplicationStrategy::Linear`.
+    #[serde(default)]
+    pub total_size: f64,
+    pub price_low: f64,
+    pub price_high: f64,
+    pub ticks: usize,
+    /// Splits rungs into bids below and asks above this price.
+    pub current_price: f64,
+    pub slippage_bps: u16,
+}
+
+/// `ticks + 1` prices geometrically spaced across `[price_low, price_high]`.
+fn geometric_prices(price_low: f64, price_high: f64, ticks: usize) -> Vec<f64> {
+    let ratio = (price_high / price_low).powf(1.0 / ticks as f64);
+    (0..=ticks).map(|i| price_low * ratio.powi(i as i32)).collect()
+}
+
+/// `x*y=k` gives `x(p) = sqrt(k/p)` for price `p` quoted as `quote` per `base`;
+/// the rung between two bounding prices is sized at the curve's `Δx` over
+/// that move, placed at the geometric midpoint.
+fn xyk_ladder(
+    reserve_x: f64,
+    reserve_y: f64,
+    price_low: f64,
+    price_high: f64,
+    ticks: usize,
+    current_price: f64,
+) -> Vec<LadderOrder> {
+    let k = reserve_x * reserve_y;
+    let prices = geometric_prices(price_low, price_high, ticks);
+    prices
+        .windows(2)
+        .map(|w| {
+            let (p_lo, p_hi) = (w[0], w[1]);
+            let x_lo = (k / p_lo).sqrt();
+            let x_hi = (k / p_hi).sqrt();
+            let mid = (p_lo * p_hi).sqrt();
+            LadderOrder {
+                price: mid,
+                size: (x_lo - x_hi).abs(),
+                side: if mid <= current_price { LadderSide::Bid } else { LadderSide::Ask },
+            }
+        })
+        .collect()
+}
+
+fn linear_ladder(
+    total_size: f64,
+    price_low: f64,
+    price_high: f64,
+    ticks: usize,
+    current_price: f64,
+) -> Vec<LadderOrder> {
+    let size = total_size / ticks as f64;
+    let step = (price_high - price_low) / ticks as f64;
+    (0..ticks)
+        .map(|i| {
+            let mid = price_low + step * (i as f64 + 0.5);
+            LadderOrder {
+                price: mid,
+                size,
+                side: if mid <= current_price { LadderSide::Bid } else { LadderSide::Ask },
+            }
+        })
+        .collect()
+}
+
+pub fn build_ladder(req: &LadderRequest) -> Vec<LadderOrder> {
+    let ticks = req.ticks.max(1);
+    match req.strategy {
+        ReplicationStrategy::Xyk => xyk_ladder(
+            req.reserve_x,
+            req.reserve_y,
+            req.price_low,
+            req.price_high,
+            ticks,
+            req.current_price,
+        ),
+        ReplicationStrategy::Linear => linear_ladder(
+            req.total_size,
+            req.price_low,
+            req.price_high,
+            ticks,
+            req.current_price,
+        ),
+    }
+}
+
+/// Builds the ladder and places each rung as a resting conditional order,
+/// leaving `ConditionalOrderEngine` to watch prices and manage fills exactly
+/// as it would for any other limit order.
+pub fn place_ladder(engine: &ConditionalOrderEngine, req: LadderRequest) -> Vec<ConditionalOrder> {
+    build_ladder(&req)
+        .into_iter()
+        .map(|rung| {
+            let (input_mint, output_mint, amount, trigger_direction) = match rung.side {
+                LadderSide::Bid => (
+                    req.quote_mint.clone(),
+                    req.base_mint.clone(),
+                    (rung.size * rung.price * 10f64.powi(req.quote_decimals as i32)) as u64,
+                    TriggerDirection::AtOrBelow,
+                ),
+                LadderSide::Ask => (
+                    req.base_mint.clone(),
+                    req.quote_mint.clone(),
+                    (rung.size * 10f64.powi(req.base_decimals as i32)) as u64,
+                    TriggerDirection::AtOrAbove,
+                ),
+            };
+            engine.place_order(PlaceOrderRequest {
+                owner_pubkey: req.owner_pubkey.clone(),
+                kind: OrderKind::Limit,
+                input_mint,
+                output_mint,
+                amount,
+                slippage_bps: req.slippage_bps,
+                watched_mint: req.base_mint.clone(),
+                trigger_direction,
+                trigger_price: rung.price,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xyk_ladder_conserves_reserve_x_across_the_full_range() {
+        let rungs = xyk_ladder(1_000.0, 1_000_000.0, 500.0, 2_000.0, 8, 1_000.0);
+        let total: f64 = rungs.iter().map(|r| r.size).sum();
+        let k = 1_000.0 * 1_000_000.0;
+        let expected = (k / 500.0f64).sqrt() - (k / 2_000.0f64).sqrt();
+        assert!((total - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn linear_ladder_has_equal_size_rungs() {
+        let rungs = linear_ladder(80.0, 1.0, 2.0, 8, 1.5);
+        assert_eq!(rungs.len(), 8);
+        for rung in &rungs {
+            assert!((rung.size - 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn rungs_split_into_bids_below_and_asks_above_current_price() {
+        let rungs = linear_ladder(80.0, 1.0, 2.0, 8, 1.5);
+        assert!(rungs.iter().any(|r| r.side == LadderSide::Bid));
+        assert!(rungs.iter().any(|r| r.side == LadderSide::Ask));
+        for rung in &rungs {
+            match rung.side {
+                LadderSide::Bid => assert!(rung.price <= 1.5),
+                LadderSide::Ask => assert!(rung.price > 1.5),
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::services::yellowstone_geyser::TransactionUpdate;
+use crate::services::yellowstone_geyser::{TransactionUpdate, PUMP_FUN_PROGRAM_ID, RAYDIUM_PROGRAM_ID};
 use anyhow::Result;
 
 pub struct MigrationDetector;
@@ -10,15 +10,8 @@ impl MigrationDetector {
 
     pub async fn detect_migration(&self, update: &TransactionUpdate) -> Result<Option<MigrationEvent>> {
         // Check if transaction involves both Pump.fun and Raydium
-        let has_pump_fun = update
-            .accounts
-            .iter()
-            .any(|acc| acc == "6EF8rrecthR5D2zonDnV5AP2k4H2F4V1Du8jQ6Cv3B1");
-
-        let has_raydium = update
-            .accounts
-            .iter()
-            .any(|acc| acc == "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+        let has_pump_fun = update.accounts.iter().any(|acc| acc == PUMP_FUN_PROGRAM_ID);
+        let has_raydium = update.accounts.iter().any(|acc| acc == RAYDIUM_PROGRAM_ID);
 
         if has_pump_fun && has_raydium {
             // Extract token mint from transaction
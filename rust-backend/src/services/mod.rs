@@ -1,13 +1,20 @@
 pub mod tx_simulator;
 pub mod honeypot_analyzer;
 pub mod priority_fee;
+pub mod helius_priority_fee;
 pub mod migration_detector;
 pub mod yellowstone_geyser;
 pub mod swap_stream;
 pub mod copy_trade;
 pub mod pulse_categorizer;
+pub mod pubsub;
+pub mod price_coalescer;
+pub mod upstream_guard;
+pub mod conditional_orders;
+pub mod market_maker;
 
 // MEV Protection modules
 pub mod jito_bundle;
 pub mod bloxroute;
 pub mod sandwich_detector;
+pub mod sandwich_feed;
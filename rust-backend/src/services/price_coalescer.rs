@@ -0,0 +1,165 @@
+/// Price Update Coalescer
+///
+/// `/sse/price/:token_address` used to be a placeholder ticker. A real
+/// upstream emits price updates in bursts - far faster than a slow browser
+/// client needs to redraw - so this buffers the latest price per token and
+/// flushes at most once per `min_interval`, modeled as a time-bucketed
+/// queue: a `BTreeMap<Instant, HashSet<TokenId>>` of scheduled flush times
+/// plus a `HashMap<TokenId, LatestPrice>` of buffered updates. A single
+/// background task drains whichever bucket is due and fans the coalesced
+/// prices out over a broadcast channel, the same single-poller-many-
+/// subscribers shape as `PubSubHub`.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, Notify};
+
+const BROADCAST_CAPACITY: usize = 1024;
+
+pub type TokenId = String;
+
+/// The latest observed price for a token, buffered until its flush time.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct LatestPrice {
+    pub price_usd: f64,
+}
+
+/// One coalesced flush: the token it's for and its latest buffered price.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CoalescedUpdate {
+    pub token_address: TokenId,
+    pub price: LatestPrice,
+}
+
+#[derive(Default)]
+struct State {
+    /// Last-write-wins buffer of prices not yet flushed.
+    buffered: HashMap<TokenId, LatestPrice>,
+    /// Scheduled flush times, each holding every token due at that instant.
+    schedule: BTreeMap<Instant, HashSet<TokenId>>,
+    /// The flush instant each token is currently scheduled under, so a
+    /// burst of publishes before that instant doesn't schedule it twice.
+    scheduled_at: HashMap<TokenId, Instant>,
+}
+
+/// Rate-limits per-token price updates so bursty upstream traffic collapses
+/// to at most one flush per token per `min_interval`. Shared as
+/// `Arc<PriceCoalescer>` across every SSE connection.
+pub struct PriceCoalescer {
+    state: Mutex<State>,
+    tx: broadcast::Sender<CoalescedUpdate>,
+    /// Wakes the flush loop when a new, possibly-nearer deadline is scheduled.
+    wake: Notify,
+}
+
+impl PriceCoalescer {
+    pub fn new() -> Arc<Self> {
+        let coalescer = Arc::new(Self {
+            state: Mutex::new(State::default()),
+            tx: broadcast::channel(BROADCAST_CAPACITY).0,
+            wake: Notify::new(),
+        });
+        coalescer.clone().spawn_flush_loop();
+        coalescer
+    }
+
+    /// Merge a new price into the per-token buffer. If the token has no
+    /// flush already scheduled, it's queued for `now + min_interval`;
+    /// otherwise this update rides along with whatever flush is already
+    /// pending, so a burst of publishes inside one `min_interval` window
+    /// still only produces a single coalesced update.
+    pub fn publish(&self, token: TokenId, price: LatestPrice, min_interval: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.buffered.insert(token.clone(), price);
+
+        if state.scheduled_at.contains_key(&token) {
+            return;
+        }
+
+        let flush_at = Instant::now() + min_interval;
+        state.scheduled_at.insert(token.clone(), flush_at);
+        state.schedule.entry(flush_at).or_default().insert(token);
+        drop(state);
+
+        self.wake.notify_one();
+    }
+
+    /// Subscribe to every coalesced flush across all tokens; callers filter
+    /// down to the token(s) they care about.
+    pub fn subscribe(&self) -> broadcast::Receiver<CoalescedUpdate> {
+        self.tx.subscribe()
+    }
+
+    fn spawn_flush_loop(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                let next_deadline = {
+                    let state = self.state.lock().unwrap();
+                    state.schedule.keys().next().copied()
+                };
+
+                let due = match next_deadline {
+                    None => {
+                        self.wake.notified().await;
+                        continue;
+                    }
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if deadline > now {
+                            tokio::select! {
+                                _ = tokio::time::sleep(deadline - now) => {}
+                                _ = self.wake.notified() => {}
+                            }
+                            continue;
+                        }
+                        deadline
+                    }
+                };
+
+                let tokens = {
+                    let mut state = self.state.lock().unwrap();
+                    state.schedule.remove(&due).unwrap_or_default()
+                };
+
+                let mut state = self.state.lock().unwrap();
+                for token in tokens {
+                    state.scheduled_at.remove(&token);
+                    if let Some(price) = state.buffered.remove(&token) {
+                        // No subscribers yet is not an error - broadcast just drops it.
+                        let _ = self.tx.send(CoalescedUpdate {
+                            token_address: token,
+                            price,
+                        });
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bursty_publishes_within_min_interval_coalesce_to_one_flush() {
+        let coalescer = PriceCoalescer::new();
+        let mut rx = coalescer.subscribe();
+
+        for price_usd in [1.0, 2.0, 3.0] {
+            coalescer.publish("TOKEN".to_string(), LatestPrice { price_usd }, Duration::from_millis(50));
+        }
+
+        let update = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("flush should happen within the timeout")
+            .expect("channel should not be closed");
+
+        assert_eq!(update.token_address, "TOKEN");
+        assert_eq!(update.price.price_usd, 3.0, "last-write-wins across the coalesced burst");
+
+        let second = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await;
+        assert!(second.is_err(), "no second flush should fire without a further publish");
+    }
+}
@@ -1,7 +1,19 @@
+/// Dynamic priority-fee estimation.
+///
+/// `get_dynamic_fee` used to just average a placeholder fee vector and add
+/// a flat 20% buffer. Real `getRecentPrioritizationFees` data is noisy on a
+/// per-block basis, so instead we take a high percentile of each fresh
+/// batch as the "target" sample for that refresh and smooth it into a
+/// tracked EMA. That keeps the recommendation from spiking on a single
+/// outlier block while still climbing quickly under sustained congestion.
 use crate::rpc::RpcManager;
-use std::sync::Arc;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriorityFeeEstimate {
@@ -11,39 +23,192 @@ pub struct PriorityFeeEstimate {
     pub network_congestion: f64, // 0.0 to 1.0
 }
 
+/// Tunables for the EMA-percentile model.
+#[derive(Debug, Clone, Copy)]
+pub struct PriorityFeeConfig {
+    /// Percentile (0.0-1.0) of each refresh batch used as the EMA sample.
+    pub percentile: f64,
+    /// EMA smoothing factor applied to each new sample.
+    pub alpha: f64,
+    /// How long a stored EMA is trusted before it's considered stale.
+    pub max_age: Duration,
+    /// Recommended fee used when there's no fresh sample and no usable EMA.
+    pub fallback_lamports: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            percentile: 0.75,
+            alpha: 0.2,
+            max_age: Duration::from_secs(15),
+            fallback_lamports: 5_000,
+        }
+    }
+}
+
+/// The EMA state tracked across refreshes, behind a lock so concurrent
+/// callers share one running estimate instead of each computing their own.
+#[derive(Debug, Clone, Copy, Default)]
+struct EmaState {
+    ema: f64,
+    last_update: Option<Instant>,
+}
+
+/// Urgency tier for [`PriorityFeeService::estimate`], each mapped to a
+/// percentile of the recent fee distribution - the same "pick your
+/// confidence of landing" shape as an EIP-1559 fee-history oracle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeLevel {
+    /// p50 - typical fee, fine when the chain isn't congested.
+    Normal,
+    /// p75 - outbids most of the recent block, for time-sensitive trades.
+    Fast,
+    /// p90 - outbids nearly everyone, for sniping/MEV-sensitive submission.
+    Turbo,
+}
+
+impl FeeLevel {
+    fn percentile(self) -> f64 {
+        match self {
+            FeeLevel::Normal => 0.5,
+            FeeLevel::Fast => 0.75,
+            FeeLevel::Turbo => 0.9,
+        }
+    }
+
+    /// One tier more urgent, capping at `Turbo` - used when congestion is
+    /// high enough that the caller's requested tier is likely to be
+    /// underbid by the time the transaction lands.
+    fn bumped(self) -> FeeLevel {
+        match self {
+            FeeLevel::Normal => FeeLevel::Fast,
+            FeeLevel::Fast => FeeLevel::Turbo,
+            FeeLevel::Turbo => FeeLevel::Turbo,
+        }
+    }
+}
+
+/// Non-zero-fee slots from the last `getRecentPrioritizationFees` sample for
+/// one account set, cached briefly since the same pool/mint is typically
+/// quoted and re-quoted within the same few seconds (e.g. the honeypot
+/// round-trip probe, or a retried swap).
+struct CachedSample {
+    non_zero_fees: Vec<u64>,
+    congestion_ratio: f64,
+    fetched_at: Instant,
+}
+
+/// How long a per-account fee sample is reused before refetching.
+const SAMPLE_CACHE_TTL: Duration = Duration::from_secs(3);
+/// Congestion ratio (fraction of sampled slots with a non-zero fee) above
+/// which `estimate` bumps the requested tier up one level.
+const CONGESTION_BUMP_THRESHOLD: f64 = 0.8;
+
 pub struct PriorityFeeService {
     rpc: Arc<RpcManager>,
+    config: PriorityFeeConfig,
+    state: RwLock<EmaState>,
+    sample_cache: RwLock<HashMap<String, CachedSample>>,
 }
 
 impl PriorityFeeService {
     pub fn new(rpc: Arc<RpcManager>) -> Self {
-        PriorityFeeService { rpc }
+        Self::with_config(rpc, PriorityFeeConfig::default())
     }
 
-    pub async fn get_dynamic_fee(&self) -> Result<PriorityFeeEstimate> {
-        // Get recent prioritization fees from RPC
-        let recent_fees = self.rpc.get_recent_prioritization_fees().await?;
-
-        // Calculate statistics
-        let min_fee = recent_fees.iter().min().copied().unwrap_or(5000);
-        let max_fee = recent_fees.iter().max().copied().unwrap_or(50000);
-        let avg_fee: u64 = if !recent_fees.is_empty() {
-            recent_fees.iter().sum::<u64>() / recent_fees.len() as u64
+    pub fn with_config(rpc: Arc<RpcManager>, config: PriorityFeeConfig) -> Self {
+        PriorityFeeService {
+            rpc,
+            config,
+            state: RwLock::new(EmaState::default()),
+            sample_cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Recommend a lamports-per-CU fee for `accounts` at the given urgency
+    /// `level`: samples the last ~150 slots' `getRecentPrioritizationFees`
+    /// (cached for [`SAMPLE_CACHE_TTL`]), drops zero-fee slots, and returns
+    /// the requested percentile - bumped up one tier if the congestion ratio
+    /// (non-zero-fee slots / total slots) exceeds [`CONGESTION_BUMP_THRESHOLD`].
+    pub async fn estimate(&self, accounts: &[Pubkey], level: FeeLevel) -> Result<u64> {
+        let cache_key = cache_key_for(accounts);
+
+        let needs_fetch = {
+            let cache = self.sample_cache.read().await;
+            match cache.get(&cache_key) {
+                Some(sample) => sample.fetched_at.elapsed() > SAMPLE_CACHE_TTL,
+                None => true,
+            }
+        };
+
+        if needs_fetch {
+            let recent_fees = self.rpc.get_recent_prioritization_fees(accounts).await?;
+            let total_slots = recent_fees.len();
+            let mut non_zero_fees: Vec<u64> = recent_fees.into_iter().filter(|&f| f > 0).collect();
+            non_zero_fees.sort_unstable();
+            let congestion_ratio = if total_slots == 0 {
+                0.0
+            } else {
+                non_zero_fees.len() as f64 / total_slots as f64
+            };
+
+            let mut cache = self.sample_cache.write().await;
+            cache.insert(
+                cache_key.clone(),
+                CachedSample {
+                    non_zero_fees,
+                    congestion_ratio,
+                    fetched_at: Instant::now(),
+                },
+            );
+        }
+
+        let cache = self.sample_cache.read().await;
+        let sample = cache.get(&cache_key).expect("just inserted or already present");
+
+        let effective_level = if sample.congestion_ratio > CONGESTION_BUMP_THRESHOLD {
+            level.bumped()
         } else {
-            5000
+            level
         };
 
-        // Calculate network congestion (0.0 = low, 1.0 = high)
-        // Higher fees indicate higher congestion
+        Ok(percentile(&sample.non_zero_fees, effective_level.percentile())
+            .map(|p| p.round() as u64)
+            .unwrap_or(self.config.fallback_lamports))
+    }
+
+    pub async fn get_dynamic_fee(&self) -> Result<PriorityFeeEstimate> {
+        self.get_dynamic_fee_for_accounts(&[]).await
+    }
+
+    /// Same as [`Self::get_dynamic_fee`], but scopes the sampled fees to
+    /// blocks that wrote to `write_accounts`, which is what callers building
+    /// an actual swap should prefer.
+    pub async fn get_dynamic_fee_for_accounts(
+        &self,
+        write_accounts: &[Pubkey],
+    ) -> Result<PriorityFeeEstimate> {
+        let recent_fees = self
+            .rpc
+            .get_recent_prioritization_fees(write_accounts)
+            .await?;
+
+        let min_fee = recent_fees.iter().min().copied().unwrap_or(0);
+        let max_fee = recent_fees.iter().max().copied().unwrap_or(0);
+
+        let recommended_fee = self.update_and_recommend(&recent_fees).await;
+
+        // Congestion is how close the recommended fee sits to the batch max;
+        // a recommendation near the top of the observed range means the
+        // network is bidding fees up across the board.
         let congestion = if max_fee > 0 {
-            ((avg_fee as f64 / max_fee as f64) * 100.0).min(100.0) / 100.0
+            (recommended_fee as f64 / max_fee as f64).min(1.0)
         } else {
-            0.5
+            0.0
         };
 
-        // Recommended fee: average + 20% buffer for faster confirmation
-        let recommended_fee = (avg_fee as f64 * 1.2) as u64;
-
         Ok(PriorityFeeEstimate {
             recommended_fee_lamports: recommended_fee,
             min_fee_lamports: min_fee,
@@ -52,6 +217,41 @@ impl PriorityFeeService {
         })
     }
 
+    /// Fold a fresh batch of fees into the stored EMA and return the
+    /// fee to recommend right now, falling back to a constant if the batch
+    /// is empty and the stored EMA is missing or stale.
+    async fn update_and_recommend(&self, recent_fees: &[u64]) -> u64 {
+        let mut state = self.state.write().await;
+
+        if let Some(sample) = percentile(recent_fees, self.config.percentile) {
+            state.ema = match state.last_update {
+                Some(_) => self.config.alpha * sample + (1.0 - self.config.alpha) * state.ema,
+                None => sample,
+            };
+            state.last_update = Some(Instant::now());
+            return state.ema.round() as u64;
+        }
+
+        let is_fresh = state
+            .last_update
+            .is_some_and(|t| t.elapsed() <= self.config.max_age);
+
+        if is_fresh {
+            state.ema.round() as u64
+        } else {
+            self.config.fallback_lamports
+        }
+    }
+
+    /// Recommended fee restricted to blocks where `accounts` were
+    /// write-locked, for callers (e.g. swap building) that want the fee to
+    /// reflect contention on the specific pools/token accounts they're
+    /// about to touch rather than the chain-wide average.
+    pub async fn get_fee_for_accounts(&self, accounts: &[Pubkey]) -> Result<u64> {
+        let estimate = self.get_dynamic_fee_for_accounts(accounts).await?;
+        Ok(estimate.recommended_fee_lamports)
+    }
+
     pub async fn get_fee_for_amount(&self, amount_usd: f64) -> Result<u64> {
         let base_estimate = self.get_dynamic_fee().await?;
 
@@ -63,3 +263,34 @@ impl PriorityFeeService {
         }
     }
 }
+
+/// Cache key for a set of write accounts: sorted so the same set in a
+/// different call order still hits the same cache entry.
+fn cache_key_for(accounts: &[Pubkey]) -> String {
+    let mut keys: Vec<String> = accounts.iter().map(|p| p.to_string()).collect();
+    keys.sort_unstable();
+    keys.join(",")
+}
+
+/// Linear-interpolated percentile (0.0-1.0) of `values`, or `None` if empty.
+/// `pub(crate)` so `JupiterClient::estimate_priority_fee` can reuse the same
+/// interpolation instead of duplicating it.
+pub(crate) fn percentile(values: &[u64], q: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sorted: Vec<u64> = values.to_vec();
+    sorted.sort_unstable();
+
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+
+    if lower == upper {
+        return Some(sorted[lower] as f64);
+    }
+
+    let weight = rank - lower as f64;
+    Some(sorted[lower] as f64 * (1.0 - weight) + sorted[upper] as f64 * weight)
+}
@@ -0,0 +1,302 @@
+/// Pub/Sub Hub
+///
+/// Provides a central fan-out point for push-style subscriptions, modeled on
+/// the `eth_subscribe` pattern: a client opens a WebSocket, subscribes to a
+/// topic, and receives an envelope per event without re-polling the
+/// underlying (rate-limited) upstream itself. A single background task drives
+/// each upstream poll loop once and broadcasts results to every subscriber.
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
+use tracing::{info, warn};
+
+use crate::api::pump_fun::PumpFunCoin;
+use crate::api::tokens::MigratingToken;
+use crate::services::jito_bundle::{BundleStatus, JitoBundleClient};
+
+const GRADUATION_THRESHOLD_USD: f64 = 69_000.0;
+const GRADUATION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BUNDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// A subscription kind a client can request over `/ws`.
+#[derive(Debug, Clone)]
+pub enum SubscriptionKind {
+    /// Emits a `MigratingToken` whenever a tracked coin crosses
+    /// [`GRADUATION_THRESHOLD_USD`] from "approaching" to "graduated".
+    GraduationEvents,
+    /// Emits each status transition (Pending -> Landed/Failed) for one bundle.
+    BundleStatus { bundle_id: String },
+}
+
+/// Envelope wrapping a subscription payload, mirroring the shape of an
+/// `eth_subscribe` notification.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Notification<T> {
+    pub subscription_id: u64,
+    pub payload: T,
+}
+
+/// A `futures::Stream` over a `tokio::sync::broadcast` receiver, tagged with
+/// the subscription id it was handed out under.
+pub struct SubscriptionStream<T> {
+    subscription_id: u64,
+    inner: Pin<Box<dyn Stream<Item = T> + Send>>,
+}
+
+impl<T> SubscriptionStream<T>
+where
+    T: Clone + Send + 'static,
+{
+    fn new(subscription_id: u64, rx: broadcast::Receiver<T>) -> Self {
+        let inner = BroadcastStream::new(rx).filter_map(|item| item.ok());
+        Self {
+            subscription_id,
+            inner: Box::pin(inner),
+        }
+    }
+
+    pub fn subscription_id(&self) -> u64 {
+        self.subscription_id
+    }
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = Notification<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let subscription_id = self.subscription_id;
+        self.inner.as_mut().poll_next(cx).map(|opt| {
+            opt.map(|payload| Notification {
+                subscription_id,
+                payload,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BundleStatusEvent {
+    pub bundle_id: String,
+    pub status: String,
+    pub landed_slot: Option<u64>,
+}
+
+/// Central fan-out point for all push subscriptions.
+///
+/// One `PubSubHub` is shared (via `Arc`) across the whole server; its two
+/// background poll loops run once regardless of how many subscribers are
+/// attached.
+pub struct PubSubHub {
+    graduation_tx: broadcast::Sender<MigratingToken>,
+    bundle_tx: broadcast::Sender<BundleStatusEvent>,
+    next_subscription_id: AtomicU64,
+    tracked_bundles: Mutex<HashMap<String, String>>, // bundle_id -> last seen status
+}
+
+impl PubSubHub {
+    pub fn new() -> Arc<Self> {
+        let (graduation_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let (bundle_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Arc::new(Self {
+            graduation_tx,
+            bundle_tx,
+            next_subscription_id: AtomicU64::new(1),
+            tracked_bundles: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn allocate_subscription_id(&self) -> u64 {
+        self.next_subscription_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Subscribe to graduation events. All subscribers share the same
+    /// underlying poll loop.
+    pub fn subscribe_graduations(&self) -> SubscriptionStream<MigratingToken> {
+        let id = self.allocate_subscription_id();
+        SubscriptionStream::new(id, self.graduation_tx.subscribe())
+    }
+
+    /// Subscribe to status transitions for a single bundle. The stream is
+    /// filtered down to the requested `bundle_id` since there is only one
+    /// broadcast channel for all bundles.
+    pub fn subscribe_bundle_status(&self, bundle_id: String) -> SubscriptionStream<BundleStatusEvent> {
+        let id = self.allocate_subscription_id();
+        self.tracked_bundles
+            .lock()
+            .unwrap()
+            .entry(bundle_id.clone())
+            .or_insert_with(|| "Pending".to_string());
+
+        let rx = self.bundle_tx.subscribe();
+        let filtered = BroadcastStream::new(rx)
+            .filter_map(|item| item.ok())
+            .filter(move |event: &BundleStatusEvent| event.bundle_id == bundle_id);
+        SubscriptionStream {
+            subscription_id: id,
+            inner: Box::pin(filtered),
+        }
+    }
+
+    fn publish_graduation(&self, token: MigratingToken) {
+        // No subscribers yet is not an error - the broadcast simply drops it.
+        let _ = self.graduation_tx.send(token);
+    }
+
+    fn publish_bundle_status(&self, event: BundleStatusEvent) {
+        let _ = self.bundle_tx.send(event);
+    }
+
+    /// Drive the Pump.fun poll loop once on a background task, fanning out
+    /// graduation transitions to every subscriber instead of each client
+    /// re-hitting the rate-limited API.
+    pub fn spawn_graduation_poll_loop(self: &Arc<Self>) {
+        let hub = Arc::clone(self);
+        tokio::spawn(async move {
+            let client = reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client");
+
+            let mut last_status: HashMap<String, &'static str> = HashMap::new();
+
+            loop {
+                match poll_pump_fun_coins(&client).await {
+                    Ok(coins) => {
+                        for coin in coins {
+                            let market_cap = coin.usd_market_cap.or(coin.market_cap).unwrap_or(0.0);
+                            let graduated = coin.complete.unwrap_or(false) || coin.raydium_pool.is_some();
+                            let status = if graduated {
+                                "graduated"
+                            } else if market_cap >= GRADUATION_THRESHOLD_USD * 0.7 {
+                                "approaching"
+                            } else {
+                                continue;
+                            };
+
+                            let previous = last_status.get(coin.mint.as_str()).copied();
+                            if previous == Some("approaching") && status == "graduated" {
+                                hub.publish_graduation(MigratingToken {
+                                    token_address: coin.mint.clone(),
+                                    token_symbol: coin.symbol.clone().unwrap_or_default(),
+                                    token_name: coin.name.clone().unwrap_or_default(),
+                                    market_cap_usd: market_cap,
+                                    graduation_status: "graduated".to_string(),
+                                    raydium_pool_address: coin.raydium_pool.clone(),
+                                    graduation_timestamp: coin.created_timestamp,
+                                    liquidity_usd: None,
+                                    logo_url: coin.image_uri.clone(),
+                                });
+                            }
+                            last_status.insert(coin.mint.clone(), status);
+                        }
+                    }
+                    Err(e) => warn!("Graduation poll loop failed to fetch Pump.fun coins: {}", e),
+                }
+
+                tokio::time::sleep(GRADUATION_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Drive bundle status polling for every tracked bundle id, removing a
+    /// bundle from tracking once it reaches a terminal state.
+    pub fn spawn_bundle_status_poll_loop(self: &Arc<Self>, jito: JitoBundleClient) {
+        let hub = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let bundle_ids: Vec<String> = {
+                    let tracked = hub.tracked_bundles.lock().unwrap();
+                    tracked.keys().cloned().collect()
+                };
+
+                for bundle_id in bundle_ids {
+                    match jito.get_bundle_status(&bundle_id).await {
+                        Ok(BundleStatus {
+                            status,
+                            landed_slot,
+                            ..
+                        }) => {
+                            let changed = {
+                                let mut tracked = hub.tracked_bundles.lock().unwrap();
+                                match tracked.get(&bundle_id) {
+                                    Some(prev) if prev == &status => false,
+                                    _ => {
+                                        tracked.insert(bundle_id.clone(), status.clone());
+                                        true
+                                    }
+                                }
+                            };
+
+                            if changed {
+                                hub.publish_bundle_status(BundleStatusEvent {
+                                    bundle_id: bundle_id.clone(),
+                                    status: status.clone(),
+                                    landed_slot,
+                                });
+                            }
+
+                            if status == "Landed" || status == "Failed" || status == "Invalid" {
+                                hub.tracked_bundles.lock().unwrap().remove(&bundle_id);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Bundle status poll failed for {}: {}", bundle_id, e);
+                        }
+                    }
+                }
+
+                tokio::time::sleep(BUNDLE_POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Register a bundle id so the background loop starts polling it.
+    pub fn track_bundle(&self, bundle_id: String) {
+        self.tracked_bundles
+            .lock()
+            .unwrap()
+            .entry(bundle_id)
+            .or_insert_with(|| "Pending".to_string());
+    }
+}
+
+impl Default for PubSubHub {
+    fn default() -> Self {
+        let (graduation_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let (bundle_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self {
+            graduation_tx,
+            bundle_tx,
+            next_subscription_id: AtomicU64::new(1),
+            tracked_bundles: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+async fn poll_pump_fun_coins(client: &reqwest::Client) -> anyhow::Result<Vec<PumpFunCoin>> {
+    let url = "https://frontend-api.pump.fun/coins?offset=0&limit=50&sort=market_cap&order=DESC&includeNsfw=false";
+    let response = client
+        .get(url)
+        .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36")
+        .header("Accept", "application/json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("Pump.fun API error: {}", response.status()));
+    }
+
+    let coins = response.json::<Vec<PumpFunCoin>>().await?;
+    info!("Graduation poll loop fetched {} coins", coins.len());
+    Ok(coins)
+}
@@ -22,6 +22,91 @@ pub struct PendingTransaction {
     pub slot: u64,
 }
 
+/// A constant-product AMM pool's reserves at the moment a sandwich is
+/// being evaluated, in lamports/base-units on each side of the curve
+/// `x*y=k`. Lets `SandwichDetector` forward-simulate the classic
+/// front-run/victim/back-run swap sequence instead of approximating
+/// profit from a flat slippage assumption.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolState {
+    pub reserve_token: u64,
+    pub reserve_sol: u64,
+    /// Swap fee in basis points (e.g. 30 = 0.3%).
+    pub fee_bps: u16,
+}
+
+impl PoolState {
+    /// Output amount for swapping `amount_in` of the `in` side into the
+    /// `out` side of a constant-product pool, net of `fee_bps`:
+    /// `out = reserve_out * amount_in_after_fee / (reserve_in + amount_in_after_fee)`.
+    pub(crate) fn swap_out(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> u64 {
+        if amount_in == 0 || reserve_in == 0 || reserve_out == 0 {
+            return 0;
+        }
+
+        let amount_in_after_fee =
+            (amount_in as u128) * (10_000 - fee_bps as u128) / 10_000;
+        let numerator = (reserve_out as u128) * amount_in_after_fee;
+        let denominator = (reserve_in as u128) + amount_in_after_fee;
+
+        (numerator / denominator) as u64
+    }
+
+    /// Buy `amount_sol_in` lamports of SOL worth of token, returning
+    /// `(token_out, reserves after the swap)`.
+    pub(crate) fn buy_token(&self, amount_sol_in: u64) -> (u64, PoolState) {
+        let token_out = Self::swap_out(self.reserve_sol, self.reserve_token, amount_sol_in, self.fee_bps);
+        let after = PoolState {
+            reserve_sol: self.reserve_sol + amount_sol_in,
+            reserve_token: self.reserve_token.saturating_sub(token_out),
+            fee_bps: self.fee_bps,
+        };
+        (token_out, after)
+    }
+
+    /// Sell `amount_token_in` of the token, returning
+    /// `(sol_out, reserves after the swap)`.
+    pub(crate) fn sell_token(&self, amount_token_in: u64) -> (u64, PoolState) {
+        let sol_out = Self::swap_out(self.reserve_token, self.reserve_sol, amount_token_in, self.fee_bps);
+        let after = PoolState {
+            reserve_token: self.reserve_token + amount_token_in,
+            reserve_sol: self.reserve_sol.saturating_sub(sol_out),
+            fee_bps: self.fee_bps,
+        };
+        (sol_out, after)
+    }
+
+    /// Recover the reserves a constant-product pool must have had to produce
+    /// `out` for `amount_in` at `price_impact_pct` (Jupiter's own quote
+    /// field), without reading the pool account directly:
+    /// `reserve_in = amount_in_after_fee * (1 - impact) / impact` and
+    /// `reserve_out = out / impact`, derived from
+    /// `impact = amount_in_after_fee / (reserve_in + amount_in_after_fee)`.
+    /// Returns `None` when `price_impact_pct` is too close to zero to invert
+    /// (an effectively bottomless pool, or a quote that didn't move price at
+    /// all) rather than risk absurd reserve estimates from noise.
+    pub(crate) fn from_quote(amount_in: u64, out: u64, price_impact_pct: f64, fee_bps: u16) -> Option<PoolState> {
+        let impact = price_impact_pct / 100.0;
+        if !(impact > 0.0001) || out == 0 {
+            return None;
+        }
+
+        let amount_in_after_fee = (amount_in as f64) * (10_000.0 - fee_bps as f64) / 10_000.0;
+        let reserve_in = amount_in_after_fee * (1.0 - impact) / impact;
+        let reserve_out = (out as f64) / impact;
+
+        if !reserve_in.is_finite() || !reserve_out.is_finite() || reserve_in < 1.0 || reserve_out < 1.0 {
+            return None;
+        }
+
+        Some(PoolState {
+            reserve_sol: reserve_in as u64,
+            reserve_token: reserve_out as u64,
+            fee_bps,
+        })
+    }
+}
+
 /// Sandwich attack pattern detection result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandwichAlert {
@@ -209,10 +294,110 @@ impl SandwichDetector {
         // Real calculation would use AMM curve math
         let ratio = front_run_amount as f64 / victim_amount as f64;
         let slippage_impact = 0.01; // 1% base slippage
-        
+
         (victim_amount as f64 * slippage_impact * ratio.min(10.0)) as u64
     }
 
+    /// Forward-simulate the classic three-swap sandwich on a constant-product
+    /// pool: the attacker buys `front_run_amount` (in SOL lamports) of token
+    /// ahead of the victim's buy, the victim's `victim_amount` buy lands on
+    /// the moved reserves, then the attacker sells the exact token amount
+    /// they just bought. Returns signed profit in lamports - negative when
+    /// the front-run costs more than the back-run recovers.
+    pub fn simulate_sandwich_profit(
+        &self,
+        pool: &PoolState,
+        front_run_amount: u64,
+        victim_amount: u64,
+    ) -> i64 {
+        let (attacker_tokens, pool_after_frontrun) = pool.buy_token(front_run_amount);
+        let (_victim_tokens, pool_after_victim) = pool_after_frontrun.buy_token(victim_amount);
+        let (sol_out, _pool_after_backrun) = pool_after_victim.sell_token(attacker_tokens);
+
+        sol_out as i64 - front_run_amount as i64
+    }
+
+    /// Find the attacker buy size (in SOL lamports) that maximizes
+    /// sandwich profit against `victim_amount`, via ternary search over
+    /// `simulate_sandwich_profit` - profit rises then falls as the
+    /// front-run size grows past the point where it eats into the victim's
+    /// own price impact, so the curve is unimodal over a reasonable range.
+    pub fn optimal_frontrun_amount(&self, pool: &PoolState, victim_amount: u64) -> u64 {
+        let mut low: u64 = 0;
+        let mut high: u64 = pool.reserve_sol.saturating_mul(2).max(victim_amount.max(1));
+
+        for _ in 0..64 {
+            if high - low < 2 {
+                break;
+            }
+            let third = (high - low) / 3;
+            let m1 = low + third;
+            let m2 = high - third;
+
+            let profit1 = self.simulate_sandwich_profit(pool, m1, victim_amount);
+            let profit2 = self.simulate_sandwich_profit(pool, m2, victim_amount);
+
+            if profit1 < profit2 {
+                low = m1 + 1;
+            } else {
+                high = m2.saturating_sub(1).max(low);
+            }
+        }
+
+        let candidates = [low, high, (low + high) / 2];
+        candidates
+            .into_iter()
+            .max_by_key(|&amount| self.simulate_sandwich_profit(pool, amount, victim_amount))
+            .unwrap_or(0)
+    }
+
+    /// Pool-aware risk analysis - the same front-run pattern matching as
+    /// `analyze_sandwich_risk`, but with `simulate_sandwich_profit` driving
+    /// the profit estimate (and therefore `classify_severity`) instead of
+    /// the flat slippage heuristic.
+    pub fn analyze_sandwich_risk_with_pool(
+        &self,
+        token_mint: &str,
+        your_amount: u64,
+        is_buy: bool,
+        pool: &PoolState,
+    ) -> Option<SandwichAlert> {
+        let activity = self.token_activity.get(token_mint)?;
+
+        if !is_buy {
+            return None;
+        }
+
+        let now = Instant::now();
+        let recent = activity
+            .iter()
+            .filter(|tx| now.duration_since(tx.timestamp) < self.analysis_window);
+
+        for tx in recent {
+            if !tx.is_buy || tx.amount <= your_amount * 5 {
+                continue;
+            }
+
+            let profit = self.simulate_sandwich_profit(pool, tx.amount, your_amount);
+            if profit <= 0 || (profit as u64) <= self.min_profit_threshold {
+                continue;
+            }
+
+            let profit = profit as u64;
+            return Some(SandwichAlert {
+                severity: self.classify_severity(profit),
+                front_runner_tx: tx.signature.clone(),
+                victim_tx: "YOUR_TX".to_string(),
+                back_runner_tx: None,
+                token_mint: token_mint.to_string(),
+                estimated_profit_lamports: profit,
+                recommendation: "Consider using MEV protection or reducing trade size".to_string(),
+            });
+        }
+
+        None
+    }
+
     /// Classify severity based on estimated profit
     fn classify_severity(&self, profit_lamports: u64) -> SandwichSeverity {
         match profit_lamports {
@@ -272,10 +457,74 @@ mod tests {
     #[test]
     fn test_severity_classification() {
         let detector = SandwichDetector::new();
-        
+
         assert_eq!(detector.classify_severity(50_000), SandwichSeverity::Low);
         assert_eq!(detector.classify_severity(500_000), SandwichSeverity::Medium);
         assert_eq!(detector.classify_severity(5_000_000), SandwichSeverity::High);
         assert_eq!(detector.classify_severity(50_000_000), SandwichSeverity::Critical);
     }
+
+    #[test]
+    fn test_amm_swap_out_respects_fee() {
+        let no_fee = PoolState::swap_out(1_000_000, 1_000_000, 10_000, 0);
+        let with_fee = PoolState::swap_out(1_000_000, 1_000_000, 10_000, 30);
+        assert!(with_fee < no_fee);
+    }
+
+    #[test]
+    fn test_pool_state_from_quote_round_trips_reserves() {
+        let pool = PoolState {
+            reserve_sol: 80_000_000_000,
+            reserve_token: 40_000_000_000,
+            fee_bps: 30,
+        };
+        let amount_in = 2_000_000_000;
+        let (out, _) = pool.buy_token(amount_in);
+        let amount_in_after_fee = amount_in as f64 * 9_970.0 / 10_000.0;
+        let spot_out = amount_in_after_fee * pool.reserve_token as f64 / pool.reserve_sol as f64;
+        let price_impact_pct = (1.0 - out as f64 / spot_out) * 100.0;
+
+        let derived = PoolState::from_quote(amount_in, out, price_impact_pct, pool.fee_bps)
+            .expect("quote with nonzero impact should be invertible");
+
+        let tolerance = 0.01;
+        assert!((derived.reserve_sol as f64 - pool.reserve_sol as f64).abs() / pool.reserve_sol as f64 < tolerance);
+        assert!((derived.reserve_token as f64 - pool.reserve_token as f64).abs() / pool.reserve_token as f64 < tolerance);
+    }
+
+    #[test]
+    fn test_pool_state_from_quote_rejects_negligible_impact() {
+        assert!(PoolState::from_quote(1_000_000, 500_000, 0.0, 30).is_none());
+    }
+
+    #[test]
+    fn test_simulate_sandwich_profit_positive_for_large_frontrun() {
+        let detector = SandwichDetector::new();
+        let pool = PoolState {
+            reserve_sol: 100_000_000_000,
+            reserve_token: 100_000_000_000,
+            fee_bps: 30,
+        };
+
+        let profit = detector.simulate_sandwich_profit(&pool, 5_000_000_000, 1_000_000_000);
+        assert!(profit > 0);
+    }
+
+    #[test]
+    fn test_optimal_frontrun_amount_beats_naive_guesses() {
+        let detector = SandwichDetector::new();
+        let pool = PoolState {
+            reserve_sol: 50_000_000_000,
+            reserve_token: 50_000_000_000,
+            fee_bps: 30,
+        };
+        let victim_amount = 2_000_000_000;
+
+        let optimal = detector.optimal_frontrun_amount(&pool, victim_amount);
+        let optimal_profit = detector.simulate_sandwich_profit(&pool, optimal, victim_amount);
+
+        for guess in [100_000_000, 1_000_000_000, 10_000_000_000] {
+            assert!(optimal_profit >= detector.simulate_sandwich_profit(&pool, guess, victim_amount));
+        }
+    }
 }
@@ -0,0 +1,170 @@
+/// Live mempool ingestion for `SandwichDetector`.
+///
+/// `SandwichDetector` only ever learned about activity through
+/// `record_transaction`, and nothing in the server called it from the
+/// chain - the detector and `CopyTradeEngine` were wired to each other but
+/// never to real data. This bridges the existing `TransactionSubscriber`
+/// geyser feed: subscribe to swap transactions scoped to the AMM programs
+/// and `watched_mints` (an `account_required`-style filter applied in-process
+/// by `TransactionSubscriber`, so only transactions touching a watched mint
+/// reach the detector), decode each into
+/// a `PendingTransaction`, and feed the shared detector continuously. A
+/// separate interval loop runs `cleanup()` so the per-token `VecDeque`
+/// windows don't grow unbounded.
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::warn;
+
+use crate::config::GeyserConfig;
+use crate::rpc::RpcManager;
+use crate::services::sandwich_detector::{PendingTransaction, SandwichDetector};
+use crate::services::yellowstone_geyser::{
+    TransactionSubscriber, TransactionUpdate, WatchedAccounts, PUMP_FUN_PROGRAM_ID,
+    RAYDIUM_PROGRAM_ID, TRANSACTION_CHANNEL_CAPACITY,
+};
+
+/// How often the detector's stale activity is swept.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct SandwichMempoolFeed {
+    config: GeyserConfig,
+    mints: WatchedAccounts,
+    rpc: Arc<RpcManager>,
+    detector: Arc<Mutex<SandwichDetector>>,
+}
+
+impl SandwichMempoolFeed {
+    pub fn new(config: GeyserConfig, rpc: Arc<RpcManager>) -> Self {
+        Self {
+            config,
+            mints: Arc::new(RwLock::new(HashSet::new())),
+            rpc,
+            detector: Arc::new(Mutex::new(SandwichDetector::new())),
+        }
+    }
+
+    /// The shared detector this feed populates - hand this to
+    /// `CopyTradeEngine`/API handlers instead of each standing up an empty
+    /// `SandwichDetector` with no history.
+    pub fn detector(&self) -> Arc<Mutex<SandwichDetector>> {
+        self.detector.clone()
+    }
+
+    /// The mint watch-set. A handler registers interest in a token (e.g.
+    /// just before trading it) by adding its mint here, mirroring how
+    /// `/ws/trading` subscribers add to `WatchedAccounts` for the account
+    /// feed.
+    pub fn watched_mints(&self) -> WatchedAccounts {
+        self.mints.clone()
+    }
+
+    /// Spawn the ingestion subscriber and the periodic cleanup sweep.
+    /// Intended to be called once at startup, like `GeyserSubscriber::run`.
+    pub fn spawn(self: Arc<Self>) {
+        let (tx, rx) = mpsc::channel(TRANSACTION_CHANNEL_CAPACITY);
+
+        let subscriber = TransactionSubscriber::new(
+            self.config.clone(),
+            vec![PUMP_FUN_PROGRAM_ID.to_string(), RAYDIUM_PROGRAM_ID.to_string()],
+            tx,
+            self.rpc.clone(),
+        )
+        .with_required_accounts(self.mints.clone());
+        tokio::spawn(subscriber.run());
+
+        tokio::spawn(self.clone().consume(rx));
+        tokio::spawn(self.clone().cleanup_loop());
+    }
+
+    async fn consume(self: Arc<Self>, mut rx: mpsc::Receiver<TransactionUpdate>) {
+        while let Some(update) = rx.recv().await {
+            let mint = match self.matching_mint(&update).await {
+                Some(mint) => mint,
+                None => continue,
+            };
+
+            let pending = decode_pending_transaction(&update, &mint);
+            self.detector.lock().await.record_transaction(pending);
+        }
+        warn!("Sandwich mempool feed channel closed, ingestion stopped");
+    }
+
+    /// Which of the currently-watched mints (if any) this update touches -
+    /// `TransactionSubscriber` already filtered to the AMM programs plus
+    /// *some* watched mint, this narrows down to which one for
+    /// `PendingTransaction::token_mint`.
+    async fn matching_mint(&self, update: &TransactionUpdate) -> Option<String> {
+        let mints = self.mints.read().await;
+        mints.iter().find(|mint| update.accounts.contains(*mint)).cloned()
+    }
+
+    async fn cleanup_loop(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            self.detector.lock().await.cleanup();
+        }
+    }
+}
+
+/// Decode a geyser `TransactionUpdate` into a `PendingTransaction` for
+/// `mint`. Direction and amount come from a best-effort scan of the
+/// transaction's log lines - Yellowstone doesn't hand us parsed instruction
+/// data here (see the `TODO`s in `TransactionSubscriber::stream_until_failure`),
+/// the same constraint `MigrationDetector::detect_migration` works under.
+fn decode_pending_transaction(update: &TransactionUpdate, mint: &str) -> PendingTransaction {
+    let is_buy = !update.logs.iter().any(|log| log.to_lowercase().contains("sell"));
+    let amount = update.logs.iter().find_map(|log| parse_log_amount(log)).unwrap_or(0);
+
+    PendingTransaction {
+        signature: update.signature.clone(),
+        from: update.accounts.first().cloned().unwrap_or_default(),
+        to: mint.to_string(),
+        token_mint: mint.to_string(),
+        amount,
+        is_buy,
+        timestamp: Instant::now(),
+        slot: update.slot,
+    }
+}
+
+/// Pull a trailing integer out of a `"... amount: 12345"`-style log line,
+/// the logging convention most SPL swap programs use.
+fn parse_log_amount(log: &str) -> Option<u64> {
+    let lower = log.to_lowercase();
+    let (_, amount_str) = lower.split_once("amount:")?;
+    amount_str.trim().split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_amount() {
+        assert_eq!(parse_log_amount("Program log: swap amount: 42000 lamports"), Some(42_000));
+        assert_eq!(parse_log_amount("Program log: no amount here"), None);
+    }
+
+    #[test]
+    fn test_decode_pending_transaction_direction() {
+        let buy = TransactionUpdate {
+            signature: "sig1".to_string(),
+            slot: 1,
+            accounts: vec!["wallet1".to_string()],
+            logs: vec!["Program log: Instruction: Buy".to_string()],
+        };
+        let sell = TransactionUpdate {
+            signature: "sig2".to_string(),
+            slot: 1,
+            accounts: vec!["wallet2".to_string()],
+            logs: vec!["Program log: Instruction: Sell".to_string()],
+        };
+
+        assert!(decode_pending_transaction(&buy, "mint").is_buy);
+        assert!(!decode_pending_transaction(&sell, "mint").is_buy);
+    }
+}
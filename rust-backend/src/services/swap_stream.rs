@@ -1,5 +1,46 @@
+/// Swap stream ingestion and OHLCV candle aggregation.
+///
+/// `monitor_swaps` used to be a stub that only commented about subscribing
+/// to Jupiter/Raydium/Orca swap events - nothing consumed a `SwapEvent` once
+/// decoded. This adds the consumption side: `CandleStore` buckets swaps into
+/// fixed time intervals per pair and maintains rolling OHLCV candles, the
+/// same live-in-memory-store shape as `PriceCoalescer`, plus a `backfill`
+/// entry point to replay historical swaps (e.g. from an RPC transaction
+/// history scan) into the same candles a live feed would produce.
+///
+/// `monitor_swaps` itself is now a real `logsSubscribe` websocket client:
+/// it mentions-filters to the same AMM program ids `TransactionSubscriber`
+/// watches for migrations, and for each confirmed swap fetches the pre/post
+/// SPL token balance deltas through `RpcManager` to recover the mints and
+/// amounts a bare log line doesn't carry (see `decode_swap_event`).
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+use crate::rpc::RpcManager;
+use crate::services::yellowstone_geyser::{PUMP_FUN_PROGRAM_ID, RAYDIUM_PROGRAM_ID};
+
+/// How many finalized candles `CandleStore` keeps per `(pair, interval)`
+/// before dropping the oldest - enough history for a chart without
+/// unbounded growth over a long-running process.
+const MAX_CANDLES_PER_SERIES: usize = 500;
+
+/// Backoff shape for `SwapStreamService::monitor_swaps`, matching
+/// `GeyserSubscriber`/`TransactionSubscriber`'s reconnect delay.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// No frame (notification or system-status ping) within this long means the
+/// socket has silently stalled even though it hasn't errored or closed -
+/// treated the same as a hard disconnect so `monitor_swaps` reconnects.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapEvent {
@@ -8,21 +49,564 @@ pub struct SwapEvent {
     pub token_out: String,
     pub amount_in: u64,
     pub amount_out: u64,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default = "default_decimals")]
+    pub decimals_in: u8,
+    #[serde(default = "default_decimals")]
+    pub decimals_out: u8,
+    pub timestamp: DateTime<Utc>,
+}
+
+fn default_decimals() -> u8 {
+    9 // SOL and most SPL tokens default to 9 decimals
+}
+
+/// Candle bucket width. `as_duration` drives both bucketing and the
+/// forward-fill step between finalized candles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Interval {
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl Interval {
+    pub fn as_duration(&self) -> ChronoDuration {
+        match self {
+            Interval::OneMinute => ChronoDuration::minutes(1),
+            Interval::FiveMinutes => ChronoDuration::minutes(5),
+            Interval::OneHour => ChronoDuration::hours(1),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::OneMinute => "1m",
+            Interval::FiveMinutes => "5m",
+            Interval::OneHour => "1h",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "1m" => Some(Interval::OneMinute),
+            "5m" => Some(Interval::FiveMinutes),
+            "1h" => Some(Interval::OneHour),
+            _ => None,
+        }
+    }
+
+    fn all() -> [Interval; 3] {
+        [Interval::OneMinute, Interval::FiveMinutes, Interval::OneHour]
+    }
+}
+
+/// One OHLCV bar for a `(pair, interval, start)` bucket.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub base_volume: f64,
+    pub quote_volume: f64,
+    pub trade_count: u64,
+}
+
+impl Candle {
+    /// A brand-new candle opened by a single swap.
+    fn opening(start: DateTime<Utc>, price: f64, base_volume: f64, quote_volume: f64) -> Self {
+        Self {
+            start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            base_volume,
+            quote_volume,
+            trade_count: 1,
+        }
+    }
+
+    /// A zero-volume candle that carries the prior close forward, so a gap
+    /// in swap activity doesn't leave a hole in the series.
+    fn forward_filled(start: DateTime<Utc>, prev_close: f64) -> Self {
+        Self {
+            start,
+            open: prev_close,
+            high: prev_close,
+            low: prev_close,
+            close: prev_close,
+            base_volume: 0.0,
+            quote_volume: 0.0,
+            trade_count: 0,
+        }
+    }
+
+    fn apply_trade(&mut self, price: f64, base_volume: f64, quote_volume: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.base_volume += base_volume;
+        self.quote_volume += quote_volume;
+        self.trade_count += 1;
+    }
+}
+
+/// Finalized history plus the still-open bucket for one `(pair, interval)`.
+#[derive(Default)]
+struct Series {
+    history: VecDeque<Candle>,
+    current: Option<Candle>,
+}
+
+impl Series {
+    fn push_history(&mut self, candle: Candle) {
+        self.history.push_back(candle);
+        if self.history.len() > MAX_CANDLES_PER_SERIES {
+            self.history.pop_front();
+        }
+    }
+
+    /// Fold one trade into this series at `bucket_start`, opening a new
+    /// candle, forward-filling gap candles, or merging into the open one as
+    /// needed.
+    fn ingest(&mut self, interval: Interval, bucket_start: DateTime<Utc>, price: f64, base_volume: f64, quote_volume: f64) {
+        // Taken out (rather than matched by reference) so the gap-filling
+        // branch is free to call `self.push_history` while still holding
+        // the old candle.
+        match self.current.take() {
+            Some(mut candle) if candle.start == bucket_start => {
+                candle.apply_trade(price, base_volume, quote_volume);
+                self.current = Some(candle);
+            }
+            Some(candle) if bucket_start > candle.start => {
+                let prev_close = candle.close;
+                let prev_start = candle.start;
+                self.push_history(candle);
+
+                let step = interval.as_duration();
+                let mut fill_start = prev_start + step;
+                while fill_start < bucket_start {
+                    self.push_history(Candle::forward_filled(fill_start, prev_close));
+                    fill_start = fill_start + step;
+                }
+
+                self.current = Some(Candle::opening(bucket_start, price, base_volume, quote_volume));
+            }
+            Some(candle) => {
+                // A late/out-of-order swap landing in an already-closed
+                // bucket - not worth reopening a finalized candle for, so
+                // it's dropped rather than corrupting the forward-fill.
+                self.current = Some(candle);
+            }
+            None => {
+                self.current = Some(Candle::opening(bucket_start, price, base_volume, quote_volume));
+            }
+        }
+    }
+
+    /// Finalized history plus the in-progress candle (if any), oldest
+    /// first, capped to the most recent `limit`.
+    fn snapshot(&self, limit: usize) -> Vec<Candle> {
+        let mut candles: Vec<Candle> = self.history.iter().copied().collect();
+        if let Some(current) = self.current {
+            candles.push(current);
+        }
+        let skip = candles.len().saturating_sub(limit);
+        candles.split_off(skip)
+    }
 }
 
-pub struct SwapStreamService;
+/// Live in-memory OHLCV store, keyed by `(pair, interval)`. Shared as
+/// `Arc<CandleStore>` across the swap subscriber and the query route, the
+/// same shape as `PriceCoalescer`.
+pub struct CandleStore {
+    series: Mutex<HashMap<(String, Interval), Series>>,
+}
+
+impl CandleStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            series: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Execution price as `amount_out / amount_in`, normalized by each
+    /// side's token decimals - i.e. units of `token_out` per `token_in`,
+    /// the price of `token_in`. `None` for a degenerate swap with zero
+    /// input, which has no meaningful price.
+    pub(crate) fn execution_price(event: &SwapEvent) -> Option<f64> {
+        if event.amount_in == 0 {
+            return None;
+        }
+        let base = event.amount_in as f64 / 10f64.powi(event.decimals_in as i32);
+        let quote = event.amount_out as f64 / 10f64.powi(event.decimals_out as i32);
+        Some(quote / base)
+    }
+
+    /// Bucket a swap into every tracked interval's rolling candles.
+    pub fn ingest(&self, event: &SwapEvent) {
+        let price = match Self::execution_price(event) {
+            Some(price) => price,
+            None => return,
+        };
+        let base_volume = event.amount_in as f64 / 10f64.powi(event.decimals_in as i32);
+        let quote_volume = event.amount_out as f64 / 10f64.powi(event.decimals_out as i32);
+        let pair = format!("{}/{}", event.token_in, event.token_out);
+
+        let mut series = self.series.lock().unwrap();
+        for interval in Interval::all() {
+            let bucket_start = Self::bucket_start(event.timestamp, interval);
+            series
+                .entry((pair.clone(), interval))
+                .or_default()
+                .ingest(interval, bucket_start, price, base_volume, quote_volume);
+        }
+    }
+
+    /// Replay historical swaps into the same candles a live feed would
+    /// produce. Events are sorted by timestamp first since a backfill
+    /// source (e.g. an RPC transaction history scan) has no ordering
+    /// guarantee, and `ingest`'s gap-forward-fill depends on seeing swaps
+    /// in chronological order.
+    pub fn backfill(&self, events: &[SwapEvent]) {
+        let mut ordered: Vec<&SwapEvent> = events.iter().collect();
+        ordered.sort_by_key(|event| event.timestamp);
+        for event in ordered {
+            self.ingest(event);
+        }
+    }
+
+    /// Floor `timestamp` to the start of its `interval` bucket.
+    fn bucket_start(timestamp: DateTime<Utc>, interval: Interval) -> DateTime<Utc> {
+        let bucket_secs = interval.as_duration().num_seconds();
+        let floored = timestamp.timestamp().div_euclid(bucket_secs) * bucket_secs;
+        Utc.timestamp_opt(floored, 0).single().unwrap_or(timestamp)
+    }
+
+    /// Candle history for `pair` at `interval`, oldest first, capped to the
+    /// most recent `limit`.
+    pub fn query(&self, pair: &str, interval: Interval, limit: usize) -> Vec<Candle> {
+        self.series
+            .lock()
+            .unwrap()
+            .get(&(pair.to_string(), interval))
+            .map(|series| series.snapshot(limit))
+            .unwrap_or_default()
+    }
+}
+
+/// Program ids `monitor_swaps` mentions-filters its `logsSubscribe` to - the
+/// same Pump.fun/Raydium universe `TransactionSubscriber` watches for
+/// migrations, since those are the AMMs the swap-derived candles and
+/// conditional orders care about.
+const WATCHED_PROGRAM_IDS: [&str; 2] = [PUMP_FUN_PROGRAM_ID, RAYDIUM_PROGRAM_ID];
+
+pub struct SwapStreamService {
+    candles: Arc<CandleStore>,
+    /// Conditional-order watcher, if one was wired up via
+    /// `with_order_engine` - notified of each swap's execution price so
+    /// resting limit/stop-loss orders can fire off the same feed that
+    /// builds candles.
+    order_engine: Option<Arc<crate::services::conditional_orders::ConditionalOrderEngine>>,
+    /// Used by `decode_swap_event` to turn a bare signature off the logs
+    /// socket into the pre/post token balance deltas a swap actually moved.
+    rpc: Arc<RpcManager>,
+    websocket_url: String,
+}
 
 impl SwapStreamService {
-    pub fn new() -> Self {
-        SwapStreamService
+    pub fn new(websocket_url: String, rpc: Arc<RpcManager>) -> Self {
+        SwapStreamService {
+            candles: CandleStore::new(),
+            order_engine: None,
+            rpc,
+            websocket_url,
+        }
+    }
+
+    pub fn with_order_engine(mut self, order_engine: Arc<crate::services::conditional_orders::ConditionalOrderEngine>) -> Self {
+        self.order_engine = Some(order_engine);
+        self
+    }
+
+    /// The shared candle store this service populates - hand this to API
+    /// handlers instead of each standing up an empty `CandleStore`.
+    pub fn candles(&self) -> Arc<CandleStore> {
+        self.candles.clone()
+    }
+
+    /// Fold one decoded swap into the live candle store, and - if a
+    /// `ConditionalOrderEngine` was wired up - check its execution price
+    /// against resting orders watching `token_in`. `monitor_swaps` calls
+    /// this for every swap it decodes off the live feed; `backfill` goes
+    /// straight to the candle store instead, so replayed history never
+    /// reaches the order engine.
+    pub fn handle_swap_event(&self, event: &SwapEvent) {
+        self.candles.ingest(event);
+
+        if let (Some(engine), Some(price)) = (&self.order_engine, CandleStore::execution_price(event)) {
+            let engine = engine.clone();
+            let mint = event.token_in.clone();
+            tokio::spawn(async move {
+                engine.on_price_update(&mint, price).await;
+            });
+        }
+    }
+
+    /// Replay historical swaps (e.g. from an RPC transaction history scan)
+    /// into the live candle store, so a freshly started server isn't
+    /// missing the tail of the series a continuous feed would have built up.
+    pub fn backfill(&self, events: &[SwapEvent]) {
+        self.candles.backfill(events);
     }
 
+    /// Drive the subscribe/reconnect loop forever. Intended to be spawned
+    /// once at startup, like `GeyserSubscriber::run` - failures are logged
+    /// and retried with backoff rather than propagated, since a dead swap
+    /// feed shouldn't take the rest of the server down with it.
     pub async fn monitor_swaps(&self) -> Result<()> {
-        // In production, this would:
-        // 1. Subscribe to Jupiter/Raydium/Orca swap events
-        // 2. Parse transaction logs
-        // 3. Publish to Redis
-        Ok(())
+        let mut delay = INITIAL_RECONNECT_DELAY;
+
+        loop {
+            match self.stream_until_failure().await {
+                Ok(()) => delay = INITIAL_RECONNECT_DELAY,
+                Err(e) => {
+                    warn!("Swap stream disconnected, reconnecting in {:?}: {}", delay, e);
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Connect, subscribe to `WATCHED_PROGRAM_IDS`' log notifications, and
+    /// forward decoded swaps until the socket errors, closes, or goes quiet
+    /// for longer than `HEARTBEAT_TIMEOUT` - a silently stalled connection
+    /// looks identical to an idle one from the caller's side, so it's
+    /// treated the same as a hard disconnect and reconnected.
+    async fn stream_until_failure(&self) -> Result<()> {
+        let (ws_stream, _) = connect_async(&self.websocket_url)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to connect to {}: {}", self.websocket_url, e))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        for (id, program_id) in WATCHED_PROGRAM_IDS.iter().enumerate() {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id as u64 + 1,
+                "method": "logsSubscribe",
+                "params": [{"mentions": [program_id]}, {"commitment": "confirmed"}],
+            });
+            write
+                .send(Message::Text(request.to_string()))
+                .await
+                .map_err(|e| anyhow::anyhow!("failed to send logsSubscribe: {}", e))?;
+        }
+        info!("Subscribed to swap log notifications for {} program id(s)", WATCHED_PROGRAM_IDS.len());
+
+        loop {
+            let frame = match tokio::time::timeout(HEARTBEAT_TIMEOUT, read.next()).await {
+                Ok(Some(Ok(frame))) => frame,
+                Ok(Some(Err(e))) => anyhow::bail!("websocket error: {}", e),
+                Ok(None) => anyhow::bail!("swap log stream closed"),
+                Err(_) => anyhow::bail!("no frame within {:?}, treating socket as stalled", HEARTBEAT_TIMEOUT),
+            };
+
+            let text = match frame {
+                Message::Text(text) => text,
+                Message::Ping(_) | Message::Pong(_) => continue,
+                Message::Close(_) => anyhow::bail!("swap log stream closed by server"),
+                _ => continue,
+            };
+
+            match serde_json::from_str::<LogsRpcFrame>(&text) {
+                Ok(LogsRpcFrame::Ack(ack)) => {
+                    debug!("logsSubscribe ack: id={} subscription={}", ack.id, ack.result);
+                }
+                Ok(LogsRpcFrame::Notification(notification)) => {
+                    self.handle_notification(notification).await;
+                }
+                // A systemStatus ping or any other frame shape this client
+                // doesn't model yet - not worth failing the stream over.
+                Ok(LogsRpcFrame::Other(_)) => {}
+                Err(e) => warn!("Failed to parse swap log frame, skipping: {}", e),
+            }
+        }
+    }
+
+    async fn handle_notification(&self, notification: LogsNotification) {
+        let value = notification.params.result.value;
+        if value.err.is_some() {
+            // Failed transaction - the logs ran but no token balances moved.
+            return;
+        }
+
+        let signature: Signature = match value.signature.parse() {
+            Ok(signature) => signature,
+            Err(e) => {
+                warn!("Swap log notification had an unparseable signature {}: {}", value.signature, e);
+                return;
+            }
+        };
+
+        match self.decode_swap_event(&signature).await {
+            Ok(Some(event)) => self.handle_swap_event(&event),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to decode swap event for {}: {}", signature, e),
+        }
+    }
+
+    /// Recover a `SwapEvent` from `signature`'s pre/post SPL token balance
+    /// deltas: the account whose balance dropped the most is `token_in`,
+    /// the one that rose the most is `token_out`. A swap with fewer than two
+    /// distinct mints moving - a transfer, a non-swap instruction sharing
+    /// the watched program, a quote round-trip with no net balance change -
+    /// isn't a swap this feed can build a candle from, so it's skipped
+    /// rather than guessed at.
+    async fn decode_swap_event(&self, signature: &Signature) -> Result<Option<SwapEvent>> {
+        let deltas = self.rpc.get_token_balance_deltas(signature).await?;
+
+        let token_in = deltas.iter().filter(|d| d.delta < 0).min_by_key(|d| d.delta);
+        let token_out = deltas.iter().filter(|d| d.delta > 0).max_by_key(|d| d.delta);
+
+        let (token_in, token_out) = match (token_in, token_out) {
+            (Some(token_in), Some(token_out)) if token_in.mint != token_out.mint => (token_in, token_out),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(SwapEvent {
+            signature: signature.to_string(),
+            token_in: token_in.mint.clone(),
+            token_out: token_out.mint.clone(),
+            amount_in: (-token_in.delta) as u64,
+            amount_out: token_out.delta as u64,
+            decimals_in: token_in.decimals,
+            decimals_out: token_out.decimals,
+            // `logsSubscribe` notifications carry a slot, not a block time;
+            // receipt time is a close enough stand-in for a live feed,
+            // rather than round-tripping to fetch the block's timestamp.
+            timestamp: Utc::now(),
+        }))
+    }
+}
+
+/// One frame off the logs-subscription socket. `#[serde(untagged)]` because
+/// an RPC ack (`id`/`result`) and a `logsNotification` push have no shared
+/// discriminant field to match on - the same heterogeneous-frame problem
+/// `pubsub::Notification`'s `eth_subscribe`-style envelope sidesteps by
+/// always wrapping in the same shape; this socket doesn't give us that
+/// luxury since it's the raw upstream JSON-RPC protocol.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LogsRpcFrame {
+    Ack(SubscribeAck),
+    Notification(LogsNotification),
+    /// A systemStatus ping, an error frame, or any other shape this client
+    /// doesn't model - caught here instead of failing `from_str` outright so
+    /// one unrecognized frame doesn't look like a dropped connection.
+    Other(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscribeAck {
+    id: u64,
+    result: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsNotification {
+    params: LogsNotificationParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsNotificationParams {
+    result: LogsResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsResult {
+    value: LogsValue,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsValue {
+    signature: String,
+    err: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(token_in: &str, token_out: &str, amount_in: u64, amount_out: u64, timestamp: DateTime<Utc>) -> SwapEvent {
+        SwapEvent {
+            signature: "sig".to_string(),
+            token_in: token_in.to_string(),
+            token_out: token_out.to_string(),
+            amount_in,
+            amount_out,
+            decimals_in: 9,
+            decimals_out: 9,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_ingest_updates_high_low_close_within_one_bucket() {
+        let store = CandleStore::new();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        store.ingest(&swap("SOL", "USDC", 1_000_000_000, 100_000_000_000, t0));
+        store.ingest(&swap("SOL", "USDC", 1_000_000_000, 120_000_000_000, t0 + ChronoDuration::seconds(10)));
+        store.ingest(&swap("SOL", "USDC", 1_000_000_000, 90_000_000_000, t0 + ChronoDuration::seconds(20)));
+
+        let candles = store.query("SOL/USDC", Interval::OneMinute, 10);
+        assert_eq!(candles.len(), 1);
+        let candle = candles[0];
+        assert_eq!(candle.open, 100.0);
+        assert_eq!(candle.high, 120.0);
+        assert_eq!(candle.low, 90.0);
+        assert_eq!(candle.close, 90.0);
+        assert_eq!(candle.trade_count, 3);
+    }
+
+    #[test]
+    fn test_ingest_forward_fills_gaps_between_candles() {
+        let store = CandleStore::new();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        store.ingest(&swap("SOL", "USDC", 1_000_000_000, 100_000_000_000, t0));
+        // Three one-minute buckets later, with no activity in between.
+        store.ingest(&swap("SOL", "USDC", 1_000_000_000, 110_000_000_000, t0 + ChronoDuration::minutes(3)));
+
+        let candles = store.query("SOL/USDC", Interval::OneMinute, 10);
+        assert_eq!(candles.len(), 4);
+        assert_eq!(candles[0].close, 100.0);
+        // The forward-filled gap candles carry the prior close with no volume.
+        assert_eq!(candles[1].open, 100.0);
+        assert_eq!(candles[1].close, 100.0);
+        assert_eq!(candles[1].trade_count, 0);
+        assert_eq!(candles[2].trade_count, 0);
+        assert_eq!(candles[3].close, 110.0);
+        assert_eq!(candles[3].trade_count, 1);
+    }
+
+    #[test]
+    fn test_backfill_replays_out_of_order_events_chronologically() {
+        let store = CandleStore::new();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        let events = vec![
+            swap("SOL", "USDC", 1_000_000_000, 110_000_000_000, t0 + ChronoDuration::seconds(10)),
+            swap("SOL", "USDC", 1_000_000_000, 100_000_000_000, t0),
+        ];
+        store.backfill(&events);
+
+        let candles = store.query("SOL/USDC", Interval::OneMinute, 10);
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].open, 100.0, "earlier timestamp should have opened the candle despite arriving second");
+        assert_eq!(candles[0].close, 110.0);
     }
 }
@@ -1,5 +1,5 @@
 use crate::rpc::RpcManager;
-use solana_sdk::transaction::Transaction;
+use solana_sdk::{account::Account, pubkey::Pubkey, transaction::Transaction};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use anyhow::Result;
@@ -24,48 +24,60 @@ pub struct SimulationReport {
     pub will_succeed: bool,
 }
 
+/// Where `TransactionSimulator::simulate` executes a transaction.
+#[derive(Clone)]
+pub enum SimulationBackend {
+    /// Round-trip to a live RPC endpoint via `simulateTransaction`. Subject
+    /// to RPC latency and rate limits, but always reflects current mainnet
+    /// state.
+    Rpc,
+    /// Execute against an in-process bank seeded with a fixed set of
+    /// accounts, so candidate routes can be dry-run in a batch without RPC
+    /// latency or rate limits, and so tests get a reproducible result that
+    /// doesn't depend on mainnet state.
+    LocalBank { accounts: Vec<(Pubkey, Account)> },
+}
+
 pub struct TransactionSimulator {
     rpc: Arc<RpcManager>,
+    backend: SimulationBackend,
 }
 
 impl TransactionSimulator {
     pub fn new(rpc: Arc<RpcManager>) -> Self {
-        TransactionSimulator { rpc }
+        TransactionSimulator {
+            rpc,
+            backend: SimulationBackend::Rpc,
+        }
+    }
+
+    /// Build a simulator against an explicit backend, e.g. `LocalBank` for
+    /// offline pre-flight of candidate routes or deterministic tests.
+    pub fn with_backend(rpc: Arc<RpcManager>, backend: SimulationBackend) -> Self {
+        TransactionSimulator { rpc, backend }
     }
 
     pub async fn simulate(&self, tx: &Transaction) -> Result<SimulationReport> {
+        match &self.backend {
+            SimulationBackend::Rpc => self.simulate_via_rpc(tx).await,
+            SimulationBackend::LocalBank { accounts } => simulate_via_local_bank(tx, accounts),
+        }
+    }
+
+    async fn simulate_via_rpc(&self, tx: &Transaction) -> Result<SimulationReport> {
         // Use RPC manager to simulate transaction
         let sim_result = self.rpc.simulate_transaction(tx).await?;
 
         // Parse the simulation result (RpcSimulateTransactionResult has fields directly)
         let will_succeed = sim_result.err.is_none();
-        
+
         // Extract logs
         let logs: Vec<String> = sim_result
             .logs
             .unwrap_or_default();
 
-        // Determine result type based on error
-        let (result, error) = if will_succeed {
-            (SimulationResult::Success, None)
-        } else {
-            let err_str = sim_result
-                .err
-                .map(|e| format!("{:?}", e))
-                .unwrap_or_else(|| "Unknown error".to_string());
-
-            let sim_res = if err_str.contains("insufficient") || err_str.contains("funds") {
-                SimulationResult::InsufficientFunds
-            } else if err_str.contains("slippage") {
-                SimulationResult::SlippageExceeded
-            } else if err_str.contains("invalid") || err_str.contains("token") {
-                SimulationResult::InvalidToken
-            } else {
-                SimulationResult::Failure
-            };
-
-            (sim_res, Some(err_str))
-        };
+        let err_str = sim_result.err.map(|e| format!("{:?}", e));
+        let (result, error) = classify_result(will_succeed, err_str);
 
         // Extract compute units from simulation
         let estimated_compute_units = sim_result.units_consumed.or_else(|| {
@@ -81,13 +93,7 @@ impl TransactionSimulator {
                 })
         });
 
-        // Estimate fee (base fee + priority fee)
-        let estimated_fee_lamports = estimated_compute_units
-            .map(|units| {
-                // Base fee: 5000 lamports
-                // Priority fee: varies, estimate 5000
-                5000 + (units / 1000) * 1000
-            });
+        let estimated_fee_lamports = estimate_fee_lamports(estimated_compute_units);
 
         Ok(SimulationReport {
             result,
@@ -99,3 +105,74 @@ impl TransactionSimulator {
         })
     }
 }
+
+/// Classify a simulation outcome the same way regardless of which backend
+/// produced it, so RPC and local-bank simulation reports stay comparable.
+fn classify_result(will_succeed: bool, err_str: Option<String>) -> (SimulationResult, Option<String>) {
+    if will_succeed {
+        return (SimulationResult::Success, None);
+    }
+
+    let err_str = err_str.unwrap_or_else(|| "Unknown error".to_string());
+
+    let result = if err_str.contains("insufficient") || err_str.contains("funds") {
+        SimulationResult::InsufficientFunds
+    } else if err_str.contains("slippage") {
+        SimulationResult::SlippageExceeded
+    } else if err_str.contains("invalid") || err_str.contains("token") {
+        SimulationResult::InvalidToken
+    } else {
+        SimulationResult::Failure
+    };
+
+    (result, Some(err_str))
+}
+
+/// Base fee + a rough priority-fee allowance, scaled by compute units. This
+/// is a placeholder estimate shared by both backends; `PriorityFeeService`
+/// is the source of truth for an actual fee to attach to a transaction.
+fn estimate_fee_lamports(units: Option<u64>) -> Option<u64> {
+    units.map(|units| 5000 + (units / 1000) * 1000)
+}
+
+/// Run `tx` against an in-process `Bank` seeded with `accounts` instead of a
+/// live RPC. This mirrors what `solana-banks-server`/`simulateTransaction`
+/// do under the hood - load the named accounts into a bank, sanitize and
+/// execute the transaction, and read compute units/logs/error off the
+/// execution result - but skips the network hop entirely, so it's suitable
+/// for batch pre-flight of many candidate routes or for tests pinned to a
+/// known account snapshot.
+fn simulate_via_local_bank(tx: &Transaction, accounts: &[(Pubkey, Account)]) -> Result<SimulationReport> {
+    use solana_runtime::{bank::Bank, genesis_utils::create_genesis_config};
+    use solana_sdk::transaction::SanitizedTransaction;
+
+    // A throwaway genesis config just gives us a bank to load accounts
+    // into; none of its economic parameters matter for simulation.
+    let genesis = create_genesis_config(u64::MAX / 2);
+    let bank = Bank::new_for_tests(&genesis.genesis_config);
+
+    for (pubkey, account) in accounts {
+        bank.store_account(pubkey, account);
+    }
+
+    let sanitized = SanitizedTransaction::try_from_legacy_transaction(tx.clone())
+        .map_err(|e| anyhow::anyhow!("Failed to sanitize transaction for local simulation: {}", e))?;
+
+    let sim = bank.simulate_transaction(&sanitized);
+
+    let will_succeed = sim.result.is_ok();
+    let err_str = sim.result.err().map(|e| format!("{:?}", e));
+    let (result, error) = classify_result(will_succeed, err_str);
+
+    let estimated_compute_units = Some(sim.units_consumed);
+    let estimated_fee_lamports = estimate_fee_lamports(estimated_compute_units);
+
+    Ok(SimulationReport {
+        result,
+        error,
+        estimated_compute_units,
+        estimated_fee_lamports,
+        logs: sim.logs,
+        will_succeed,
+    })
+}
@@ -0,0 +1,385 @@
+/// Upstream Guard
+///
+/// Shared outbound rate-limiting + response caching for upstreams that
+/// throttle aggressively (Pump.fun's Cloudflare front door in particular).
+/// A token-bucket limiter, keyed by upstream host, makes callers `await` a
+/// permit before making a request; a TTL cache collapses bursts of client
+/// requests into a single upstream fetch. Both are backed by Redis when
+/// configured (so multiple server instances share one budget/cache) and fall
+/// back to in-process state otherwise.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::telemetry::Metrics;
+
+/// Token-bucket parameters. One bucket per rate-limited host.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub capacity: f64,
+    pub refill_per_sec: f64,
+}
+
+impl RateLimit {
+    pub const fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+        }
+    }
+}
+
+/// Default limit applied to the Pump.fun upstream: a small burst allowance
+/// that refills slowly enough to stay under Cloudflare's throttling.
+pub const PUMP_FUN_RATE_LIMIT: RateLimit = RateLimit::new(5.0, 1.0);
+
+/// Default cache TTL for Pump.fun coin listings.
+pub const PUMP_FUN_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// `User-Agent` sent when a guard isn't given one via `with_user_agent`.
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+struct CacheEntry {
+    body: String,
+    fetched_at: Instant,
+}
+
+/// Shared rate-limit + cache guard in front of a throttling-prone upstream.
+pub struct UpstreamGuard {
+    limit: RateLimit,
+    redis: Option<redis::aio::ConnectionManager>,
+    local_buckets: Mutex<HashMap<String, TokenBucketState>>,
+    local_cache: Mutex<HashMap<String, CacheEntry>>,
+    http: reqwest::Client,
+    /// Fetch-attempt/stale-fallback counters for `/metrics`. `None` when
+    /// constructed without `with_metrics`, e.g. in tests.
+    metrics: Option<Arc<Metrics>>,
+    /// `User-Agent` header sent to the upstream. Defaults to
+    /// `DEFAULT_USER_AGENT`; override with `with_user_agent` to target a
+    /// staging mirror with a different fingerprint requirement.
+    user_agent: String,
+}
+
+impl UpstreamGuard {
+    /// Build a guard. `redis_url` is optional - when unset (or when Redis is
+    /// unreachable at startup) this falls back to purely in-process limiting
+    /// and caching, which is still correct for a single instance.
+    pub async fn new(redis_url: Option<&str>, limit: RateLimit) -> Self {
+        let redis = match redis_url {
+            Some(url) => match redis::Client::open(url) {
+                Ok(client) => match redis::aio::ConnectionManager::new(client).await {
+                    Ok(manager) => Some(manager),
+                    Err(e) => {
+                        warn!("UpstreamGuard: failed to connect to Redis ({}), falling back to in-process limiting", e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    warn!("UpstreamGuard: invalid Redis URL ({}), falling back to in-process limiting", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Self {
+            limit,
+            redis,
+            local_buckets: Mutex::new(HashMap::new()),
+            local_cache: Mutex::new(HashMap::new()),
+            http: reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            metrics: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+
+    /// Record fetch-attempt/stale-fallback counts into the shared
+    /// `/metrics` endpoint.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Override the `User-Agent` header sent to the upstream.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Wait until a permit for `key` (typically the upstream host) is
+    /// available, consuming one token.
+    pub async fn throttle(&self, key: &str) {
+        loop {
+            let wait = if let Some(redis) = &self.redis {
+                self.throttle_redis(redis.clone(), key).await
+            } else {
+                self.throttle_local(key).await
+            };
+
+            match wait {
+                Some(duration) if !duration.is_zero() => tokio::time::sleep(duration).await,
+                _ => return,
+            }
+        }
+    }
+
+    /// Returns `None` when a token was consumed immediately, or
+    /// `Some(duration)` to wait before retrying.
+    async fn throttle_local(&self, key: &str) -> Option<Duration> {
+        let mut buckets = self.local_buckets.lock().await;
+        let now = Instant::now();
+        let state = buckets.entry(key.to_string()).or_insert(TokenBucketState {
+            tokens: self.limit.capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.limit.refill_per_sec).min(self.limit.capacity);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            let deficit = 1.0 - state.tokens;
+            Some(Duration::from_secs_f64(deficit / self.limit.refill_per_sec))
+        }
+    }
+
+    async fn throttle_redis(&self, mut redis: redis::aio::ConnectionManager, key: &str) -> Option<Duration> {
+        use redis::AsyncCommands;
+
+        let bucket_key = format!("upstream_guard:bucket:{}", key);
+        let now = now_unix_secs();
+
+        let raw: Option<String> = redis.get(&bucket_key).await.ok().flatten();
+        let (mut tokens, last_refill) = match raw.and_then(|s| parse_bucket(&s)) {
+            Some(parsed) => parsed,
+            None => (self.limit.capacity, now),
+        };
+
+        let elapsed = (now - last_refill).max(0.0);
+        tokens = (tokens + elapsed * self.limit.refill_per_sec).min(self.limit.capacity);
+
+        if tokens >= 1.0 {
+            tokens -= 1.0;
+            let value = format!("{}:{}", tokens, now);
+            let _: Result<(), _> = redis.set_ex(&bucket_key, value, 3600).await;
+            None
+        } else {
+            let deficit = 1.0 - tokens;
+            let value = format!("{}:{}", tokens, now);
+            let _: Result<(), _> = redis.set_ex(&bucket_key, value, 3600).await;
+            Some(Duration::from_secs_f64(deficit / self.limit.refill_per_sec))
+        }
+    }
+
+    /// Fetch `url` as JSON, serving a cached response when one is fresh
+    /// enough, and falling back to the last good snapshot (rather than
+    /// propagating an upstream error) when the limiter is exhausted or the
+    /// upstream itself errors.
+    pub async fn cached_get<T: DeserializeOwned + Serialize>(&self, url: &str, ttl: Duration) -> Result<T> {
+        let body = self.cached_get_raw(url, ttl).await?;
+        serde_json::from_str(&body).map_err(|e| anyhow!("Failed to decode response: {}", e))
+    }
+
+    /// Same fetch/cache/stale-fallback behavior as `cached_get`, but returns
+    /// the raw response body instead of decoding it - for callers that need
+    /// tolerant parsing of a shape that isn't a single well-known type (e.g.
+    /// the Pump.fun proxy's array-or-`{coins:[...]}` fallback).
+    pub async fn cached_get_raw(&self, url: &str, ttl: Duration) -> Result<String> {
+        if let Some(cached) = self.read_cache(url, ttl).await {
+            return Ok(cached);
+        }
+
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_else(|| url.to_string());
+
+        // An exhausted limiter serves the last good snapshot when there is
+        // one, and only waits for a permit when there isn't.
+        let wait = match &self.redis {
+            Some(redis) => self.throttle_redis(redis.clone(), &host).await,
+            None => self.throttle_local(&host).await,
+        };
+        if wait.is_some_and(|duration| !duration.is_zero()) {
+            if let Some(stale) = self.read_cache(url, Duration::MAX).await {
+                if let Some(metrics) = &self.metrics {
+                    metrics.increment("upstream_stale_fallback_total", &host, 1).await;
+                }
+                return Ok(stale);
+            }
+            self.throttle(&host).await;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.increment("upstream_fetch_attempts_total", &host, 1).await;
+        }
+
+        match self.fetch_and_cache(url).await {
+            Ok(body) => Ok(body),
+            Err(e) => {
+                warn!("cached_get: upstream fetch for {} failed ({}), checking for stale snapshot", url, e);
+                if let Some(metrics) = &self.metrics {
+                    metrics.increment("upstream_fetch_failures_total", &host, 1).await;
+                }
+                match self.read_cache(url, Duration::MAX).await {
+                    Some(stale) => {
+                        if let Some(metrics) = &self.metrics {
+                            metrics.increment("upstream_stale_fallback_total", &host, 1).await;
+                        }
+                        Ok(stale)
+                    }
+                    None => Err(e),
+                }
+            }
+        }
+    }
+
+    async fn read_cache(&self, url: &str, ttl: Duration) -> Option<String> {
+        if let Some(redis) = &self.redis {
+            return self.read_cache_redis(redis.clone(), url, ttl).await;
+        }
+
+        let cache = self.local_cache.lock().await;
+        cache.get(url).and_then(|entry| {
+            if entry.fetched_at.elapsed() <= ttl {
+                Some(entry.body.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    async fn read_cache_redis(&self, mut redis: redis::aio::ConnectionManager, url: &str, ttl: Duration) -> Option<String> {
+        use redis::AsyncCommands;
+
+        let cache_key = format!("upstream_guard:cache:{}", url);
+        let fetched_at_key = format!("upstream_guard:cache_ts:{}", url);
+
+        let body: Option<String> = redis.get(&cache_key).await.ok().flatten();
+        let fetched_at: Option<f64> = redis
+            .get::<_, Option<String>>(&fetched_at_key)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|s| s.parse().ok());
+
+        match (body, fetched_at) {
+            (Some(body), Some(fetched_at)) if ttl == Duration::MAX || now_unix_secs() - fetched_at <= ttl.as_secs_f64() => {
+                Some(body)
+            }
+            _ => None,
+        }
+    }
+
+    async fn fetch_and_cache(&self, url: &str) -> Result<String> {
+        let response = self
+            .http
+            .get(url)
+            .header("User-Agent", &self.user_agent)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            if status.is_server_error() {
+                if let Some(metrics) = &self.metrics {
+                    let host = reqwest::Url::parse(url)
+                        .ok()
+                        .and_then(|u| u.host_str().map(|h| h.to_string()))
+                        .unwrap_or_else(|| url.to_string());
+                    metrics.increment("upstream_5xx_total", &host, 1).await;
+                }
+            }
+            return Err(anyhow!("Upstream error: {}", status));
+        }
+
+        let body = response.text().await?;
+        self.write_cache(url, &body).await;
+        Ok(body)
+    }
+
+    async fn write_cache(&self, url: &str, body: &str) {
+        if let Some(redis) = &self.redis {
+            use redis::AsyncCommands;
+            let mut redis = redis.clone();
+            let cache_key = format!("upstream_guard:cache:{}", url);
+            let fetched_at_key = format!("upstream_guard:cache_ts:{}", url);
+            // Snapshots are kept far past their TTL so a later stale-fallback
+            // read can still find them.
+            let _: Result<(), _> = redis.set_ex(&cache_key, body.to_string(), 86_400).await;
+            let _: Result<(), _> = redis
+                .set_ex(&fetched_at_key, now_unix_secs().to_string(), 86_400)
+                .await;
+            return;
+        }
+
+        let mut cache = self.local_cache.lock().await;
+        cache.insert(
+            url.to_string(),
+            CacheEntry {
+                body: body.to_string(),
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+}
+
+fn now_unix_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn parse_bucket(raw: &str) -> Option<(f64, f64)> {
+    let (tokens, last_refill) = raw.split_once(':')?;
+    Some((tokens.parse().ok()?, last_refill.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_bucket_exhausts_then_refills() {
+        let guard = UpstreamGuard::new(None, RateLimit::new(1.0, 100.0)).await;
+
+        assert!(guard.throttle_local("host").await.is_none());
+        assert!(guard.throttle_local("host").await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(guard.throttle_local("host").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_cache_round_trip() {
+        let guard = UpstreamGuard::new(None, PUMP_FUN_RATE_LIMIT).await;
+        guard.write_cache("https://example.com/x", "{\"a\":1}").await;
+
+        let cached = guard.read_cache("https://example.com/x", Duration::from_secs(60)).await;
+        assert_eq!(cached.as_deref(), Some("{\"a\":1}"));
+
+        let expired = guard.read_cache("https://example.com/x", Duration::from_secs(0)).await;
+        assert!(expired.is_none());
+    }
+}
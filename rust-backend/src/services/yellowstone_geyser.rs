@@ -1,15 +1,62 @@
-use crate::rpc::RpcManager;
+/// Yellowstone gRPC geyser feed.
+///
+/// Connects to a Yellowstone-compatible geyser endpoint and watches a
+/// dynamic set of accounts (AMM pools, a user's token accounts, ...),
+/// relaying decoded updates onto a broadcast channel that `/ws/trading`
+/// fans out to `accounts`-channel subscribers. This cuts update latency
+/// from RPC-poll intervals (see `RpcManager::get_account_data`) down to
+/// whatever the geyser plugin itself emits.
+///
+/// The upstream gRPC dial/subscribe call is still a placeholder here - see
+/// the `TODO`s in `stream_until_failure` - this module owns the watched-set
+/// bookkeeping, the decoded update types, and the reconnect-with-resubscribe
+/// loop the rest of the server depends on.
+///
+/// [`TransactionSubscriber`] is the sibling feed: instead of accounts, it
+/// filters to Pump.fun/Raydium program ids and feeds `MigrationDetector`,
+/// with slot-aware backfill through `RpcManager` so a disconnect can't drop
+/// a migration transaction.
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{info, warn};
 
-// Placeholder for Yellowstone Geyser proto types
-// In production, these would be generated from proto files
-pub struct YellowstoneSubscriber {
-    rpc: Arc<RpcManager>,
-    tx_sender: mpsc::Sender<TransactionUpdate>,
+use crate::config::GeyserConfig;
+use crate::rpc::RpcManager;
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// How often an active subscription checks whether the watched-account set
+/// changed underneath it and needs to be resubscribed.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Program ids the transaction-update stream filters to - the Pump.fun
+/// bonding curve and the Raydium AMM, the two sides `MigrationDetector` looks
+/// for in the same transaction.
+pub const PUMP_FUN_PROGRAM_ID: &str = "6EF8rrecthR5D2zonDnV5AP2k4H2F4V1Du8jQ6Cv3B1";
+pub const RAYDIUM_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+/// Bounded so a slow `MigrationDetector` consumer applies backpressure to the
+/// subscriber loop (the `send` awaits) rather than the server buffering an
+/// unbounded backlog of undelivered updates in memory.
+pub const TRANSACTION_CHANNEL_CAPACITY: usize = 256;
+
+/// A decoded account update pushed by the geyser stream.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AccountUpdate {
+    pub pubkey: String,
+    pub slot: u64,
+    pub lamports: u64,
+    pub owner: String,
+    pub data_len: usize,
 }
 
+/// A decoded transaction update pushed by the geyser stream, e.g. for
+/// `MigrationDetector` to scan for Pump.fun -> Raydium migrations without
+/// polling.
 #[derive(Debug, Clone)]
 pub struct TransactionUpdate {
     pub signature: String,
@@ -18,48 +65,243 @@ pub struct TransactionUpdate {
     pub logs: Vec<String>,
 }
 
-impl YellowstoneSubscriber {
-    pub fn new(rpc: Arc<RpcManager>, tx_sender: mpsc::Sender<TransactionUpdate>) -> Self {
-        YellowstoneSubscriber { rpc, tx_sender }
+/// Accounts the server currently wants updates for, shared between
+/// `/ws/trading` subscribers (who add to it as clients subscribe) and the
+/// geyser loop (which reads it to issue/refresh its upstream subscription).
+pub type WatchedAccounts = Arc<RwLock<HashSet<String>>>;
+
+pub struct GeyserSubscriber {
+    config: GeyserConfig,
+    watched: WatchedAccounts,
+    tx: broadcast::Sender<AccountUpdate>,
+}
+
+impl GeyserSubscriber {
+    pub fn new(config: GeyserConfig, watched: WatchedAccounts, tx: broadcast::Sender<AccountUpdate>) -> Self {
+        GeyserSubscriber { config, watched, tx }
     }
 
-    pub async fn subscribe_to_transactions(&mut self) -> Result<()> {
-        // In production, this would:
-        // 1. Connect to Yellowstone Geyser gRPC endpoint
-        // 2. Subscribe to Pump.fun program (6EF8rrecthR5D2zonDnV5AP2k4H2F4V1Du8jQ6Cv3B1)
-        // 3. Subscribe to Raydium program (675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8)
-        // 4. Process transactions in <100ms
-        // 5. Send updates via channel
-
-        // For now, this is a placeholder
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                // In production, receive from gRPC stream and send to channel
+    /// Drive the subscribe/reconnect loop forever. Intended to be spawned
+    /// once at startup; failures are logged and retried with backoff rather
+    /// than propagated, since a dead geyser feed shouldn't take the rest of
+    /// the server down with it.
+    pub async fn run(self) {
+        if self.config.endpoint.is_none() {
+            warn!("GEYSER_GRPC_URL not set, account-update stream disabled");
+            return;
+        }
+
+        let mut delay = INITIAL_RECONNECT_DELAY;
+
+        loop {
+            match self.stream_until_failure().await {
+                Ok(()) => {
+                    // Either the watched set changed (resubscribe) or there
+                    // was nothing to watch yet - neither is a failure.
+                    delay = INITIAL_RECONNECT_DELAY;
+                }
+                Err(e) => {
+                    warn!("Geyser stream failed, reconnecting in {:?}: {}", delay, e);
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
             }
-        });
+        }
+    }
 
-        Ok(())
+    /// Connect, subscribe to the current watched-account set, and forward
+    /// updates until the stream errors, closes, or the watched set changes
+    /// underneath it. Resubscribing on every (re)connect means a client that
+    /// changed its accounts mid-outage still gets the right set once the
+    /// stream comes back.
+    async fn stream_until_failure(&self) -> Result<()> {
+        let accounts: HashSet<String> = self.watched.read().await.clone();
+
+        if accounts.is_empty() {
+            // Nothing to watch yet - avoid hot-looping a reconnect against
+            // an empty subscription; wait for a subscriber to register interest.
+            tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+            return Ok(());
+        }
+
+        info!("Subscribing to geyser account updates for {} account(s)", accounts.len());
+
+        // TODO: replace with the generated Yellowstone geyser gRPC client
+        // (tonic), dialing `self.config.endpoint` with the `x-token`
+        // metadata header and issuing a SubscribeRequest scoped to
+        // `accounts`, then forwarding each decoded update through `self.tx`.
+        let _ = &self.tx;
+
+        loop {
+            tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+            let current = self.watched.read().await.clone();
+            if current != accounts {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Geyser transaction-update feed for `MigrationDetector`: subscribes to
+/// transactions touching [`PUMP_FUN_PROGRAM_ID`] / [`RAYDIUM_PROGRAM_ID`] and
+/// forwards them over a bounded channel. Unlike [`GeyserSubscriber`]'s
+/// account feed, a dropped transaction here means a missed migration, so this
+/// tracks the last processed slot and backfills any gap through
+/// [`RpcManager`] on reconnect (and periodically while connected, in case the
+/// stream itself silently skips a slot).
+pub struct TransactionSubscriber {
+    config: GeyserConfig,
+    program_ids: Vec<String>,
+    tx: mpsc::Sender<TransactionUpdate>,
+    rpc: Arc<RpcManager>,
+    last_slot: RwLock<Option<u64>>,
+    /// Mirrors Yellowstone's `account_required` filter field: when set, a
+    /// transaction must touch at least one of these accounts *in addition*
+    /// to one of `program_ids`, not just one or the other. Lets a consumer
+    /// scope the same program-level subscription down to a specific mint
+    /// (e.g. `SandwichMempoolFeed`) without standing up a second stream.
+    required_accounts: Option<WatchedAccounts>,
+}
+
+impl TransactionSubscriber {
+    pub fn new(
+        config: GeyserConfig,
+        program_ids: Vec<String>,
+        tx: mpsc::Sender<TransactionUpdate>,
+        rpc: Arc<RpcManager>,
+    ) -> Self {
+        TransactionSubscriber {
+            config,
+            program_ids,
+            tx,
+            rpc,
+            last_slot: RwLock::new(None),
+            required_accounts: None,
+        }
+    }
+
+    /// Additionally require the transaction to touch one of `required`,
+    /// analogous to Yellowstone's `account_required` filter field, so a
+    /// caller only interested in one mint isn't handed every transaction
+    /// that merely touches the AMM program. `matches_filter` applies this
+    /// in-process to each update before it is forwarded; it is not sent to
+    /// the geyser endpoint. An empty set imposes no requirement.
+    pub fn with_required_accounts(mut self, required: WatchedAccounts) -> Self {
+        self.required_accounts = Some(required);
+        self
     }
 
-    pub async fn process_transaction(&self, update: TransactionUpdate) -> Result<()> {
-        // Detect Pump.fun migration to Raydium
-        let has_pump_fun = update
-            .accounts
-            .iter()
-            .any(|acc| acc == "6EF8rrecthR5D2zonDnV5AP2k4H2F4V1Du8jQ6Cv3B1");
-
-        let has_raydium = update
-            .accounts
-            .iter()
-            .any(|acc| acc == "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
-
-        if has_pump_fun && has_raydium {
-            // Migration detected!
-            // Publish to Redis for Pulse categorizer
-            // This would be implemented with Redis client
+    /// Drive the subscribe/reconnect loop forever. Like
+    /// `GeyserSubscriber::run`, failures are logged and retried with backoff
+    /// rather than propagated.
+    pub async fn run(self) {
+        if self.config.endpoint.is_none() {
+            warn!("GEYSER_GRPC_URL not set, transaction-update stream disabled");
+            return;
         }
 
+        let mut delay = INITIAL_RECONNECT_DELAY;
+
+        loop {
+            match self.stream_until_failure().await {
+                Ok(()) => delay = INITIAL_RECONNECT_DELAY,
+                Err(e) => {
+                    warn!("Transaction geyser stream failed, reconnecting in {:?}: {}", delay, e);
+                    if let Err(e) = self.backfill_gap().await {
+                        warn!("Failed to backfill missed slots after disconnect: {}", e);
+                    }
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Connect and forward transaction updates until the stream errors or
+    /// closes. While connected, also runs the periodic resync so a stream
+    /// that silently drops a slot (rather than disconnecting outright) still
+    /// gets backfilled.
+    async fn stream_until_failure(&self) -> Result<()> {
+        info!(
+            "Subscribing to geyser transaction updates for {} program id(s)",
+            self.program_ids.len()
+        );
+
+        // TODO: replace with the generated Yellowstone geyser gRPC client
+        // (tonic), dialing `self.config.endpoint` with the `x-token`
+        // metadata header and issuing a SubscribeRequest with a
+        // `transactions` filter scoped to `self.program_ids`, decoding each
+        // `SubscribeUpdateTransaction` into a `TransactionUpdate`, updating
+        // `self.last_slot`, and forwarding it through `self.tx` (awaiting the
+        // bounded send so a slow `MigrationDetector` backpressures the
+        // stream rather than this loop buffering unboundedly).
+        let _ = &self.tx;
+
+        let resync_interval = Duration::from_secs(self.config.resync_interval_secs.max(1));
+        let mut ticker = tokio::time::interval(resync_interval);
+        ticker.tick().await; // first tick fires immediately
+
+        loop {
+            ticker.tick().await;
+            self.backfill_gap().await?;
+        }
+    }
+
+    /// Replay slots between the last slot this subscriber has seen and the
+    /// current tip through `RpcManager`, forwarding any matching transaction
+    /// the same way the live stream would. A `None` last slot (startup, or
+    /// just after a reconnect with nothing processed yet) only records the
+    /// current tip rather than replaying the whole chain.
+    async fn backfill_gap(&self) -> Result<()> {
+        let current_slot = self.rpc.get_slot().await?;
+
+        let from_slot = {
+            let last = *self.last_slot.read().await;
+            match last {
+                Some(slot) if slot < current_slot => slot + 1,
+                Some(_) => return Ok(()),
+                None => {
+                    *self.last_slot.write().await = Some(current_slot);
+                    return Ok(());
+                }
+            }
+        };
+
+        for slot in from_slot..=current_slot {
+            let updates = match self.rpc.get_block_transactions(slot).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    warn!("Backfill: failed to fetch block for slot {}: {}", slot, e);
+                    continue;
+                }
+            };
+
+            for update in updates {
+                if !self.matches_filter(&update).await {
+                    continue;
+                }
+                if self.tx.send(update).await.is_err() {
+                    // Receiver gone - nothing left to backfill for.
+                    return Ok(());
+                }
+            }
+        }
+
+        *self.last_slot.write().await = Some(current_slot);
         Ok(())
     }
+
+    async fn matches_filter(&self, update: &TransactionUpdate) -> bool {
+        if !self.program_ids.iter().any(|id| update.accounts.contains(id)) {
+            return false;
+        }
+
+        match &self.required_accounts {
+            None => true,
+            Some(required) => {
+                let required = required.read().await;
+                required.is_empty() || required.iter().any(|acc| update.accounts.contains(acc))
+            }
+        }
+    }
 }
@@ -0,0 +1,241 @@
+/// Prometheus-Style Submission/Quote Metrics
+///
+/// `BloxrouteClient`, `JitoBundleClient`, `JupiterClient`, and
+/// `RpcConnectionPool` each had no cross-cutting observability - an operator
+/// could see `JitoBundleClient`'s own per-region latency (`/jito/latency`)
+/// but nothing for bloXroute submission, Jupiter quotes, or pool contention,
+/// and nothing in a format a scraper understands. This centralizes fixed
+/// bucket latency histograms and success/failure counters, keyed by
+/// `(operation, provider)`, and renders them in the Prometheus text
+/// exposition format behind `/metrics`. Modeled on the histogram +
+/// benchrunner telemetry approach used in lite-rpc.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::RwLock;
+
+/// Bucket upper bounds in milliseconds - Prometheus's "le" (less-or-equal)
+/// histogram buckets, plus an implicit `+Inf` bucket for everything above
+/// the last boundary.
+const LATENCY_BUCKETS_MS: [f64; 9] = [5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0];
+
+/// Cumulative (Prometheus-style) fixed-bucket histogram: `bucket_counts[i]`
+/// holds the number of observations `<= LATENCY_BUCKETS_MS[i]`, so rendering
+/// just walks the array rather than needing a running-total pass.
+#[derive(Debug, Default)]
+struct Histogram {
+    bucket_counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// Milliseconds, rounded - exact enough for a `_sum` series used only to
+    /// derive averages, not for billing.
+    sum_ms: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            bucket_counts: (0..LATENCY_BUCKETS_MS.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, latency_ms: f64) {
+        for (i, &boundary) in LATENCY_BUCKETS_MS.iter().enumerate() {
+            if latency_ms <= boundary {
+                self.bucket_counts[i].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_ms.fetch_add(latency_ms.round() as u64, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    successes: AtomicU64,
+    failures: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct OperationMetrics {
+    latency: Histogram,
+    requests: Counters,
+}
+
+/// Shared metrics registry, held as `Arc<Metrics>` in `AppState` and handed
+/// to every client that submits or quotes on a private route.
+#[derive(Default)]
+pub struct Metrics {
+    by_operation_provider: RwLock<HashMap<(String, String), OperationMetrics>>,
+    /// Monotonic event counts keyed by `(metric_name, label)`, for events
+    /// that aren't a latency/success observation - e.g. Pump.fun fetch
+    /// attempts, retries, or Cloudflare fallbacks.
+    counters: RwLock<HashMap<(String, String), AtomicU64>>,
+    /// Point-in-time values keyed by `(metric_name, label)` - e.g. active
+    /// SSE connections or coins returned by the last Pump.fun fetch.
+    gauges: RwLock<HashMap<(String, String), AtomicU64>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one call's latency and outcome, e.g.
+    /// `observe("submit_bundle", "jito", 42.0, true)`.
+    pub async fn observe(&self, operation: &str, provider: &str, latency_ms: f64, success: bool) {
+        let mut map = self.by_operation_provider.write().await;
+        let entry = map
+            .entry((operation.to_string(), provider.to_string()))
+            .or_insert_with(|| OperationMetrics {
+                latency: Histogram::new(),
+                requests: Counters::default(),
+            });
+
+        entry.latency.record(latency_ms);
+        if success {
+            entry.requests.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            entry.requests.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Increment a named counter, e.g.
+    /// `increment("pump_fun_fetch_attempts_total", "frontend-api.pump.fun", 1)`.
+    pub async fn increment(&self, name: &str, label: &str, amount: u64) {
+        let map = self.counters.read().await;
+        if let Some(counter) = map.get(&(name.to_string(), label.to_string())) {
+            counter.fetch_add(amount, Ordering::Relaxed);
+            return;
+        }
+        drop(map);
+
+        let mut map = self.counters.write().await;
+        map.entry((name.to_string(), label.to_string()))
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Set a named gauge to an absolute value, e.g.
+    /// `set_gauge("pump_fun_coins_returned", "total", 50)`.
+    pub async fn set_gauge(&self, name: &str, label: &str, value: u64) {
+        let map = self.gauges.read().await;
+        if let Some(gauge) = map.get(&(name.to_string(), label.to_string())) {
+            gauge.store(value, Ordering::Relaxed);
+            return;
+        }
+        drop(map);
+
+        let mut map = self.gauges.write().await;
+        map.entry((name.to_string(), label.to_string()))
+            .or_insert_with(|| AtomicU64::new(0))
+            .store(value, Ordering::Relaxed);
+    }
+
+    /// Adjust a named gauge by a signed delta, e.g. `+1`/`-1` for active SSE
+    /// connections opening/closing.
+    pub async fn add_gauge(&self, name: &str, label: &str, delta: i64) {
+        let map = self.gauges.read().await;
+        if let Some(gauge) = map.get(&(name.to_string(), label.to_string())) {
+            apply_gauge_delta(gauge, delta);
+            return;
+        }
+        drop(map);
+
+        let mut map = self.gauges.write().await;
+        let gauge = map
+            .entry((name.to_string(), label.to_string()))
+            .or_insert_with(|| AtomicU64::new(0));
+        apply_gauge_delta(gauge, delta);
+    }
+
+    /// Render every recorded series in the Prometheus text exposition
+    /// format (https://prometheus.io/docs/instrumenting/exposition_formats/).
+    pub async fn render_prometheus(&self) -> String {
+        let map = self.by_operation_provider.read().await;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP submission_latency_ms Submission/quote latency in milliseconds.");
+        let _ = writeln!(out, "# TYPE submission_latency_ms histogram");
+        for ((operation, provider), metrics) in map.iter() {
+            let labels = format!("operation=\"{}\",provider=\"{}\"", operation, provider);
+            for (i, boundary) in LATENCY_BUCKETS_MS.iter().enumerate() {
+                let cumulative = metrics.latency.bucket_counts[i].load(Ordering::Relaxed);
+                let _ = writeln!(
+                    out,
+                    "submission_latency_ms_bucket{{{},le=\"{}\"}} {}",
+                    labels, boundary, cumulative
+                );
+            }
+            let total = metrics.latency.count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "submission_latency_ms_bucket{{{},le=\"+Inf\"}} {}", labels, total);
+            let _ = writeln!(
+                out,
+                "submission_latency_ms_sum{{{}}} {}",
+                labels,
+                metrics.latency.sum_ms.load(Ordering::Relaxed)
+            );
+            let _ = writeln!(out, "submission_latency_ms_count{{{}}} {}", labels, total);
+        }
+
+        let _ = writeln!(out, "# HELP submission_requests_total Submission/quote attempts by outcome.");
+        let _ = writeln!(out, "# TYPE submission_requests_total counter");
+        for ((operation, provider), metrics) in map.iter() {
+            let labels = format!("operation=\"{}\",provider=\"{}\"", operation, provider);
+            let _ = writeln!(
+                out,
+                "submission_requests_total{{{},outcome=\"success\"}} {}",
+                labels,
+                metrics.requests.successes.load(Ordering::Relaxed)
+            );
+            let _ = writeln!(
+                out,
+                "submission_requests_total{{{},outcome=\"failure\"}} {}",
+                labels,
+                metrics.requests.failures.load(Ordering::Relaxed)
+            );
+        }
+        drop(map);
+
+        let counters = self.counters.read().await;
+        let mut counter_names: Vec<&String> = counters.keys().map(|(name, _)| name).collect();
+        counter_names.sort_unstable();
+        counter_names.dedup();
+        for name in counter_names {
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for ((metric_name, label), count) in counters.iter() {
+                if metric_name == name {
+                    let _ = writeln!(out, "{}{{label=\"{}\"}} {}", name, label, count.load(Ordering::Relaxed));
+                }
+            }
+        }
+        drop(counters);
+
+        let gauges = self.gauges.read().await;
+        let mut gauge_names: Vec<&String> = gauges.keys().map(|(name, _)| name).collect();
+        gauge_names.sort_unstable();
+        gauge_names.dedup();
+        for name in gauge_names {
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            for ((metric_name, label), value) in gauges.iter() {
+                if metric_name == name {
+                    let _ = writeln!(out, "{}{{label=\"{}\"}} {}", name, label, value.load(Ordering::Relaxed));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Apply a signed delta to an atomic gauge stored as `u64`, saturating at
+/// zero rather than wrapping when a decrement would go negative.
+fn apply_gauge_delta(gauge: &AtomicU64, delta: i64) {
+    gauge
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_add_signed(delta))
+        })
+        .ok();
+}
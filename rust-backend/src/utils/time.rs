@@ -1,3 +1,24 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Wall-clock interval `PrecisionTimer::calibrate` sleeps to measure
+/// `_rdtsc`'s cycle rate against `Instant`.
+const CALIBRATION_SLEEP: Duration = Duration::from_millis(100);
+
+/// `CYCLES_PER_NS_FIXED` is cycles-per-nanosecond scaled by this factor so
+/// it fits an `AtomicU64` - storing the raw `f64` bits would need a CAS
+/// loop to update atomically from `calibrate`, this is a plain store/load.
+const FREQUENCY_SCALE: f64 = 1_000_000.0;
+
+/// Cycles-per-nanosecond, fixed-point. Defaults to a 3.5 GHz guess until
+/// `PrecisionTimer::calibrate` runs at startup.
+static CYCLES_PER_NS_FIXED: AtomicU64 = AtomicU64::new(3_500_000);
+
+/// Whether `now()` should read `_rdtsc` at all - false once `calibrate`
+/// finds a non-invariant TSC (or the calibration measurement itself looks
+/// unreliable), at which point everything falls back to wall-clock time.
+static USE_RDTSC: AtomicBool = AtomicBool::new(true);
+
 pub struct PrecisionTimer;
 
 impl PrecisionTimer {
@@ -5,29 +26,89 @@ impl PrecisionTimer {
     pub fn now() -> u64 {
         #[cfg(target_arch = "x86_64")]
         {
-            unsafe { std::arch::x86_64::_rdtsc() }
+            if USE_RDTSC.load(Ordering::Relaxed) {
+                return unsafe { std::arch::x86_64::_rdtsc() };
+            }
         }
-        #[cfg(not(target_arch = "x86_64"))]
+        Self::wall_clock_ns()
+    }
+
+    fn wall_clock_ns() -> u64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    }
+
+    /// Convert a `now()` reading to nanoseconds. When `now()` is backed by
+    /// `_rdtsc`, this applies the frequency `calibrate` measured; when it
+    /// fell back to wall-clock nanoseconds (non-x86_64, or a CPU without an
+    /// invariant TSC), a `now()` reading already *is* nanoseconds.
+    pub fn cycles_to_ns(cycles: u64) -> u64 {
+        #[cfg(target_arch = "x86_64")]
         {
-            // Fallback to std::time for non-x86_64 architectures
-            use std::time::{SystemTime, UNIX_EPOCH};
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_nanos() as u64
+            if USE_RDTSC.load(Ordering::Relaxed) {
+                let cycles_per_ns = CYCLES_PER_NS_FIXED.load(Ordering::Relaxed) as f64 / FREQUENCY_SCALE;
+                return (cycles as f64 / cycles_per_ns) as u64;
+            }
         }
+        cycles
     }
 
-    /// Convert rdtsc cycles to nanoseconds
-    /// Note: This requires calibration which is usually done at startup
-    pub fn cycles_to_ns(cycles: u64, frequency_ghz: f64) -> u64 {
-        (cycles as f64 / frequency_ghz) as u64
+    /// Calibrate the `_rdtsc` cycle rate against wall-clock time, and fall
+    /// back to wall-clock timing entirely if the TSC isn't invariant (or
+    /// the calibration measurement can't be trusted). Run once at startup,
+    /// before any latency derived from `now()`/`cycles_to_ns` is reported.
+    pub fn calibrate() {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if !Self::has_invariant_tsc() {
+                USE_RDTSC.store(false, Ordering::Relaxed);
+                return;
+            }
+
+            let start_cycles = unsafe { std::arch::x86_64::_rdtsc() };
+            let start_wall = Instant::now();
+            std::thread::sleep(CALIBRATION_SLEEP);
+            let end_cycles = unsafe { std::arch::x86_64::_rdtsc() };
+            let elapsed_ns = start_wall.elapsed().as_nanos() as f64;
+
+            if elapsed_ns <= 0.0 || end_cycles <= start_cycles {
+                USE_RDTSC.store(false, Ordering::Relaxed);
+                return;
+            }
+
+            let cycles_per_ns = (end_cycles - start_cycles) as f64 / elapsed_ns;
+            CYCLES_PER_NS_FIXED.store((cycles_per_ns * FREQUENCY_SCALE) as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the CPU reports an invariant TSC (CPUID leaf `0x8000_0007`,
+    /// EDX bit 8) - the counter ticks at a constant rate regardless of
+    /// P-state/C-state changes, which is what makes a one-time `calibrate`
+    /// valid for the life of the process instead of drifting under load.
+    #[cfg(target_arch = "x86_64")]
+    fn has_invariant_tsc() -> bool {
+        unsafe {
+            let leaf = std::arch::x86_64::__cpuid(0x8000_0007);
+            leaf.edx & (1 << 8) != 0
+        }
+    }
+
+    /// The calibrated cycles-per-nanosecond rate, in GHz, that
+    /// `cycles_to_ns` is currently applying - 1.0 when `now()` fell back to
+    /// wall-clock nanoseconds instead of `_rdtsc`.
+    pub fn calibrated_ghz() -> f64 {
+        if cfg!(target_arch = "x86_64") && USE_RDTSC.load(Ordering::Relaxed) {
+            CYCLES_PER_NS_FIXED.load(Ordering::Relaxed) as f64 / FREQUENCY_SCALE
+        } else {
+            1.0
+        }
     }
 }
 
+/// Calibrated CPU frequency in GHz - see `PrecisionTimer::calibrate`.
 pub fn get_cpu_frequency() -> f64 {
-    // In a real system, we would calibrate this at startup
-    // For now, returning a common value like 3.5 GHz
-    3.5
+    PrecisionTimer::calibrated_ghz()
 }
-